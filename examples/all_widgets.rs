@@ -344,7 +344,12 @@ fn draw_ui(gl: &mut Gl,
         .frame_color(ddl_color.plain_contrast())
         .label("Colors")
         .label_color(ddl_color.plain_contrast())
-        .callback(|selected_idx, new_idx, _string| *selected_idx = Some(new_idx))
+        .callback(|selected_idx, path, _item| *selected_idx = Some(path[0]))
+        .focusable(true)
+        .reorderable(|colors, from, to| {
+            let color = colors.remove(from).unwrap();
+            colors.insert(to, color);
+        })
         .draw(gl);
 
     // Draw an xy_pad.
@@ -414,6 +419,9 @@ fn draw_ui(gl: &mut Gl,
 
         }); // End of matrix widget callback.
 
+    // Draw any open drop down lists above everything else.
+    uic.draw_overlay(gl);
+
 }
 
 /// Draw a circle controlled by the XYPad.
@@ -0,0 +1,195 @@
+
+use color::Color;
+use dimensions::Dimensions;
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use widget::Widget::ABToggle;
+
+/// Represents the state of the ABToggle widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted,
+    /// The widget is pressed. Stores the time at which the press began.
+    Clicked(f64),
+    /// The widget has been held long enough to preview the other state.
+    Previewing,
+}
+
+impl State {
+    /// Return the associated Rectangle state.
+    fn as_rectangle_state(&self) -> rectangle::State {
+        match self {
+            &State::Normal => rectangle::State::Normal,
+            &State::Highlighted => rectangle::State::Highlighted,
+            &State::Clicked(_) | &State::Previewing => rectangle::State::Clicked,
+        }
+    }
+}
+
+widget_fns!(ABToggle, State, ABToggle(State::Normal));
+
+/// Check the current state of the ABToggle.
+fn get_new_state(is_over: bool,
+                 prev: State,
+                 mouse: Mouse,
+                 time: f64,
+                 hold_secs: f64) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::State::{Normal, Highlighted, Clicked, Previewing};
+    match (is_over, prev, mouse.left) {
+        (_,     Previewing,     Down) => Previewing,
+        (_,     Clicked(start), Down) => {
+            if time - start >= hold_secs { Previewing } else { Clicked(start) }
+        },
+        (true,  Normal,         Down) => Normal,
+        (true,  _,              Down) => Clicked(time),
+        (true,  _,              Up)   => Highlighted,
+        _                             => Normal,
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct ABToggleContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    value: bool,
+    labels: (&'a str, &'a str),
+    hold_secs: f64,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<|bool|:'a>,
+    maybe_preview_callback: Option<|bool|:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+}
+
+impl<'a> ABToggleContext<'a> {
+    /// The labels for the `A` (false) and `B` (true) states.
+    #[inline]
+    pub fn labels(self, a: &'a str, b: &'a str) -> ABToggleContext<'a> {
+        ABToggleContext { labels: (a, b), ..self }
+    }
+    /// The time in seconds the widget must be held before previewing the other state.
+    #[inline]
+    pub fn hold_duration(self, secs: f64) -> ABToggleContext<'a> {
+        ABToggleContext { hold_secs: secs, ..self }
+    }
+    /// Called with the previewed value once the preview begins and with the
+    /// original value once it is released.
+    #[inline]
+    pub fn preview_callback(self, callback: |bool|:'a) -> ABToggleContext<'a> {
+        ABToggleContext { maybe_preview_callback: Some(callback), ..self }
+    }
+    #[inline]
+    pub fn label_color(self, color: Color) -> ABToggleContext<'a> {
+        ABToggleContext { maybe_label_color: Some(color), ..self }
+    }
+    #[inline]
+    pub fn label_font_size(self, size: u32) -> ABToggleContext<'a> {
+        ABToggleContext { maybe_label_font_size: Some(size), ..self }
+    }
+}
+
+pub trait ABToggleBuilder<'a> {
+    /// An ab_toggle builder method to be implemented by the UiContext.
+    fn ab_toggle(&'a mut self, ui_id: UIID, value: bool) -> ABToggleContext<'a>;
+}
+
+impl<'a> ABToggleBuilder<'a> for UiContext {
+    /// Create an ab_toggle context to be built upon.
+    fn ab_toggle(&'a mut self, ui_id: UIID, value: bool) -> ABToggleContext<'a> {
+        let pos = self.flow_pos();
+        ABToggleContext {
+            uic: self,
+            ui_id: ui_id,
+            value: value,
+            labels: ("A", "B"),
+            hold_secs: 0.3,
+            pos: pos,
+            dim: [96.0, 48.0],
+            maybe_callback: None,
+            maybe_preview_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+        }
+    }
+}
+
+impl_callable!(ABToggleContext, |bool|:'a);
+impl_colorable!(ABToggleContext);
+impl_frameable!(ABToggleContext);
+impl_hoverable!(ABToggleContext);
+impl_positionable!(ABToggleContext);
+impl_shapeable!(ABToggleContext);
+
+impl<'a> ::draw::Drawable for ABToggleContext<'a> {
+    /// Draw the ab_toggle. A click calls the `callback` with the toggled value
+    /// while a press and hold calls the `preview_callback`.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let time = self.uic.frame_time();
+        let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
+        let new_state = get_new_state(is_over, state, mouse, time, self.hold_secs);
+
+        // Callbacks.
+        match (state, new_state) {
+            (State::Clicked(_), State::Highlighted) => match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(!self.value), None => (),
+            },
+            (State::Clicked(_), State::Previewing) => match self.maybe_preview_callback {
+                Some(ref mut callback) => (*callback)(!self.value), None => (),
+            },
+            (State::Previewing, State::Highlighted) | (State::Previewing, State::Normal) => {
+                match self.maybe_preview_callback {
+                    Some(ref mut callback) => (*callback)(self.value), None => (),
+                }
+            },
+            _ => (),
+        }
+
+        // Draw each half, with the active (or previewed) state pressed.
+        let shown = match new_state {
+            State::Previewing => !self.value,
+            _ => self.value,
+        };
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            false => None,
+        };
+        let text_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+        let size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_medium);
+        let half_dim = [self.dim[0] / 2.0, self.dim[1]];
+        let (a_text, b_text) = self.labels;
+        for &(is_b, text) in [(false, a_text), (true, b_text)].iter() {
+            let half_pos = if is_b { [self.pos[0] + half_dim[0], self.pos[1]] } else { self.pos };
+            let rect_state = if is_b == shown { rectangle::State::Clicked }
+                             else if rectangle::is_over(half_pos, mouse.pos, half_dim) {
+                                 new_state.as_rectangle_state()
+                             } else { rectangle::State::Normal };
+            rectangle::draw_with_centered_label(
+                self.uic.win_w, self.uic.win_h, graphics, self.uic, rect_state,
+                half_pos, half_dim, maybe_frame, color, text, size, text_color
+            );
+        }
+
+        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+
+    }
+}
@@ -0,0 +1,43 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use graphics;
+use graphics::Context;
+use label;
+use opengl_graphics::Gl;
+use point::Point;
+use ui_context::UiContext;
+
+/// The font size of the count shown within a badge.
+static BADGE_FONT_SIZE: u32 = 12;
+/// Counts above this are shown as "99+".
+static MAX_SHOWN_COUNT: u32 = 99;
+
+/// A trait used for widget contexts that may display a badge (i.e. an "unread" count).
+pub trait Badgeable {
+    fn badge(self, count: u32) -> Self;
+    fn badge_color(self, color: Color) -> Self;
+    fn badge_rgba(self, r: f32, g: f32, b: f32, a: f32) -> Self;
+}
+
+/// Draw a badge showing the given count centered on the top right corner of the given
+/// rectangle. Nothing is drawn for a count of zero.
+pub fn draw(uic: &mut UiContext, graphics: &mut Gl,
+            pos: Point, dim: Dimensions, count: u32, maybe_color: Option<Color>) {
+    if count == 0 { return }
+    let text = match count > MAX_SHOWN_COUNT {
+        true => format!("{}+", MAX_SHOWN_COUNT),
+        false => count.to_string(),
+    };
+    let text_w = label::width(uic, BADGE_FONT_SIZE, text.as_slice());
+    let h = BADGE_FONT_SIZE as f64 * 1.5;
+    let w = (text_w + BADGE_FONT_SIZE as f64).max(h);
+    let center = [pos[0] + dim[0], pos[1]];
+    let color = maybe_color.unwrap_or(Color::new(0.85, 0.15, 0.15, 1.0));
+    let Color(col) = color;
+    let context = Context::abs(uic.win_w, uic.win_h);
+    graphics::Ellipse::new(col)
+        .draw([center[0] - w / 2.0, center[1] - h / 2.0, w, h], &context, graphics);
+    let t_pos = [center[0] - text_w / 2.0, center[1] - BADGE_FONT_SIZE as f64 / 2.0];
+    uic.draw_text(graphics, t_pos, BADGE_FONT_SIZE, color.plain_contrast(), text.as_slice());
+}
@@ -1,22 +1,47 @@
-
+use std::num::Float;
+use badge;
 use color::Color;
 use dimensions::Dimensions;
-use opengl_graphics::Gl;
-use mouse::Mouse;
+use focus;
+use graphics;
+use graphics::{
+    Context,
+    ImageSize,
+    RelativeTransform,
+};
+use input::keyboard::Key;
+use keyboard;
+use label;
+use opengl_graphics::{
+    Gl,
+    Texture,
+};
+use mouse::{Cursor, Mouse};
+use padding;
 use point::Point;
 use rectangle;
+use rectangle::Gradient;
 use ui_context::{
     UIID,
     UiContext,
 };
 use widget::Widget;
 
+/// The space left between an image and the edges of the button or its label, unless
+/// a padding is given.
+static IMAGE_PADDING: f64 = 4.0;
+
 /// Represents the state of the Button widget.
 #[deriving(PartialEq, Clone, Copy)]
 pub enum State {
     Normal,
     Highlighted,
-    Clicked,
+    /// Stores the time at which the button was pressed.
+    Clicked(f64),
+    /// The button was held for the long press duration and has not yet been released.
+    LongPressed,
+    /// The right mouse button was pressed over the button.
+    RightClicked,
 }
 
 impl State {
@@ -25,7 +50,7 @@ impl State {
         match self {
             &State::Normal => rectangle::State::Normal,
             &State::Highlighted => rectangle::State::Highlighted,
-            &State::Clicked => rectangle::State::Clicked,
+            &State::Clicked(_) | &State::LongPressed | &State::RightClicked => rectangle::State::Clicked,
         }
     }
 }
@@ -35,31 +60,153 @@ widget_fns!(Button, State, Widget::Button(State::Normal));
 /// Check the current state of the button.
 fn get_new_state(is_over: bool,
                  prev: State,
-                 mouse: Mouse) -> State {
+                 mouse: Mouse,
+                 time: f64,
+                 maybe_long_press_secs: Option<f64>) -> State {
     use mouse::ButtonState::{Down, Up};
-    use self::State::{Normal, Highlighted, Clicked};
-    match (is_over, prev, mouse.left) {
-        (true,  Normal,  Down) => Normal,
-        (true,  _,       Down) => Clicked,
-        (true,  _,       Up)   => Highlighted,
-        (false, Clicked, Down) => Clicked,
-        _                      => Normal,
+    use self::State::{Normal, Highlighted, Clicked, LongPressed, RightClicked};
+    match (is_over, prev, mouse.left, mouse.right) {
+        (_,     RightClicked,   _,    Down) => RightClicked,
+        (true,  Highlighted,    Up,   Down) => RightClicked,
+        (true,  Normal,         Down, _)    => Normal,
+        (true,  LongPressed,    Down, _)    => LongPressed,
+        (true,  Clicked(start), Down, _)    => match maybe_long_press_secs {
+            Some(secs) if time - start >= secs => LongPressed,
+            _ => Clicked(start),
+        },
+        (true,  _,              Down, _)    => Clicked(time),
+        (true,  _,              Up,   _)    => Highlighted,
+        (false, Clicked(start), Down, _)    => Clicked(start),
+        _                                   => Normal,
     }
 }
 
+/// Draw the texture scaled to fit within the given rect, keeping its aspect ratio.
+fn draw_image(win_w: f64, win_h: f64, graphics: &mut Gl, texture: &Texture,
+              pos: Point, dim: Dimensions, maybe_tint: Option<Color>) {
+    let (tex_w, tex_h) = texture.get_size();
+    if tex_w == 0 || tex_h == 0 { return }
+    let scale = (dim[0] / tex_w as f64).min(dim[1] / tex_h as f64);
+    let (w, h) = (tex_w as f64 * scale, tex_h as f64 * scale);
+    let context = Context::abs(win_w, win_h)
+        .trans(pos[0] + (dim[0] - w) / 2.0, pos[1] + (dim[1] - h) / 2.0)
+        .scale(scale, scale);
+    let image = match maybe_tint {
+        Some(Color(col)) => graphics::Image::colored(col),
+        None => graphics::Image::new(),
+    };
+    image.draw(texture, &context, graphics);
+}
+
 /// A context on which the builder pattern can be implemented.
 pub struct ButtonContext<'a> {
     uic: &'a mut UiContext,
     ui_id: UIID,
     pos: Point,
     dim: Dimensions,
+    maybe_badge: Option<u32>,
+    maybe_badge_color: Option<Color>,
     maybe_color: Option<Color>,
     maybe_frame: Option<f64>,
     maybe_frame_color: Option<Color>,
+    maybe_image: Option<&'a Texture>,
+    maybe_image_color: Option<Color>,
     maybe_label: Option<&'a str>,
     maybe_label_color: Option<Color>,
     maybe_label_font_size: Option<u32>,
+    maybe_label_min_font_size: Option<u32>,
+    maybe_padding: Option<f64>,
+    maybe_margin: Option<f64>,
+    maybe_corner_radius: Option<f64>,
+    maybe_gradient: Option<Gradient>,
+    is_label_wrapped: bool,
     maybe_callback: Option<||:'a>,
+    maybe_double_click_callback: Option<||:'a>,
+    maybe_right_callback: Option<||:'a>,
+    is_enabled: bool,
+    is_focusable: bool,
+    maybe_toggled: Option<&'a mut bool>,
+    maybe_toggle_callback: Option<|bool|:'a>,
+    maybe_hotkey: Option<Key>,
+    maybe_long_press: Option<(f64, ||:'a)>,
+}
+
+impl<'a> ButtonContext<'a> {
+    /// Show the texture centered on the button, scaled to fit while keeping its
+    /// aspect ratio. If the button also has a label, the image is shown beside it.
+    #[inline]
+    pub fn image(self, texture: &'a Texture) -> ButtonContext<'a> {
+        ButtonContext { maybe_image: Some(texture), ..self }
+    }
+    /// Show the texture at the left of the button with the label beside it.
+    #[inline]
+    pub fn image_with_label(self, texture: &'a Texture, text: &'a str) -> ButtonContext<'a> {
+        ButtonContext { maybe_image: Some(texture), maybe_label: Some(text), ..self }
+    }
+    /// Tint the image with the given color, which is highlighted and clicked
+    /// along with the button.
+    #[inline]
+    pub fn image_color(self, color: Color) -> ButtonContext<'a> {
+        ButtonContext { maybe_image_color: Some(color), ..self }
+    }
+    /// Round the button's corners to the given radius.
+    #[inline]
+    pub fn corner_radius(self, radius: f64) -> ButtonContext<'a> {
+        ButtonContext { maybe_corner_radius: Some(radius), ..self }
+    }
+    /// Fill the button with a gradient from the top color to the bottom color in place of
+    /// its color.
+    #[inline]
+    pub fn gradient(self, top: Color, bottom: Color) -> ButtonContext<'a> {
+        ButtonContext { maybe_gradient: Some(Gradient::Vertical(top, bottom)), ..self }
+    }
+    /// Wrap the label onto as many lines as needed to fit the button's width, with the
+    /// lines centered vertically. With `shrink_label_to_fit`, the font size is reduced
+    /// until the lines also fit the button's height.
+    #[inline]
+    pub fn wrap_label(self) -> ButtonContext<'a> {
+        ButtonContext { is_label_wrapped: true, ..self }
+    }
+    /// Call the given closure when the button is double clicked. It is called upon the
+    /// second press, and the regular `callback` is still called for both clicks.
+    #[inline]
+    pub fn on_double_click(self, callback: ||:'a) -> ButtonContext<'a> {
+        ButtonContext { maybe_double_click_callback: Some(callback), ..self }
+    }
+    /// Call the given closure when the button is clicked with the right mouse button.
+    #[inline]
+    pub fn right_callback(self, callback: ||:'a) -> ButtonContext<'a> {
+        ButtonContext { maybe_right_callback: Some(callback), ..self }
+    }
+    /// Allow the button to take keyboard focus when pressed or tabbed to. While focused,
+    /// the button shows a focus ring and Enter or Space calls the `callback`.
+    #[inline]
+    pub fn focusable(self, focusable: bool) -> ButtonContext<'a> {
+        ButtonContext { is_focusable: focusable, ..self }
+    }
+    /// Latch the button so that each click toggles the given bool. The button stays
+    /// pressed while the bool is true.
+    #[inline]
+    pub fn toggleable(self, value: &'a mut bool) -> ButtonContext<'a> {
+        ButtonContext { maybe_toggled: Some(value), ..self }
+    }
+    /// Call the given closure with the new value each time a toggleable button is toggled.
+    #[inline]
+    pub fn on_toggle(self, callback: |bool|:'a) -> ButtonContext<'a> {
+        ButtonContext { maybe_toggle_callback: Some(callback), ..self }
+    }
+    /// Also activate the button whenever the given key is pressed, wherever the mouse is,
+    /// unless another widget has keyboard focus. The key's name is shown after the label, i.e. "Save (S)".
+    #[inline]
+    pub fn hotkey(self, key: Key) -> ButtonContext<'a> {
+        ButtonContext { maybe_hotkey: Some(key), ..self }
+    }
+    /// Call the given closure once the button has been held down for the given number of
+    /// seconds. The regular `callback` is not called when a long pressed button is released.
+    #[inline]
+    pub fn on_long_press(self, secs: f64, callback: ||:'a) -> ButtonContext<'a> {
+        ButtonContext { maybe_long_press: Some((secs, callback)), ..self }
+    }
 }
 
 pub trait ButtonBuilder<'a> {
@@ -71,72 +218,238 @@ impl<'a> ButtonBuilder<'a> for UiContext {
 
     /// Create a button context to be built upon.
     fn button(&'a mut self, ui_id: UIID) -> ButtonContext<'a> {
+        let pos = self.flow_pos();
         ButtonContext {
             uic: self,
             ui_id: ui_id,
-            pos: [0.0, 0.0],
+            pos: pos,
             dim: [64.0, 64.0],
+            maybe_badge: None,
+            maybe_badge_color: None,
             maybe_callback: None,
+            maybe_double_click_callback: None,
+            maybe_right_callback: None,
             maybe_color: None,
             maybe_frame: None,
             maybe_frame_color: None,
+            maybe_image: None,
+            maybe_image_color: None,
             maybe_label: None,
             maybe_label_color: None,
             maybe_label_font_size: None,
+            maybe_label_min_font_size: None,
+            maybe_padding: None,
+            maybe_margin: None,
+            maybe_corner_radius: None,
+            maybe_gradient: None,
+            is_label_wrapped: false,
+            is_enabled: true,
+            is_focusable: false,
+            maybe_toggled: None,
+            maybe_toggle_callback: None,
+            maybe_hotkey: None,
+            maybe_long_press: None,
         }
     }
 
 }
 
+impl_auto_sizable!(ButtonContext);
+impl_badgeable!(ButtonContext);
 impl_callable!(ButtonContext, ||:'a);
 impl_colorable!(ButtonContext);
+impl_enableable!(ButtonContext);
 impl_frameable!(ButtonContext);
+impl_hoverable!(ButtonContext);
 impl_labelable!(ButtonContext);
+impl_paddable!(ButtonContext);
 impl_positionable!(ButtonContext);
 impl_shapeable!(ButtonContext);
 
 impl<'a> ::draw::Drawable for ButtonContext<'a> {
     fn draw(&mut self, graphics: &mut Gl) {
 
+        // Draw within the margin, while placing the area that includes it.
+        let (outer_pos, outer_dim) = (self.pos, self.dim);
+        let (pos, dim) = padding::inset(self.pos, self.dim, self.maybe_margin.unwrap_or(0.0));
+        self.pos = pos;
+        self.dim = dim;
+
         let state = *get_state(self.uic, self.ui_id);
-        let mouse = self.uic.get_mouse_state();
-        let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
-        let new_state = get_new_state(is_over, state, mouse);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let is_over = self.is_enabled && rectangle::is_over(self.pos, mouse.pos, self.dim);
+        let time = self.uic.frame_time();
+        let long_press_secs = self.maybe_long_press.as_ref().map(|&(secs, _)| secs);
+        let new_state = match self.is_enabled {
+            true => get_new_state(is_over, state, mouse, time, long_press_secs),
+            false => State::Normal,
+        };
+        if is_over { self.uic.set_cursor(Cursor::Hand) }
 
-        // Callback.
+        // Take focus when pressed and lose it when the mouse is pressed elsewhere.
+        let is_pressed = match new_state { State::Clicked(_) => true, _ => false };
+        let is_focusable = self.is_enabled && self.is_focusable;
+        let is_focused = self.uic.update_focus(self.ui_id, is_focusable, is_pressed, is_over);
+        let is_key_activated = is_focused && self.uic.get_pressed_keys().iter()
+            .any(|key| match *key { Key::Return | Key::Space => true, _ => false });
+        let is_hotkey_pressed = match (self.is_enabled, self.maybe_hotkey) {
+            (true, Some(hotkey)) => self.uic.get_unfocused_keys().iter().any(|key| *key == hotkey),
+            _ => false,
+        };
+
+        // Callback. A press that became a drag (i.e. of a draggable area beneath the
+        // button) isn't a click.
+        let is_clicked = match (is_over, state, new_state) {
+            (true, State::Clicked(_), State::Highlighted) => !self.uic.is_past_drag_threshold(),
+            _ => false,
+        };
+        if is_clicked || is_key_activated || is_hotkey_pressed {
+            if let Some(ref mut value) = self.maybe_toggled {
+                **value = !**value;
+                match self.maybe_toggle_callback {
+                    Some(ref mut callback) => (*callback)(**value), None => (),
+                }
+            }
+            match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(), None => (),
+            }
+        }
         match (is_over, state, new_state) {
-            (true, State::Clicked, State::Highlighted) => match self.maybe_callback {
+            (true, State::RightClicked, State::Highlighted) => match self.maybe_right_callback {
                 Some(ref mut callback) => (*callback)(), None => (),
+            },
+            (true, State::Highlighted, State::Clicked(_)) if mouse.is_double_click => {
+                match self.maybe_double_click_callback {
+                    Some(ref mut callback) => (*callback)(), None => (),
+                }
+            },
+            (true, State::Clicked(_), State::LongPressed) => match self.maybe_long_press {
+                Some((_, ref mut callback)) => (*callback)(), None => (),
             }, _ => (),
         }
 
         // Draw.
-        let rect_state = new_state.as_rectangle_state();
-        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
-        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        // A toggleable button stays pressed while active.
+        let rect_state = match self.maybe_toggled {
+            Some(ref value) if **value => rectangle::State::Clicked,
+            _ => new_state.as_rectangle_state(),
+        };
+        // The colors fade between states, so the rectangle itself is drawn as Normal.
+        let transition = self.uic.transition(self.ui_id, rect_state);
+        let rect_state = rectangle::State::Normal;
+        let style = self.uic.theme.style(self.uic.theme.maybe_button);
+        let color = self.maybe_color.unwrap_or(style.shape_color);
+        let color = if self.is_enabled { color } else { color.disabled() };
+        let color = transition.color(color);
+        let label_color = self.maybe_label_color.unwrap_or(style.label_color);
+        let label_color = if self.is_enabled { label_color } else { label_color.disabled() };
+        let frame_w = self.maybe_frame.unwrap_or(style.frame_width);
         let maybe_frame = match frame_w > 0.0 {
-            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(style.frame_color))),
             false => None,
         };
-        match self.maybe_label {
-            None => {
-                rectangle::draw(
+        let radius = self.maybe_corner_radius.unwrap_or(self.uic.theme.corner_radius());
+        let is_enabled = self.is_enabled;
+        let maybe_gradient = self.maybe_gradient.or(self.uic.theme.maybe_gradient).map(|gradient| {
+            gradient.map(|color| {
+                transition.color(if is_enabled { color } else { color.disabled() })
+            })
+        });
+        let (win_w, win_h) = (self.uic.win_w, self.uic.win_h);
+        let image_padding = self.maybe_padding.unwrap_or(IMAGE_PADDING);
+        let text_pad = frame_w + self.maybe_padding.unwrap_or(0.0);
+        let maybe_tint = self.maybe_image_color.map(|tint| match new_state {
+            State::Normal => tint,
+            State::Highlighted => tint.highlighted(),
+            State::Clicked(_) | State::LongPressed | State::RightClicked => tint.clicked(),
+        });
+        let hotkey_text = match (self.maybe_label, self.maybe_hotkey) {
+            (Some(text), Some(key)) => Some(format!("{} ({})", text, keyboard::key_name(key))),
+            _ => None,
+        };
+        let maybe_label = match hotkey_text {
+            Some(ref text) => Some(text.as_slice()),
+            None => self.maybe_label,
+        };
+        match (self.maybe_image, maybe_label) {
+            (None, None) => {
+                rectangle::draw_rounded(
                     self.uic.win_w, self.uic.win_h, graphics, rect_state, self.pos,
-                    self.dim, maybe_frame, color
+                    self.dim, maybe_frame, color, maybe_gradient, radius
                 )
             },
-            Some(text) => {
-                let text_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+            (Some(texture), None) => {
+                rectangle::draw_rounded(win_w, win_h, graphics, rect_state, self.pos, self.dim,
+                                        maybe_frame, color, maybe_gradient, radius);
+                let pad = frame_w + image_padding;
+                let image_pos = [self.pos[0] + pad, self.pos[1] + pad];
+                let image_dim = [self.dim[0] - pad * 2.0, self.dim[1] - pad * 2.0];
+                draw_image(win_w, win_h, graphics, texture, image_pos, image_dim, maybe_tint);
+            },
+            (Some(texture), Some(text)) => {
+                rectangle::draw_rounded(win_w, win_h, graphics, rect_state, self.pos, self.dim,
+                                        maybe_frame, color, maybe_gradient, radius);
+                // The image fills a square at the left and the label is centered in the rest.
+                let pad = frame_w + image_padding;
+                let side = self.dim[1] - pad * 2.0;
+                let image_pos = [self.pos[0] + pad, self.pos[1] + pad];
+                draw_image(win_w, win_h, graphics, texture, image_pos, [side, side], maybe_tint);
+                let text_x = image_pos[0] + side + image_padding;
+                let text_area_w = self.pos[0] + self.dim[0] - frame_w - text_x;
+                let text_color = label_color;
                 let size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_medium);
-                rectangle::draw_with_centered_label(
-                    self.uic.win_w, self.uic.win_h, graphics, self.uic, rect_state,
-                    self.pos, self.dim, maybe_frame, color,
-                    text, size, text_color
-                )
+                if self.is_label_wrapped {
+                    let text_area_pos = [text_x, self.pos[1] + frame_w];
+                    let text_area_dim = [text_area_w, self.dim[1] - frame_w * 2.0];
+                    let size = label::shrink_wrapped_to_fit(self.uic, text, size,
+                                                            self.maybe_label_min_font_size,
+                                                            text_area_dim[0], text_area_dim[1]);
+                    label::draw_wrapped(self.uic, graphics, text, text_area_pos, text_area_dim,
+                                        size, text_color);
+                } else {
+                    let size = label::shrink_to_fit(self.uic, text, size, self.maybe_label_min_font_size,
+                                                    text_area_w);
+                    let text_w = label::width(self.uic, size, text);
+                    let l_pos = [text_x + (text_area_w - text_w) / 2.0,
+                                 self.pos[1] + (self.dim[1] - size as f64) / 2.0];
+                    self.uic.set_baseline(self.ui_id, label::baseline(l_pos[1], size));
+                    self.uic.draw_text(graphics, l_pos, size, text_color, text);
+                }
+            },
+            (None, Some(text)) if self.is_label_wrapped => {
+                rectangle::draw_rounded(win_w, win_h, graphics, rect_state, self.pos, self.dim,
+                                        maybe_frame, color, maybe_gradient, radius);
+                let (text_area_pos, text_area_dim) = padding::inset(self.pos, self.dim, text_pad);
+                let size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_medium);
+                let size = label::shrink_wrapped_to_fit(self.uic, text, size,
+                                                        self.maybe_label_min_font_size,
+                                                        text_area_dim[0], text_area_dim[1]);
+                label::draw_wrapped(self.uic, graphics, text, text_area_pos, text_area_dim,
+                                    size, label_color);
+            },
+            (None, Some(text)) => {
+                let text_color = label_color;
+                let size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_medium);
+                let size = label::shrink_to_fit(self.uic, text, size, self.maybe_label_min_font_size,
+                                                self.dim[0] - text_pad * 2.0);
+                let text_w = label::width(self.uic, size, text);
+                let text_pos = [self.pos[0] + (self.dim[0] - text_w) / 2.0,
+                                self.pos[1] + (self.dim[1] - size as f64) / 2.0];
+                self.uic.set_baseline(self.ui_id, label::baseline(text_pos[1], size));
+                rectangle::draw_rounded(win_w, win_h, graphics, rect_state, self.pos, self.dim,
+                                        maybe_frame, color, maybe_gradient, radius);
+                self.uic.draw_text(graphics, text_pos, size, text_color, text)
             },
         }
+        if let Some(count) = self.maybe_badge {
+            badge::draw(self.uic, graphics, self.pos, self.dim, count, self.maybe_badge_color);
+        }
+        if is_focused {
+            let ring_color = self.uic.theme.link_color();
+            focus::draw_ring(win_w, win_h, graphics, self.pos, self.dim, ring_color);
+        }
 
-        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+        set_state(self.uic, self.ui_id, new_state, outer_pos, outer_dim);
 
     }
 }
@@ -0,0 +1,110 @@
+
+use color::Color;
+use dimensions::Dimensions;
+use graphics;
+use graphics::Context;
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use point::Point;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use widget::Widget::Canvas;
+
+/// The Canvas has no interactive state of its own, however it is stored
+/// within the UiContext so that other widgets may be positioned relative to it.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct State;
+
+widget_fns!(Canvas, State, Canvas(State));
+
+/// A context on which the builder pattern can be implemented.
+pub struct CanvasContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    pos: Point,
+    dim: Dimensions,
+    maybe_draw_fn: Option<|&mut Gl, &Context, Point, Dimensions|:'a>,
+    maybe_mouse_fn: Option<|Mouse, Dimensions|:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+}
+
+impl<'a> CanvasContext<'a> {
+    /// The closure used to draw the canvas' contents. It is given a Context for the
+    /// window along with the canvas' position and dimensions, and all drawing within
+    /// it is clipped to the canvas' bounds.
+    #[inline]
+    pub fn draw_fn(self, draw_fn: |&mut Gl, &Context, Point, Dimensions|:'a) -> CanvasContext<'a> {
+        CanvasContext { maybe_draw_fn: Some(draw_fn), ..self }
+    }
+    /// The closure used to handle the mouse within the canvas. It is given the mouse with
+    /// its positions relative to the top left corner of the area within the canvas' frame,
+    /// along with the dimensions of that area, and is called before the contents are drawn.
+    #[inline]
+    pub fn mouse_fn(self, mouse_fn: |Mouse, Dimensions|:'a) -> CanvasContext<'a> {
+        CanvasContext { maybe_mouse_fn: Some(mouse_fn), ..self }
+    }
+}
+
+pub trait CanvasBuilder<'a> {
+    /// A canvas builder method to be implemented by the UiContext.
+    fn canvas(&'a mut self, ui_id: UIID) -> CanvasContext<'a>;
+}
+
+impl<'a> CanvasBuilder<'a> for UiContext {
+    fn canvas(&'a mut self, ui_id: UIID) -> CanvasContext<'a> {
+        let pos = self.flow_pos();
+        CanvasContext {
+            uic: self,
+            ui_id: ui_id,
+            pos: pos,
+            dim: [256.0, 256.0],
+            maybe_draw_fn: None,
+            maybe_mouse_fn: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+        }
+    }
+}
+
+impl_colorable!(CanvasContext);
+impl_frameable!(CanvasContext);
+impl_hoverable!(CanvasContext);
+impl_positionable!(CanvasContext);
+impl_shapeable!(CanvasContext);
+
+impl<'a> ::draw::Drawable for CanvasContext<'a> {
+    /// Draw the canvas. It is only filled and framed if a color or frame was given.
+    fn draw(&mut self, graphics: &mut Gl) {
+        let context = Context::abs(self.uic.win_w, self.uic.win_h);
+        let frame_w = self.maybe_frame.unwrap_or(0.0);
+        if frame_w > 0.0 {
+            let Color(col) = self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color);
+            graphics::Rectangle::new(col)
+                .draw([self.pos[0], self.pos[1], self.dim[0], self.dim[1]], &context, graphics);
+        }
+        let inner_pos = [self.pos[0] + frame_w, self.pos[1] + frame_w];
+        let inner_dim = [self.dim[0] - frame_w * 2.0, self.dim[1] - frame_w * 2.0];
+        if let Some(Color(col)) = self.maybe_color {
+            graphics::Rectangle::new(col)
+                .draw([inner_pos[0], inner_pos[1], inner_dim[0], inner_dim[1]], &context, graphics);
+        }
+        if let Some(ref mut mouse_fn) = self.maybe_mouse_fn {
+            let mouse = self.uic.get_mouse_state_for(self.ui_id).relative_to(inner_pos);
+            (*mouse_fn)(mouse, inner_dim)
+        }
+        match self.maybe_draw_fn {
+            Some(ref mut draw_fn) => {
+                self.uic.push_clip(inner_pos, inner_dim);
+                (*draw_fn)(graphics, &context, inner_pos, inner_dim);
+                self.uic.pop_clip();
+            },
+            None => (),
+        }
+        set_state(self.uic, self.ui_id, State, self.pos, self.dim);
+    }
+}
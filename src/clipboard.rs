@@ -0,0 +1,28 @@
+
+/// A clipboard through which widgets copy and paste text. Applications may provide an
+/// implementation backed by the platform's clipboard (i.e. via their window backend)
+/// with `UiContext::set_clipboard`.
+pub trait Clipboard {
+    /// Return the text held by the clipboard (if any).
+    fn get_text(&mut self) -> Option<String>;
+    /// Replace the clipboard's contents with the given text.
+    fn set_text(&mut self, text: &str);
+}
+
+/// A clipboard that only holds text within the process. This is used when no system
+/// clipboard has been given.
+pub struct LocalClipboard {
+    maybe_text: Option<String>,
+}
+
+impl LocalClipboard {
+    /// Constructor for an empty LocalClipboard.
+    pub fn new() -> LocalClipboard {
+        LocalClipboard { maybe_text: None }
+    }
+}
+
+impl Clipboard for LocalClipboard {
+    fn get_text(&mut self) -> Option<String> { self.maybe_text.clone() }
+    fn set_text(&mut self, text: &str) { self.maybe_text = Some(text.to_string()) }
+}
@@ -152,6 +152,25 @@ impl Color {
         Color::new(r, g, b, a)
     }
 
+    /// Return a greyed, faded version of the current Color for drawing disabled widgets.
+    pub fn disabled(&self) -> Color {
+        let grey = self.luminance();
+        Color::new((self.r() + grey) * 0.5f32,
+                   (self.g() + grey) * 0.5f32,
+                   (self.b() + grey) * 0.5f32,
+                   self.a() * 0.5f32)
+    }
+
+    /// Return the color the given amount of the way from this color to the other, where
+    /// 0.0 gives this color and 1.0 gives the other.
+    pub fn mix(&self, other: Color, amount: f32) -> Color {
+        let amount = clampf32(amount);
+        Color::new(self.r() + (other.r() - self.r()) * amount,
+                   self.g() + (other.g() - self.g()) * amount,
+                   self.b() + (other.b() - self.b()) * amount,
+                   self.a() + (other.a() - self.a()) * amount)
+    }
+
     /// Return a random color.
     pub fn random() -> Color {
         let r = random::<f32>();
@@ -0,0 +1,150 @@
+use std::num::Float;
+use color::Color;
+use draw;
+use gl;
+use graphics;
+use graphics::Context;
+use graphics::ImageSize;
+use opengl_graphics::{
+    Gl,
+    Texture,
+};
+
+/// The Okabe-Ito palette, whose colors remain distinguishable under all common
+/// forms of color blindness. Ordered black, orange, sky blue, bluish green,
+/// yellow, blue, vermillion and reddish purple.
+pub static OKABE_ITO: [Color, ..8] = [
+    Color([0.0, 0.0, 0.0, 1.0]),
+    Color([0.9, 0.6, 0.0, 1.0]),
+    Color([0.34, 0.71, 0.91, 1.0]),
+    Color([0.0, 0.62, 0.45, 1.0]),
+    Color([0.94, 0.89, 0.26, 1.0]),
+    Color([0.0, 0.45, 0.7, 1.0]),
+    Color([0.84, 0.37, 0.0, 1.0]),
+    Color([0.8, 0.47, 0.65, 1.0]),
+];
+
+/// A color vision deficiency for which the final frame may be simulated.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Deficiency {
+    /// No red cones.
+    Protanopia,
+    /// No green cones.
+    Deuteranopia,
+    /// No blue cones.
+    Tritanopia,
+}
+
+impl Deficiency {
+    /// The matrix mapping RGB to the simulated RGB (Machado et al. 2009, full severity).
+    fn matrix(&self) -> [[f32, ..3], ..3] {
+        match *self {
+            Deficiency::Protanopia => [[0.152286, 1.052583, -0.204868],
+                                       [0.114503, 0.786281, 0.099216],
+                                       [-0.003882, -0.048116, 1.051998]],
+            Deficiency::Deuteranopia => [[0.367322, 0.860646, -0.227968],
+                                         [0.280085, 0.672501, 0.047413],
+                                         [-0.011820, 0.042940, 0.968881]],
+            Deficiency::Tritanopia => [[1.255528, -0.076749, -0.178779],
+                                       [-0.078411, 0.930809, 0.147602],
+                                       [0.004733, 0.691367, 0.303900]],
+        }
+    }
+}
+
+/// The number of levels at which linear light is quantized when encoding it back to sRGB.
+static LINEAR_LEVELS: uint = 4096;
+
+/// Decode an sRGB channel in [0, 1] to linear light, in which the matrices apply.
+fn to_linear(c: f32) -> f32 {
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+/// Encode a channel of linear light in [0, 1] as sRGB.
+fn to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 }
+}
+
+/// Return the given color as it would be seen with the given deficiency.
+pub fn simulate(color: Color, deficiency: Deficiency) -> Color {
+    let Color([r, g, b, a]) = color;
+    let (r, g, b) = (to_linear(r), to_linear(g), to_linear(b));
+    let m = deficiency.matrix();
+    let channel = |row: [f32, ..3]| {
+        to_srgb((row[0] * r + row[1] * g + row[2] * b).max(0.0).min(1.0))
+    };
+    Color([channel(m[0]), channel(m[1]), channel(m[2]), a])
+}
+
+/// Redraws the finished frame as it would be seen with a deficiency. The texture the
+/// simulated frame is uploaded to is kept between frames and only recreated when the
+/// window is resized.
+pub struct Simulator {
+    /// The linear light of each sRGB byte.
+    decode: Vec<f32>,
+    /// The sRGB byte of each quantized level of linear light.
+    encode: Vec<u8>,
+    maybe_texture: Option<Texture>,
+}
+
+impl Simulator {
+
+    /// Construct a Simulator. No texture is created until the first frame is drawn.
+    pub fn new() -> Simulator {
+        let max_level = (LINEAR_LEVELS - 1u) as f32;
+        Simulator {
+            decode: range(0u, 256u).map(|i| to_linear(i as f32 / 255.0)).collect(),
+            encode: range(0u, LINEAR_LEVELS).map(|i| {
+                (to_srgb(i as f32 / max_level) * 255.0 + 0.5) as u8
+            }).collect(),
+            maybe_texture: None,
+        }
+    }
+
+    /// Redraw everything drawn so far this frame as it would be seen with the given
+    /// deficiency. This should be called after all widgets have been drawn.
+    pub fn draw(&mut self, win_w: f64, win_h: f64, graphics: &mut Gl, deficiency: Deficiency) {
+        let mut image = draw::read_pixels(win_w, win_h, [0.0, 0.0], [win_w, win_h]);
+        let m = deficiency.matrix();
+        let max_level = (LINEAR_LEVELS - 1u) as f32;
+        for px in image.data.as_mut_slice().chunks_mut(4u) {
+            let (r, g, b) = (self.decode[px[0] as uint],
+                             self.decode[px[1] as uint],
+                             self.decode[px[2] as uint]);
+            for i in range(0u, 3u) {
+                let c = (m[i][0] * r + m[i][1] * g + m[i][2] * b).max(0.0).min(1.0);
+                px[i] = self.encode[(c * max_level + 0.5) as uint];
+            }
+        }
+        let (w, h) = (image.width as u32, image.height as u32);
+        let pixels = image.data.as_ptr() as *const gl::types::GLvoid;
+        let is_same_size = match self.maybe_texture {
+            Some(ref texture) => texture.get_size() == (w, h),
+            None => false,
+        };
+        match is_same_size {
+            true => unsafe {
+                let texture = self.maybe_texture.as_ref().unwrap();
+                gl::BindTexture(gl::TEXTURE_2D, texture.get_id());
+                gl::TexSubImage2D(gl::TEXTURE_2D, 0, 0, 0, w as i32, h as i32,
+                                  gl::RGBA, gl::UNSIGNED_BYTE, pixels);
+            },
+            false => {
+                let mut id: gl::types::GLuint = 0;
+                unsafe {
+                    gl::GenTextures(1, &mut id);
+                    gl::BindTexture(gl::TEXTURE_2D, id);
+                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+                    gl::TexImage2D(gl::TEXTURE_2D, 0, gl::RGBA as i32, w as i32, h as i32, 0,
+                                   gl::RGBA, gl::UNSIGNED_BYTE, pixels);
+                }
+                // The previous Texture deletes its GL texture when dropped.
+                self.maybe_texture = Some(Texture::new(id, w, h));
+            },
+        }
+        let context = Context::abs(win_w, win_h);
+        graphics::Image::new().draw(self.maybe_texture.as_ref().unwrap(), &context, graphics);
+    }
+
+}
@@ -0,0 +1,116 @@
+
+use dimensions::Dimensions;
+use opengl_graphics::Gl;
+use point::Point;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use widget::Widget::Compound;
+
+/// A Compound has no state of its own as its children store theirs, however it is stored
+/// within the UiContext so that other widgets may be positioned relative to it.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct State;
+
+widget_fns!(Compound, State, Compound(State));
+
+/// The rect and range of UIIDs reserved for a compound widget's children.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct Scope {
+    /// The UIID of the compound widget itself.
+    pub ui_id: UIID,
+    pub pos: Point,
+    pub dim: Dimensions,
+    num_children: uint,
+}
+
+impl Scope {
+
+    /// The UIID of the child at the given index. The children occupy the `num_children`
+    /// UIIDs following the compound widget's own, so a compound widget with `n` children
+    /// must be given a UIID at least `n + 1` below the next widget's.
+    pub fn child_id(&self, idx: uint) -> UIID {
+        if idx >= self.num_children {
+            panic!("Child index {} is out of range for a compound widget with {} children.",
+                   idx, self.num_children);
+        }
+        self.ui_id + 1 + idx as UIID
+    }
+
+    /// Convert a point relative to the top left of the scope's rect to window co-ordinates.
+    pub fn point(&self, rel: Point) -> Point {
+        [self.pos[0] + rel[0], self.pos[1] + rel[1]]
+    }
+
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct CompoundContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    num_children: uint,
+    pos: Point,
+    dim: Dimensions,
+    maybe_content: Option<|&mut UiContext, &mut Gl, &Scope|:'a>,
+}
+
+impl<'a> CompoundContext<'a> {
+    /// Instantiate the child widgets. The closure is given the Scope from which the
+    /// children's positions and UIIDs should be taken.
+    #[inline]
+    pub fn content(self, content: |&mut UiContext, &mut Gl, &Scope|:'a) -> CompoundContext<'a> {
+        CompoundContext { maybe_content: Some(content), ..self }
+    }
+}
+
+pub trait CompoundBuilder<'a> {
+    /// A compound builder method to be implemented by the UiContext. `num_children`
+    /// UIIDs are reserved following the given one.
+    fn compound(&'a mut self, ui_id: UIID, num_children: uint) -> CompoundContext<'a>;
+}
+
+impl<'a> CompoundBuilder<'a> for UiContext {
+    fn compound(&'a mut self, ui_id: UIID, num_children: uint) -> CompoundContext<'a> {
+        let pos = self.flow_pos();
+        CompoundContext {
+            uic: self,
+            ui_id: ui_id,
+            num_children: num_children,
+            pos: pos,
+            dim: [128.0, 64.0],
+            maybe_content: None,
+        }
+    }
+}
+
+impl_hoverable!(CompoundContext);
+impl_positionable!(CompoundContext);
+impl_shapeable!(CompoundContext);
+
+impl<'a> ::draw::Drawable for CompoundContext<'a> {
+    /// Draw the children. Afterwards the compound widget is placed as a whole, so
+    /// the next widget may be positioned relative to it rather than its last child.
+    fn draw(&mut self, graphics: &mut Gl) {
+        let scope = Scope {
+            ui_id: self.ui_id,
+            pos: self.pos,
+            dim: self.dim,
+            num_children: self.num_children,
+        };
+        let CompoundContext { // borrowck
+            ref mut uic,
+            ref mut maybe_content,
+            ..
+        } = *self;
+        match *maybe_content {
+            Some(ref mut content) => {
+                uic.begin_children(scope.ui_id);
+                (*content)(&mut **uic, graphics, &scope);
+                uic.end_children();
+            },
+            None => (),
+        }
+        set_state(&mut **uic, scope.ui_id, State, scope.pos, scope.dim);
+    }
+}
@@ -0,0 +1,60 @@
+use std::num::Float;
+use point::Point;
+use ui_context::UIID;
+
+/// How far a stick must be pushed from its center (as a fraction of its full travel) to
+/// move focus.
+pub static STICK_DEAD_ZONE: f64 = 0.5;
+
+/// The controller buttons with which widgets are navigated. Applications should pass these
+/// to `UiContext::handle_controller` as they are pressed, mapping the buttons of their
+/// controller library (i.e. the D-pad to the directions, "A" to Activate and "B" to Cancel).
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum ControllerButton {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Activate the focused widget as though Enter were pressed.
+    Activate,
+    /// Remove focus from the focused widget.
+    Cancel,
+}
+
+/// Return the direction in which a stick is pushed (if it is pushed beyond the dead zone).
+/// Each axis ranges from -1.0 to 1.0, with positive `y` being down. Applications pass the
+/// stick's position to `UiContext::handle_controller_stick`, which uses this to move focus.
+pub fn stick_direction(x: f64, y: f64) -> Option<ControllerButton> {
+    match (x.abs() > y.abs(), x, y) {
+        (true, x, _) if x > STICK_DEAD_ZONE => Some(ControllerButton::Right),
+        (true, x, _) if x < -STICK_DEAD_ZONE => Some(ControllerButton::Left),
+        (false, _, y) if y > STICK_DEAD_ZONE => Some(ControllerButton::Down),
+        (false, _, y) if y < -STICK_DEAD_ZONE => Some(ControllerButton::Up),
+        _ => None,
+    }
+}
+
+/// Return the widget whose center is nearest to the given point in the given direction,
+/// favouring those most directly in line with it.
+pub fn nearest_in_direction(from: Point,
+                            direction: ControllerButton,
+                            centers: &[(UIID, Point)]) -> Option<UIID> {
+    let mut maybe_nearest: Option<(UIID, f64)> = None;
+    for &(ui_id, center) in centers.iter() {
+        let (dx, dy) = (center[0] - from[0], center[1] - from[1]);
+        let (along, across) = match direction {
+            ControllerButton::Up => (-dy, dx),
+            ControllerButton::Down => (dy, dx),
+            ControllerButton::Left => (-dx, dy),
+            ControllerButton::Right => (dx, dy),
+            _ => return None,
+        };
+        if along <= 0.0 { continue }
+        let score = along + across.abs() * 2.0;
+        maybe_nearest = match maybe_nearest {
+            Some((_, nearest)) if nearest <= score => maybe_nearest,
+            _ => Some((ui_id, score)),
+        };
+    }
+    maybe_nearest.map(|(ui_id, _)| ui_id)
+}
@@ -0,0 +1,313 @@
+
+use color::Color;
+use dimensions::Dimensions;
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use point::Point;
+use position::Edge;
+use rectangle;
+use snap;
+use snap::Guide;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use vecmath::vec2_sub;
+use widget::Widget::Dock;
+
+/// The height of a dock panel's title bar.
+static TITLE_BAR_HEIGHT: f64 = 24.0;
+/// The distance from a window edge within which a dragged panel will dock to it.
+static DOCK_MARGIN: f64 = 32.0;
+
+/// Where a dock panel currently lives.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Placement {
+    /// The panel has not yet been moved, so the placement given to the builder is used.
+    Initial,
+    /// Floating freely with its top left corner at the given point.
+    Floating(Point),
+    /// Docked to the given window edge.
+    Docked(Edge),
+}
+
+/// Represents the interaction with a dock panel.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    Highlighted,
+    /// The title bar is being dragged. Stores the mouse offset from the panel's position.
+    Dragging(Point),
+}
+
+/// Represents the state of the Dock widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct State {
+    interaction: Interaction,
+    placement: Placement,
+}
+
+widget_fns!(Dock, State, Dock(State {
+    interaction: Interaction::Normal,
+    placement: Placement::Initial,
+}));
+
+/// Return the rect occupied by a panel docked to the given edge.
+fn docked_rect(edge: Edge, size: f64, win_w: f64, win_h: f64) -> (Point, Dimensions) {
+    match edge {
+        Edge::Left   => ([0.0, 0.0], [size, win_h]),
+        Edge::Right  => ([win_w - size, 0.0], [size, win_h]),
+        Edge::Top    => ([0.0, 0.0], [win_w, size]),
+        Edge::Bottom => ([0.0, win_h - size], [win_w, size]),
+    }
+}
+
+/// Return the window edge that a panel dropped at the given mouse position would dock to.
+fn edge_under(mouse_pos: Point, win_w: f64, win_h: f64) -> Option<Edge> {
+    if mouse_pos[0] <= DOCK_MARGIN { Some(Edge::Left) }
+    else if mouse_pos[0] >= win_w - DOCK_MARGIN { Some(Edge::Right) }
+    else if mouse_pos[1] <= DOCK_MARGIN { Some(Edge::Top) }
+    else if mouse_pos[1] >= win_h - DOCK_MARGIN { Some(Edge::Bottom) }
+    else { None }
+}
+
+/// Check the current interaction with the panel's title bar.
+fn get_new_interaction(is_over_title: bool,
+                       prev: Interaction,
+                       pos: Point,
+                       mouse: Mouse) -> Interaction {
+    use mouse::ButtonState::{Down, Up};
+    use self::Interaction::{Normal, Highlighted, Dragging};
+    match (is_over_title, prev, mouse.left) {
+        (_,     Dragging(grab), Down) => Dragging(grab),
+        (true,  Highlighted,    Down) => Dragging(vec2_sub(mouse.pos, pos)),
+        (true,  _,              Up)   => Highlighted,
+        _                             => Normal,
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct DockContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    title: &'a str,
+    initial_edge: Option<Edge>,
+    docked_size: f64,
+    maybe_snap_grid: Option<f64>,
+    pos: Point,
+    dim: Dimensions,
+    maybe_content: Option<|&mut UiContext, &mut Gl, Point, Dimensions|:'a>,
+    maybe_callback: Option<|Placement|:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+}
+
+impl<'a> DockContext<'a> {
+    /// Begin docked to the given edge rather than floating at the given position.
+    #[inline]
+    pub fn docked(self, edge: Edge) -> DockContext<'a> {
+        DockContext { initial_edge: Some(edge), ..self }
+    }
+    /// The thickness of the panel while it is docked.
+    #[inline]
+    pub fn docked_size(self, size: f64) -> DockContext<'a> {
+        DockContext { docked_size: size, ..self }
+    }
+    /// While floating, also snap the panel to a grid with the given spacing.
+    /// Hold Alt while dragging to temporarily disable all snapping.
+    #[inline]
+    pub fn snap_grid(self, spacing: f64) -> DockContext<'a> {
+        DockContext { maybe_snap_grid: Some(spacing), ..self }
+    }
+    /// Draw the panel's contents. The closure is given the position and
+    /// dimensions of the area beneath the title bar.
+    #[inline]
+    pub fn content(self, content: |&mut UiContext, &mut Gl, Point, Dimensions|:'a) -> DockContext<'a> {
+        DockContext { maybe_content: Some(content), ..self }
+    }
+    #[inline]
+    pub fn label_color(self, color: Color) -> DockContext<'a> {
+        DockContext { maybe_label_color: Some(color), ..self }
+    }
+    #[inline]
+    pub fn label_font_size(self, size: u32) -> DockContext<'a> {
+        DockContext { maybe_label_font_size: Some(size), ..self }
+    }
+}
+
+pub trait DockBuilder<'a> {
+    /// A dock builder method to be implemented by the UiContext.
+    fn dock(&'a mut self, ui_id: UIID, title: &'a str) -> DockContext<'a>;
+}
+
+impl<'a> DockBuilder<'a> for UiContext {
+    fn dock(&'a mut self, ui_id: UIID, title: &'a str) -> DockContext<'a> {
+        let pos = self.flow_pos();
+        DockContext {
+            uic: self,
+            ui_id: ui_id,
+            title: title,
+            initial_edge: None,
+            docked_size: 200.0,
+            maybe_snap_grid: None,
+            pos: pos,
+            dim: [200.0, 300.0],
+            maybe_content: None,
+            maybe_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+        }
+    }
+}
+
+impl_callable!(DockContext, |Placement|:'a);
+impl_colorable!(DockContext);
+impl_frameable!(DockContext);
+impl_hoverable!(DockContext);
+impl_positionable!(DockContext);
+impl_shapeable!(DockContext);
+
+impl<'a> DockContext<'a> {
+    /// Return the panel's rect for the given placement.
+    fn rect(&self, placement: Placement) -> (Point, Dimensions) {
+        let (win_w, win_h) = (self.uic.win_w, self.uic.win_h);
+        match placement {
+            Placement::Initial => match self.initial_edge {
+                Some(edge) => docked_rect(edge, self.docked_size, win_w, win_h),
+                None => (self.pos, self.dim),
+            },
+            Placement::Floating(pos) => (pos, self.dim),
+            Placement::Docked(edge) => docked_rect(edge, self.docked_size, win_w, win_h),
+        }
+    }
+    /// Return the position of the panel while it is being dragged, snapped to the
+    /// window edges, other panels and the grid, along with the guides to display.
+    fn dragged_pos(&self, mouse_pos: Point, grab: Point) -> (Point, Vec<Guide>) {
+        let pos = vec2_sub(mouse_pos, grab);
+        match self.uic.get_modifiers().alt {
+            true => (pos, Vec::new()),
+            false => {
+                let others = self.uic.get_panel_rects(self.ui_id);
+                snap::snap(pos, self.dim, self.uic.win_w, self.uic.win_h,
+                           others.as_slice(), self.maybe_snap_grid)
+            },
+        }
+    }
+}
+
+impl<'a> ::draw::Drawable for DockContext<'a> {
+    /// Draw the panel. The `callback` is called with the new Placement whenever
+    /// the panel is docked or floated.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let (win_w, win_h) = (self.uic.win_w, self.uic.win_h);
+        // The panel only leaves its placement once the mouse has moved past the drag
+        // threshold, so that a click on the title bar doesn't re-dock it.
+        let is_moved = self.uic.is_past_drag_threshold();
+        let (prev_pos, prev_dim) = match state.interaction {
+            Interaction::Dragging(grab) if is_moved => {
+                let (pos, _) = self.dragged_pos(mouse.pos, grab);
+                (pos, self.dim)
+            },
+            _ => self.rect(state.placement),
+        };
+        let is_over_title = rectangle::is_over(prev_pos, mouse.pos, [prev_dim[0], TITLE_BAR_HEIGHT]);
+        let new_interaction = get_new_interaction(is_over_title, state.interaction, prev_pos, mouse);
+
+        // Dock or float the panel once it is dropped.
+        let placement = match (state.interaction, new_interaction) {
+            (Interaction::Dragging(grab), Interaction::Highlighted)
+            | (Interaction::Dragging(grab), Interaction::Normal) if is_moved => {
+                let placement = match edge_under(mouse.pos, win_w, win_h) {
+                    Some(edge) => Placement::Docked(edge),
+                    None => {
+                        let (pos, _) = self.dragged_pos(mouse.pos, grab);
+                        Placement::Floating(pos)
+                    },
+                };
+                match self.maybe_callback {
+                    Some(ref mut callback) => (*callback)(placement),
+                    None => (),
+                }
+                placement
+            },
+            _ => state.placement,
+        };
+
+        // Draw.
+        let (pos, dim, guides) = match new_interaction {
+            Interaction::Dragging(grab) if is_moved => {
+                let (pos, guides) = self.dragged_pos(mouse.pos, grab);
+                (pos, self.dim, guides)
+            },
+            _ => {
+                let (pos, dim) = self.rect(placement);
+                (pos, dim, Vec::new())
+            },
+        };
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            false => None,
+        };
+
+        // Preview the area the panel would dock to, otherwise show the snapping guides.
+        if let (Interaction::Dragging(_), true) = (new_interaction, is_moved) {
+            match edge_under(mouse.pos, win_w, win_h) {
+                Some(edge) => {
+                    let (d_pos, d_dim) = docked_rect(edge, self.docked_size, win_w, win_h);
+                    let preview_color = Color::new(1.0, 1.0, 1.0, 0.3) * color.highlighted();
+                    rectangle::draw(win_w, win_h, graphics, rectangle::State::Normal,
+                                    d_pos, d_dim, None, preview_color);
+                },
+                None => snap::draw_guides(win_w, win_h, graphics, guides.as_slice(), color.highlighted()),
+            }
+        }
+
+        rectangle::draw(win_w, win_h, graphics, rectangle::State::Normal,
+                        pos, dim, maybe_frame, color);
+        let title_state = match new_interaction {
+            Interaction::Normal => rectangle::State::Normal,
+            Interaction::Highlighted => rectangle::State::Highlighted,
+            Interaction::Dragging(_) => rectangle::State::Clicked,
+        };
+        let t_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+        let t_size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_small);
+        let title = self.title;
+        rectangle::draw_with_centered_label(
+            win_w, win_h, graphics, self.uic, title_state, pos, [dim[0], TITLE_BAR_HEIGHT],
+            maybe_frame, color.clicked(), title, t_size, t_color
+        );
+
+        // Contents.
+        let ui_id = self.ui_id;
+        let DockContext { // borrowck
+            ref mut uic,
+            ref mut maybe_content,
+            ..
+        } = *self;
+        match *maybe_content {
+            Some(ref mut content) => {
+                let content_pos = [pos[0] + frame_w, pos[1] + TITLE_BAR_HEIGHT];
+                let content_dim = [dim[0] - frame_w * 2.0, dim[1] - TITLE_BAR_HEIGHT - frame_w];
+                uic.begin_children(ui_id);
+                (*content)(&mut **uic, graphics, content_pos, content_dim);
+                uic.end_children();
+            },
+            None => (),
+        }
+
+        let new_state = State { interaction: new_interaction, placement: placement };
+        set_state(self.uic, self.ui_id, new_state, pos, dim);
+
+    }
+}
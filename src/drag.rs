@@ -0,0 +1,75 @@
+
+use std::any::{Any, AnyRefExt};
+use color::Color;
+use graphics;
+use graphics::Context;
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+
+/// The radius of the "not allowed" indicator.
+pub static NOT_ALLOWED_RADIUS: f64 = 8.0;
+/// The offset of a drag ghost from the mouse cursor.
+static GHOST_OFFSET: f64 = 12.0;
+/// The padding around the label of a drag ghost.
+static GHOST_PADDING: f64 = 6.0;
+
+/// A drag between widgets, begun with `UiContext::start_drag` and carrying a payload of
+/// any type to the DropTarget on which it is released.
+pub struct Drag {
+    /// The widget from which the drag began.
+    pub source: UIID,
+    /// The text shown beside the cursor while dragging.
+    pub label: String,
+    payload: Box<Any + 'static>,
+}
+
+impl Drag {
+
+    /// Constructor for a Drag.
+    pub fn new<T: 'static>(source: UIID, label: String, payload: T) -> Drag {
+        Drag { source: source, label: label, payload: box payload as Box<Any + 'static> }
+    }
+
+    /// Return the payload if it is of the type `T`.
+    pub fn payload<T: 'static>(&self) -> Option<&T> {
+        self.payload.downcast_ref::<T>()
+    }
+
+}
+
+/// Draw the ghost of a dragged item (its label in a translucent box) beside the cursor.
+pub fn draw_ghost(uic: &mut UiContext, graphics: &mut Gl, mouse_pos: Point, label: &str) {
+    let size = uic.theme.font_size_small;
+    let text_w = ::label::width(uic, size, label);
+    let pos = [mouse_pos[0] + GHOST_OFFSET, mouse_pos[1] + GHOST_OFFSET];
+    let dim = [text_w + GHOST_PADDING * 2.0, size as f64 + GHOST_PADDING * 2.0];
+    let mut color = uic.theme.shape_color;
+    color.set_a(0.75);
+    let frame = Some((1.0, uic.theme.link_color()));
+    let text_color = uic.theme.label_color;
+    let (win_w, win_h) = (uic.win_w, uic.win_h);
+    rectangle::draw_with_centered_label(win_w, win_h, graphics, uic, rectangle::State::Normal,
+                                        pos, dim, frame, color, label, size, text_color);
+}
+
+/// Draw a "not allowed" indicator (a circle with a diagonal slash) centered on
+/// the given point. Drag sources draw this beside the cursor while their dragged
+/// item is over somewhere that it cannot be dropped.
+pub fn draw_not_allowed(win_w: f64, win_h: f64, graphics: &mut Gl, center: Point) {
+    let context = Context::abs(win_w, win_h);
+    let r = NOT_ALLOWED_RADIUS;
+    let (x, y) = (center[0], center[1]);
+    let Color(red) = Color::new(0.85, 0.15, 0.15, 1.0);
+    let Color(white) = Color::white();
+    graphics::Ellipse::new(red).draw([x - r, y - r, r * 2.0, r * 2.0], &context, graphics);
+    let inner_r = r * 0.7;
+    graphics::Ellipse::new(white)
+        .draw([x - inner_r, y - inner_r, inner_r * 2.0, inner_r * 2.0], &context, graphics);
+    let d = inner_r * 0.7071;
+    graphics::Line::round(red, r * 0.15).draw([x - d, y - d, x + d, y + d], &context, graphics);
+}
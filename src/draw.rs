@@ -1,5 +1,9 @@
-
+use std::num::Float;
+use std::ptr;
+use dimensions::Dimensions;
+use gl;
 use opengl_graphics::Gl;
+use point::Point;
 
 /// A trait to be implemented for all
 /// drawable widget contexts.
@@ -7,3 +11,145 @@ pub trait Drawable {
     fn draw(&mut self, graphics: &mut Gl);
 }
 
+/// Restrict all following drawing to the given rectangle (in window co-ordinates)
+/// until `clear_clip` is called. This replaces any clip already set, so widgets should
+/// clip with `UiContext::push_clip` and `pop_clip` in order to nest within one another.
+pub fn set_clip(win_h: f64, pos: Point, dim: Dimensions) {
+    // GL measures the scissor box from the bottom left of the window.
+    let y = win_h - (pos[1] + dim[1]);
+    unsafe {
+        gl::Enable(gl::SCISSOR_TEST);
+        gl::Scissor(pos[0] as i32, y as i32, dim[0] as i32, dim[1] as i32);
+    }
+}
+
+/// Remove any clipping rectangle set with `set_clip`.
+pub fn clear_clip() {
+    unsafe { gl::Disable(gl::SCISSOR_TEST); }
+}
+
+/// A stack of clipping rectangles, each of which is restricted to those beneath it so
+/// that clipping widgets may be nested, i.e. a Canvas within a ScrollArea.
+pub struct ClipStack {
+    rects: Vec<(Point, Dimensions)>,
+}
+
+impl ClipStack {
+    /// Constructor for an empty ClipStack.
+    pub fn new() -> ClipStack {
+        ClipStack { rects: Vec::new() }
+    }
+
+    /// Restrict all following drawing to the intersection of the given rectangle with the
+    /// current clip (if any) until the matching `pop`.
+    pub fn push(&mut self, win_h: f64, pos: Point, dim: Dimensions) {
+        let (pos, dim) = match self.current() {
+            Some((c_pos, c_dim)) => {
+                let x = pos[0].max(c_pos[0]);
+                let y = pos[1].max(c_pos[1]);
+                let w = ((pos[0] + dim[0]).min(c_pos[0] + c_dim[0]) - x).max(0.0);
+                let h = ((pos[1] + dim[1]).min(c_pos[1] + c_dim[1]) - y).max(0.0);
+                ([x, y], [w, h])
+            },
+            None => (pos, dim),
+        };
+        self.rects.push((pos, dim));
+        set_clip(win_h, pos, dim);
+    }
+
+    /// Remove the latest clip, restoring the one beneath it (if any).
+    pub fn pop(&mut self, win_h: f64) {
+        self.rects.pop();
+        match self.current() {
+            Some((pos, dim)) => set_clip(win_h, pos, dim),
+            None => clear_clip(),
+        }
+    }
+
+    /// Return the rectangle to which drawing is currently restricted (if any).
+    pub fn current(&self) -> Option<(Point, Dimensions)> {
+        self.rects.last().map(|&rect| rect)
+    }
+
+    /// Remove every clip without touching the GL state, i.e. at the start of a frame.
+    pub fn clear(&mut self) {
+        self.rects.clear();
+    }
+}
+
+
+/// A block of RGBA pixels read back from the window, with rows ordered from top to bottom.
+#[deriving(Show, Clone)]
+pub struct Image {
+    pub width: uint,
+    pub height: uint,
+    pub data: Vec<u8>,
+}
+
+/// Read back the pixels within the given rectangle (in window co-ordinates) of what
+/// has been drawn so far. The rectangle is clamped to the window.
+pub fn read_pixels(win_w: f64, win_h: f64, pos: Point, dim: Dimensions) -> Image {
+    let x = pos[0].max(0.0);
+    let y = pos[1].max(0.0);
+    let w = ((pos[0] + dim[0]).min(win_w) - x).max(0.0) as uint;
+    let h = ((pos[1] + dim[1]).min(win_h) - y).max(0.0) as uint;
+    let mut flipped: Vec<u8> = Vec::from_elem(w * h * 4u, 0u8);
+    // GL measures from the bottom left of the window.
+    let gl_y = win_h - (y + h as f64);
+    unsafe {
+        gl::ReadPixels(x as i32, gl_y as i32, w as i32, h as i32, gl::RGBA, gl::UNSIGNED_BYTE,
+                       flipped.as_mut_ptr() as *mut gl::types::GLvoid);
+    }
+    let row_len = w * 4u;
+    let mut data = Vec::with_capacity(w * h * 4u);
+    for row in range(0u, h).rev() {
+        data.push_all(flipped.slice(row * row_len, (row + 1u) * row_len));
+    }
+    Image { width: w, height: h, data: data }
+}
+
+/// A framebuffer of the window's size to which all drawing is redirected for as long as
+/// it lives, so that widgets may be drawn offscreen (see `UiContext::render_widget_to_image`).
+/// The previously bound framebuffer is restored when it is dropped.
+pub struct Offscreen {
+    fbo: gl::types::GLuint,
+    texture: gl::types::GLuint,
+    prev_fbo: gl::types::GLint,
+}
+
+impl Offscreen {
+
+    /// Create and bind a transparent framebuffer of the given size.
+    pub fn new(win_w: f64, win_h: f64) -> Offscreen {
+        let (mut fbo, mut texture, mut prev_fbo) = (0, 0, 0);
+        unsafe {
+            gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut prev_fbo);
+            gl::GenTextures(1, &mut texture);
+            gl::BindTexture(gl::TEXTURE_2D, texture);
+            gl::TexImage2D(gl::TEXTURE_2D, 0, gl::RGBA as i32, win_w as i32, win_h as i32, 0,
+                           gl::RGBA, gl::UNSIGNED_BYTE, ptr::null());
+            gl::GenFramebuffers(1, &mut fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0,
+                                     gl::TEXTURE_2D, texture, 0);
+            // Clear the whole framebuffer, whatever the current clip.
+            let is_clipped = gl::IsEnabled(gl::SCISSOR_TEST) == gl::TRUE;
+            gl::Disable(gl::SCISSOR_TEST);
+            gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+            if is_clipped { gl::Enable(gl::SCISSOR_TEST) }
+        }
+        Offscreen { fbo: fbo, texture: texture, prev_fbo: prev_fbo }
+    }
+
+}
+
+impl Drop for Offscreen {
+    fn drop(&mut self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.prev_fbo as gl::types::GLuint);
+            gl::DeleteFramebuffers(1, &self.fbo);
+            gl::DeleteTextures(1, &self.texture);
+        }
+    }
+}
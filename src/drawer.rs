@@ -0,0 +1,292 @@
+use std::num::Float;
+
+use color::Color;
+use dimensions::Dimensions;
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use point::Point;
+use position::Edge;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use utils::{
+    clamp,
+    smooth_step,
+};
+use widget::Widget::Drawer;
+
+/// The thickness of the tab used to toggle the drawer.
+static HANDLE_THICKNESS: f64 = 20.0;
+/// The length of the tab used to toggle the drawer.
+static HANDLE_LENGTH: f64 = 60.0;
+/// The distance from the window edge within which hovering opens the drawer.
+static HOVER_MARGIN: f64 = 4.0;
+
+/// Represents the interaction with the drawer's handle.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    Highlighted,
+    Clicked,
+}
+
+/// Represents the state of the Drawer widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct State {
+    interaction: Interaction,
+    /// Whether or not the drawer is (or is becoming) open.
+    is_open: bool,
+    /// The linear progress of the slide animation (0.0 is closed, 1.0 is open).
+    progress: f64,
+}
+
+impl Interaction {
+    /// Return the associated Rectangle state.
+    fn as_rectangle_state(&self) -> rectangle::State {
+        match self {
+            &Interaction::Normal => rectangle::State::Normal,
+            &Interaction::Highlighted => rectangle::State::Highlighted,
+            &Interaction::Clicked => rectangle::State::Clicked,
+        }
+    }
+}
+
+widget_fns!(Drawer, State, Drawer(State {
+    interaction: Interaction::Normal,
+    is_open: false,
+    progress: 0.0,
+}));
+
+/// Check the current interaction with the handle.
+fn get_new_interaction(is_over: bool,
+                       prev: Interaction,
+                       mouse: Mouse) -> Interaction {
+    use mouse::ButtonState::{Down, Up};
+    use self::Interaction::{Normal, Highlighted, Clicked};
+    match (is_over, prev, mouse.left) {
+        (true,  Normal,  Down) => Normal,
+        (true,  _,       Down) => Clicked,
+        (true,  _,       Up)   => Highlighted,
+        (false, Clicked, Down) => Clicked,
+        _                      => Normal,
+    }
+}
+
+/// Return the position and dimensions of the drawer panel for the given edge.
+fn panel_rect(edge: Edge, length: f64, offset: f64,
+              win_w: f64, win_h: f64) -> (Point, Dimensions) {
+    match edge {
+        Edge::Left   => ([offset - length, 0.0], [length, win_h]),
+        Edge::Right  => ([win_w - offset, 0.0], [length, win_h]),
+        Edge::Top    => ([0.0, offset - length], [win_w, length]),
+        Edge::Bottom => ([0.0, win_h - offset], [win_w, length]),
+    }
+}
+
+/// Return the position and dimensions of the handle attached to the panel's inner side.
+fn handle_rect(edge: Edge, panel_pos: Point, panel_dim: Dimensions) -> (Point, Dimensions) {
+    match edge {
+        Edge::Left => ([panel_pos[0] + panel_dim[0], (panel_dim[1] - HANDLE_LENGTH) / 2.0],
+                       [HANDLE_THICKNESS, HANDLE_LENGTH]),
+        Edge::Right => ([panel_pos[0] - HANDLE_THICKNESS, (panel_dim[1] - HANDLE_LENGTH) / 2.0],
+                        [HANDLE_THICKNESS, HANDLE_LENGTH]),
+        Edge::Top => ([(panel_dim[0] - HANDLE_LENGTH) / 2.0, panel_pos[1] + panel_dim[1]],
+                      [HANDLE_LENGTH, HANDLE_THICKNESS]),
+        Edge::Bottom => ([(panel_dim[0] - HANDLE_LENGTH) / 2.0, panel_pos[1] - HANDLE_THICKNESS],
+                         [HANDLE_LENGTH, HANDLE_THICKNESS]),
+    }
+}
+
+/// Return the rect in which the drawer claims the mouse: the handle, along with the part of
+/// the panel within the window while the drawer is at all open.
+fn placed_rect(panel_pos: Point, panel_dim: Dimensions, handle_pos: Point,
+               handle_dim: Dimensions, progress: f64,
+               win_w: f64, win_h: f64) -> (Point, Dimensions) {
+    if progress <= 0.0 { return (handle_pos, handle_dim) }
+    let start = [panel_pos[0].max(0.0).min(handle_pos[0]),
+                 panel_pos[1].max(0.0).min(handle_pos[1])];
+    let end = [(panel_pos[0] + panel_dim[0]).min(win_w).max(handle_pos[0] + handle_dim[0]),
+               (panel_pos[1] + panel_dim[1]).min(win_h).max(handle_pos[1] + handle_dim[1])];
+    (start, [end[0] - start[0], end[1] - start[1]])
+}
+
+/// Is the mouse close enough to the given window edge to open the drawer on hover.
+fn is_near_edge(edge: Edge, mouse_pos: Point, win_w: f64, win_h: f64) -> bool {
+    match edge {
+        Edge::Left   => mouse_pos[0] <= HOVER_MARGIN,
+        Edge::Right  => mouse_pos[0] >= win_w - HOVER_MARGIN,
+        Edge::Top    => mouse_pos[1] <= HOVER_MARGIN,
+        Edge::Bottom => mouse_pos[1] >= win_h - HOVER_MARGIN,
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct DrawerContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    edge: Edge,
+    length: f64,
+    duration: f64,
+    open_on_hover: bool,
+    maybe_push: Option<&'a mut f64>,
+    maybe_content: Option<|&mut UiContext, &mut Gl, Point, Dimensions|:'a>,
+    maybe_callback: Option<|bool|:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+}
+
+impl<'a> DrawerContext<'a> {
+    /// The distance the drawer extends from its edge when fully open.
+    #[inline]
+    pub fn length(self, length: f64) -> DrawerContext<'a> {
+        DrawerContext { length: length, ..self }
+    }
+    /// The time in seconds taken to slide fully open or closed.
+    #[inline]
+    pub fn duration(self, secs: f64) -> DrawerContext<'a> {
+        DrawerContext { duration: secs, ..self }
+    }
+    /// Open the drawer when the mouse touches its window edge and close it
+    /// again once the mouse leaves the drawer.
+    #[inline]
+    pub fn open_on_hover(self, open_on_hover: bool) -> DrawerContext<'a> {
+        DrawerContext { open_on_hover: open_on_hover, ..self }
+    }
+    /// Push rather than overlay content. The distance by which the drawer currently
+    /// extends into the window is written to `offset` so that the caller can shift
+    /// the rest of their layout by it.
+    #[inline]
+    pub fn push(self, offset: &'a mut f64) -> DrawerContext<'a> {
+        DrawerContext { maybe_push: Some(offset), ..self }
+    }
+    /// Draw the drawer's contents. The closure is given the position and
+    /// dimensions of the panel's area within its frame.
+    #[inline]
+    pub fn content(self, content: |&mut UiContext, &mut Gl, Point, Dimensions|:'a) -> DrawerContext<'a> {
+        DrawerContext { maybe_content: Some(content), ..self }
+    }
+}
+
+pub trait DrawerBuilder<'a> {
+    /// A drawer builder method to be implemented by the UiContext.
+    fn drawer(&'a mut self, ui_id: UIID, edge: Edge) -> DrawerContext<'a>;
+}
+
+impl<'a> DrawerBuilder<'a> for UiContext {
+
+    /// Create a drawer context to be built upon.
+    fn drawer(&'a mut self, ui_id: UIID, edge: Edge) -> DrawerContext<'a> {
+        DrawerContext {
+            uic: self,
+            ui_id: ui_id,
+            edge: edge,
+            length: 200.0,
+            duration: 0.25,
+            open_on_hover: false,
+            maybe_push: None,
+            maybe_content: None,
+            maybe_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+        }
+    }
+
+}
+
+impl_callable!(DrawerContext, |bool|:'a);
+impl_colorable!(DrawerContext);
+impl_frameable!(DrawerContext);
+impl_hoverable!(DrawerContext);
+
+impl<'a> ::draw::Drawable for DrawerContext<'a> {
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let (win_w, win_h) = (self.uic.win_w, self.uic.win_h);
+
+        // Determine where the drawer was left at the end of the previous frame.
+        let offset = smooth_step(state.progress) * self.length;
+        let (panel_pos, panel_dim) = panel_rect(self.edge, self.length, offset, win_w, win_h);
+        let (handle_pos, handle_dim) = handle_rect(self.edge, panel_pos, panel_dim);
+        let is_over_handle = rectangle::is_over(handle_pos, mouse.pos, handle_dim);
+        let is_over_panel = state.progress > 0.0
+            && rectangle::is_over(panel_pos, mouse.pos, panel_dim);
+        let new_interaction = get_new_interaction(is_over_handle, state.interaction, mouse);
+
+        // Toggle on handle release, otherwise follow the hover behaviour if enabled.
+        let is_open = match (is_over_handle, state.interaction, new_interaction) {
+            (true, Interaction::Clicked, Interaction::Highlighted) => !state.is_open,
+            _ => if !self.open_on_hover { state.is_open }
+                 else if is_near_edge(self.edge, mouse.pos, win_w, win_h) { true }
+                 else if state.is_open && !is_over_panel && !is_over_handle { false }
+                 else { state.is_open },
+        };
+
+        // Callback.
+        if is_open != state.is_open {
+            match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(is_open),
+                None => (),
+            }
+        }
+
+        // Step the slide animation towards its target.
+        let step = if self.duration > 0.0 { self.uic.frame_dt() / self.duration } else { 1.0 };
+        let progress = match is_open {
+            true => clamp(state.progress + step, 0.0, 1.0),
+            false => clamp(state.progress - step, 0.0, 1.0),
+        };
+        let offset = smooth_step(progress) * self.length;
+        match self.maybe_push {
+            Some(ref mut push) => **push = offset,
+            None => (),
+        }
+
+        // Draw.
+        let (panel_pos, panel_dim) = panel_rect(self.edge, self.length, offset, win_w, win_h);
+        let (handle_pos, handle_dim) = handle_rect(self.edge, panel_pos, panel_dim);
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            false => None,
+        };
+        if progress > 0.0 {
+            rectangle::draw(win_w, win_h, graphics, rectangle::State::Normal,
+                            panel_pos, panel_dim, maybe_frame, color);
+            let ui_id = self.ui_id;
+            let DrawerContext { // borrowck
+                ref mut uic,
+                ref mut maybe_content,
+                ..
+            } = *self;
+            match *maybe_content {
+                Some(ref mut content) => {
+                    let inner_pos = [panel_pos[0] + frame_w, panel_pos[1] + frame_w];
+                    let inner_dim = [panel_dim[0] - frame_w * 2.0, panel_dim[1] - frame_w * 2.0];
+                    uic.begin_children(ui_id);
+                    (*content)(&mut **uic, graphics, inner_pos, inner_dim);
+                    uic.end_children();
+                },
+                None => (),
+            }
+        }
+        rectangle::draw(win_w, win_h, graphics, new_interaction.as_rectangle_state(),
+                        handle_pos, handle_dim, maybe_frame, color);
+
+        let new_state = State {
+            interaction: new_interaction,
+            is_open: is_open,
+            progress: progress,
+        };
+        let (placed_pos, placed_dim) = placed_rect(panel_pos, panel_dim, handle_pos, handle_dim,
+                                                   progress, win_w, win_h);
+        set_state(self.uic, self.ui_id, new_state, placed_pos, placed_dim);
+
+    }
+}
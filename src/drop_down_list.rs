@@ -1,13 +1,25 @@
+use std::fmt::Show;
+use std::num::Float;
 use color::Color;
 use dimensions::Dimensions;
+use focus;
+use input::keyboard::Key;
+use input::keyboard::Key::Backspace;
+use label;
+use label::FontSize;
 use mouse::Mouse;
 use opengl_graphics::Gl;
+use overlay::Primitive;
 use point::Point;
 use rectangle;
 use ui_context::{
     UIID,
     UiContext,
 };
+use utils::{
+    clamp,
+    smooth_step,
+};
 use vecmath::vec2_add;
 use widget::Widget::DropDownList;
 
@@ -15,106 +27,271 @@ use widget::Widget::DropDownList;
 pub type Idx = uint;
 pub type Len = uint;
 
-/// Represents the state of the menu.
+/// The width of the scrollbar shown when only some of the items are visible.
+static SCROLLBAR_WIDTH: f64 = 8.0;
+
+/// The time (in seconds) for which an item must be hovered before its sub-menu opens.
+static SUB_MENU_DELAY: f64 = 0.25;
+
+/// Represents the state of the DropDownList widget.
+#[deriving(PartialEq, Clone, Copy)]
+pub struct State {
+    menu: Menu,
+    /// The linear progress of the open animation (0.0 is closed, 1.0 is open).
+    progress: f64,
+    /// The font size fitted to the widest item, along with the number of items, the
+    /// requested font size and the width it was fitted for, so that the items are only
+    /// measured again when one of those changes.
+    maybe_fitted: Option<(FontSize, Len, FontSize, f64)>,
+}
+
+/// Represents the state of the menu. Open stores the index of the first visible item.
 #[deriving(PartialEq, Clone, Copy)]
-pub enum State {
+pub enum Menu {
     Closed(DrawState),
-    Open(DrawState),
+    Open(DrawState, Idx, SubMenu),
 }
 
-/// Represents the state of the DropDownList widget.
+/// Represents the state of the sub-menu of an open list.
+#[deriving(PartialEq, Clone, Copy)]
+pub enum SubMenu {
+    Closed,
+    /// The item at the index has been hovered since the time (in seconds).
+    Pending(Idx, f64),
+    /// The sub-menu of the item at the index is open.
+    Open(Idx, DrawState),
+}
+
+/// Represents the interaction with the closed or open list.
 #[deriving(PartialEq, Clone, Copy)]
 pub enum DrawState {
     Normal,
     Highlighted(Idx, Len),
     Clicked(Idx, Len),
+    /// The scrollbar of the open list is being dragged.
+    Scrolling,
+    /// The mouse was pressed outside of the open list. Releasing it outside closes the list.
+    ClickedOutside,
 }
 
 impl DrawState {
     /// Translate the DropDownList's DrawState to the equivalent rectangle::State.
     fn as_rect_state(&self) -> rectangle::State {
         match self {
-            &DrawState::Normal => rectangle::State::Normal,
+            &DrawState::Normal | &DrawState::ClickedOutside => rectangle::State::Normal,
             &DrawState::Highlighted(_, _) => rectangle::State::Highlighted,
-            &DrawState::Clicked(_, _) => rectangle::State::Clicked,
+            &DrawState::Clicked(_, _) | &DrawState::Scrolling => rectangle::State::Clicked,
         }
     }
 }
 
-impl State {
-    /// Translate the DropDownList's State to the equivalent rectangle::State.
+impl Menu {
+    /// Translate the DropDownList's Menu to the equivalent rectangle::State.
     fn as_rect_state(&self) -> rectangle::State {
         match self {
-            &State::Open(draw_state) | &State::Closed(draw_state) => draw_state.as_rect_state(),
+            &Menu::Open(draw_state, _, _) | &Menu::Closed(draw_state) => draw_state.as_rect_state(),
         }
     }
 }
 
-widget_fns!(DropDownList, State, DropDownList(State::Closed(DrawState::Normal)));
+/// The kind of each item within the list.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum ItemKind {
+    Normal,
+    /// Shown greyed and may not be selected.
+    Disabled,
+    /// Shown as a line dividing the items either side. Its string is not displayed.
+    Separator,
+}
+
+/// Return whether or not the item at the given index may be highlighted and selected.
+fn is_selectable(maybe_kinds: Option<&[ItemKind]>, idx: Idx) -> bool {
+    match maybe_kinds {
+        Some(kinds) if idx < kinds.len() => kinds[idx] == ItemKind::Normal,
+        _ => true,
+    }
+}
+
+/// The direction in which the list opens from its closed position.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Direction {
+    Down,
+    Up,
+}
+
+widget_fns!(DropDownList, State, DropDownList(State {
+    menu: Menu::Closed(DrawState::Normal),
+    progress: 0.0,
+    maybe_fitted: None,
+}));
+
+/// Return the number of sub items of the item at the given index.
+fn num_sub_items<T>(maybe_sub_items: Option<&[Vec<T>]>, idx: Idx) -> Len {
+    match maybe_sub_items {
+        Some(sub_items) if idx < sub_items.len() => sub_items[idx].len(),
+        _ => 0u,
+    }
+}
+
+/// Return the nearest item after (or before) the given one that may be selected from the
+/// closed list, or the first (or last) such item if none is given.
+fn next_selectable<T>(maybe_kinds: Option<&[ItemKind]>,
+                      maybe_sub_items: Option<&[Vec<T>]>,
+                      from: Option<Idx>,
+                      len: Len,
+                      forwards: bool) -> Option<Idx> {
+    let candidates: Vec<Idx> = match (from, forwards) {
+        (Some(idx), true) => range(idx + 1u, len).collect(),
+        (Some(idx), false) => range(0u, idx).rev().collect(),
+        (None, true) => range(0u, len).collect(),
+        (None, false) => range(0u, len).rev().collect(),
+    };
+    candidates.into_iter().find(|&idx| {
+        is_selectable(maybe_kinds, idx) && num_sub_items(maybe_sub_items, idx) == 0u
+    })
+}
+
+/// Return the position of the sub-menu of the item in the given visible row. The
+/// sub-menu opens to the left instead if it would run off the right of the window.
+fn sub_menu_pos(open_pos: Point, dim: Dimensions, row: Len, num_sub: Len,
+                frame_w: f64, win_w: f64, win_h: f64) -> Point {
+    let right = open_pos[0] + dim[0] - frame_w;
+    let x = match right + dim[0] > win_w {
+        true => open_pos[0] - dim[0] + frame_w,
+        false => right,
+    };
+    let y = open_pos[1] + (dim[1] - frame_w) * row as f64;
+    let sub_h = (dim[1] - frame_w) * num_sub as f64 + frame_w;
+    [x, y.min(win_h - sub_h).max(0.0)]
+}
 
-/// Is the cursor currently over the
+/// Return the position of the top of the open list showing `num_visible` items. When
+/// opening upward, the last visible item takes the place of the closed list.
+fn open_pos(pos: Point, dim: Dimensions, num_visible: Len, frame_w: f64, direction: Direction) -> Point {
+    match direction {
+        Direction::Down => pos,
+        Direction::Up => {
+            let items_above = if num_visible > 0u { num_visible - 1u } else { 0u };
+            [pos[0], pos[1] - (dim[1] - frame_w) * items_above as f64]
+        },
+    }
+}
+
+/// Return the rect of the scrollbar of an open list showing `num_visible` items.
+fn scrollbar_rect(open_pos: Point, dim: Dimensions, num_visible: Len, frame_w: f64) -> (Point, Dimensions) {
+    let list_h = (dim[1] - frame_w) * num_visible as f64 + frame_w;
+    ([open_pos[0] + dim[0] - SCROLLBAR_WIDTH, open_pos[1]], [SCROLLBAR_WIDTH, list_h])
+}
+
+/// Determine the new DrawState of an open sub-menu while the mouse is over the item at `idx`.
+fn get_new_sub_state(idx: Idx, prev: DrawState, len: Len, mouse: Mouse) -> DrawState {
+    use self::DrawState::{Normal, Clicked, Highlighted};
+    use mouse::ButtonState::{Down, Up};
+    match (prev, mouse.left) {
+        (Highlighted(_, _), Down) => Clicked(idx, len),
+        (Clicked(p_idx, _), Down) => Clicked(p_idx, len),
+        (_,                 Up)   => Highlighted(idx, len),
+        _                         => Normal,
+    }
+}
+
+/// Is the cursor currently over the list. While open, the visible row under the
+/// cursor is mapped to the index of its item through the first visible index.
 fn is_over(pos: Point,
+           open_pos: Point,
            mouse_pos: Point,
            dim: Dimensions,
-           state: State,
-           len: Len) -> Option<Idx> {
+           state: Menu,
+           num_visible: Len) -> Option<Idx> {
     match state {
-        State::Closed(_) => {
+        Menu::Closed(_) => {
             match rectangle::is_over(pos, mouse_pos, dim) {
                 false => None,
                 true => Some(0u),
             }
         },
-        State::Open(_) => {
-            let total_h = dim[1] * len as f64;
-            match rectangle::is_over(pos, mouse_pos, [dim[0], total_h]) {
+        Menu::Open(_, first, _) => {
+            let total_h = dim[1] * num_visible as f64;
+            match rectangle::is_over(open_pos, mouse_pos, [dim[0], total_h]) {
                 false => None,
-                true => Some((((mouse_pos[1] - pos[1]) / total_h) * len as f64) as uint),
+                true => Some(first + (((mouse_pos[1] - open_pos[1]) / total_h) * num_visible as f64) as uint),
             }
         },
     }
 }
 
-/// Determine and return the new State by comparing the mouse state
-/// and position to the previous State.
+/// Determine and return the new Menu by comparing the mouse state
+/// and position to the previous Menu.
 fn get_new_state(is_over_idx: Option<Idx>,
+                 is_over_unselectable: bool,
+                 is_over_scrollbar: bool,
+                 is_multi_select: bool,
                  len: Len,
-                 state: State,
-                 mouse: Mouse) -> State {
-    use self::DrawState::{Normal, Clicked, Highlighted};
+                 state: Menu,
+                 mouse: Mouse) -> Menu {
+    use self::DrawState::{Normal, Clicked, Highlighted, Scrolling, ClickedOutside};
     use mouse::ButtonState::{Down, Up};
     match state {
-        State::Closed(draw_state) => {
+        Menu::Closed(draw_state) => {
             match is_over_idx {
                 Some(_) => {
                     match (draw_state, mouse.left) {
-                        (Normal,            Down) => State::Closed(Normal),
+                        (Normal,            Down) => Menu::Closed(Normal),
                         (Normal,            Up)   |
-                        (Highlighted(_, _), Up)   => State::Closed(Highlighted(0u, len)),
-                        (Highlighted(_, _), Down) => State::Closed(Clicked(0u, len)),
-                        (Clicked(_, _),     Down) => State::Closed(Clicked(0u, len)),
-                        (Clicked(_, _),     Up)   => State::Open(Normal),
+                        (Highlighted(_, _), Up)   => Menu::Closed(Highlighted(0u, len)),
+                        (Highlighted(_, _), Down) => Menu::Closed(Clicked(0u, len)),
+                        (Clicked(_, _),     Down) => Menu::Closed(Clicked(0u, len)),
+                        (Clicked(_, _),     Up)   => Menu::Open(Normal, 0u, SubMenu::Closed),
+                        (Scrolling,         _)    |
+                        (ClickedOutside,    _)    => Menu::Closed(Normal),
                     }
                 },
-                None => State::Closed(Normal),
+                None => Menu::Closed(Normal),
             }
         },
-        State::Open(draw_state) => {
+        Menu::Open(draw_state, first, sub) => {
+            if is_over_scrollbar {
+                return match (draw_state, mouse.left) {
+                    (Clicked(_, _), Down) | (ClickedOutside, Down) => Menu::Open(draw_state, first, sub),
+                    (_,             Down) => Menu::Open(Scrolling, first, sub),
+                    (_,             Up)   => Menu::Open(Normal, first, sub),
+                }
+            }
+            if is_over_unselectable {
+                return match (draw_state, mouse.left) {
+                    (Clicked(_, _), Down) | (Scrolling, Down) |
+                    (ClickedOutside, Down) => Menu::Open(draw_state, first, sub),
+                    _ => Menu::Open(Normal, first, sub),
+                }
+            }
             match is_over_idx {
                 Some(idx) => {
                     match (draw_state, mouse.left) {
-                        (Normal,            Down) => State::Open(Normal),
+                        (Normal,            Down) => Menu::Open(Normal, first, sub),
                         (Normal,            Up)   |
-                        (Highlighted(_, _), Up)   => State::Open(Highlighted(idx, len)),
-                        (Highlighted(_, _), Down) => State::Open(Clicked(idx, len)),
-                        (Clicked(p_idx, _), Down) => State::Open(Clicked(p_idx, len)),
-                        (Clicked(_, _),     Up)   => State::Closed(Normal),
+                        (Highlighted(_, _), Up)   |
+                        (Scrolling,         Up)   => Menu::Open(Highlighted(idx, len), first, sub),
+                        (Highlighted(_, _), Down) => Menu::Open(Clicked(idx, len), first, sub),
+                        (Clicked(p_idx, _), Down) => Menu::Open(Clicked(p_idx, len), first, sub),
+                        (Clicked(_, _),     Up)   => match is_multi_select {
+                            true => Menu::Open(Highlighted(idx, len), first, sub),
+                            false => Menu::Closed(Normal),
+                        },
+                        (Scrolling,         Down) => Menu::Open(Scrolling, first, sub),
+                        (ClickedOutside,    Down) => Menu::Open(ClickedOutside, first, sub),
+                        (ClickedOutside,    Up)   => Menu::Open(Highlighted(idx, len), first, sub),
                     }
                 },
+                // The list only closes when both pressed and released outside of it. Releasing
+                // a press that began on an item outside of the list doesn't select the item.
                 None => {
                     match (draw_state, mouse.left) {
-                        (Highlighted(p_idx, _), Up) => State::Open(Highlighted(p_idx, len)),
-                        _ => State::Closed(Normal),
+                        (Clicked(_, _),  Down) |
+                        (Scrolling,      Down) |
+                        (ClickedOutside, Down) => Menu::Open(draw_state, first, sub),
+                        (ClickedOutside, Up)   => Menu::Closed(Normal),
+                        (_,              Down) => Menu::Open(ClickedOutside, first, sub),
+                        (_,              Up)   => Menu::Open(Normal, first, sub),
                     }
                 },
             }
@@ -123,80 +300,412 @@ fn get_new_state(is_over_idx: Option<Idx>,
 }
 
 /// A context on which the builder pattern can be implemented.
-pub struct DropDownListContext<'a> {
+pub struct DropDownListContext<'a, T:'a> {
     uic: &'a mut UiContext,
     ui_id: UIID,
-    strings: &'a mut Vec<String>,
+    items: &'a mut Vec<T>,
     selected: &'a mut Option<Idx>,
     pos: Point,
     dim: Dimensions,
-    maybe_callback: Option<|&mut Option<Idx>, Idx, String|:'a>,
+    maybe_direction: Option<Direction>,
+    maybe_max_visible: Option<Len>,
+    maybe_item_kinds: Option<&'a [ItemKind]>,
+    is_filterable: bool,
+    maybe_checked: Option<&'a mut Vec<bool>>,
+    maybe_sub_items: Option<&'a [Vec<T>]>,
+    open_duration: f64,
+    maybe_callback: Option<|&mut Option<Idx>, &[Idx], &T|:'a>,
+    maybe_reorder_callback: Option<|&mut Vec<T>, Idx, Idx|:'a>,
     maybe_color: Option<Color>,
     maybe_frame: Option<f64>,
     maybe_frame_color: Option<Color>,
     maybe_label: Option<&'a str>,
     maybe_label_color: Option<Color>,
     maybe_label_font_size: Option<u32>,
+    maybe_label_min_font_size: Option<u32>,
+    is_enabled: bool,
+    is_focusable: bool,
+    depth: i32,
 }
 
-pub trait DropDownListBuilder<'a> {
+impl<'a, T> DropDownListContext<'a, T> {
+    /// Always open the list in the given direction. By default the list opens upward
+    /// only when opening downward would run off the bottom of the window.
+    #[inline]
+    pub fn open_direction(self, direction: Direction) -> DropDownListContext<'a, T> {
+        DropDownListContext { maybe_direction: Some(direction), ..self }
+    }
+    /// Show at most `n` items at once while open. The rest of the list may be
+    /// reached with the mouse wheel or by dragging the scrollbar.
+    #[inline]
+    pub fn max_visible_items(self, n: Len) -> DropDownListContext<'a, T> {
+        DropDownListContext { maybe_max_visible: Some(n), ..self }
+    }
+    /// The kind of each item, in the same order as the items. Items beyond the
+    /// end of the slice are Normal.
+    #[inline]
+    pub fn item_kinds(self, kinds: &'a [ItemKind]) -> DropDownListContext<'a, T> {
+        DropDownListContext { maybe_item_kinds: Some(kinds), ..self }
+    }
+    /// Filter the open list by the text typed while it is open. Only the items containing
+    /// the typed text are shown. The typed text is shown in place of the closed list.
+    #[inline]
+    pub fn filterable(self, filterable: bool) -> DropDownListContext<'a, T> {
+        DropDownListContext { is_filterable: filterable, ..self }
+    }
+    /// Allow the list to take keyboard focus when pressed or tabbed to. While focused and
+    /// closed, the list shows a focus ring and the Up and Down keys select the neighbouring
+    /// item, calling the `callback` with its path.
+    #[inline]
+    pub fn focusable(self, focusable: bool) -> DropDownListContext<'a, T> {
+        DropDownListContext { is_focusable: focusable, ..self }
+    }
+    /// Allow the selected item to be moved with Ctrl+Up / Ctrl+Down while the list is
+    /// focused (see `focusable`) and closed. As with a ReorderList's `callback`, the
+    /// `callback` is given the items along with the index the item was moved `from` and
+    /// `to`, and should move it (along with any kinds or sub items given for it). The
+    /// selection follows the item.
+    #[inline]
+    pub fn reorderable(self, callback: |&mut Vec<T>, Idx, Idx|:'a)
+                       -> DropDownListContext<'a, T> {
+        DropDownListContext { maybe_reorder_callback: Some(callback), ..self }
+    }
+    /// Allow any number of items to be selected. Each item shows whether or not it is
+    /// checked, and clicking an item toggles it without closing the list. The `callback`
+    /// is called with the path to each item as it is toggled.
+    #[inline]
+    pub fn multi_select(self, checked: &'a mut Vec<bool>) -> DropDownListContext<'a, T> {
+        DropDownListContext { maybe_checked: Some(checked), ..self }
+    }
+    /// The sub items of each item, in the same order as the items. Hovering an item with
+    /// sub items opens them in a list beside it. The `callback` is given the path of
+    /// indices to the selected item, i.e. `[item_idx, sub_item_idx]` for a sub item.
+    #[inline]
+    pub fn sub_items(self, sub_items: &'a [Vec<T>]) -> DropDownListContext<'a, T> {
+        DropDownListContext { maybe_sub_items: Some(sub_items), ..self }
+    }
+    /// The time (in seconds) taken for the list to grow open or shrink closed.
+    /// A duration of 0.0 opens and closes the list within a single frame.
+    #[inline]
+    pub fn open_duration(self, secs: f64) -> DropDownListContext<'a, T> {
+        DropDownListContext { open_duration: secs, ..self }
+    }
+}
+
+pub trait DropDownListBuilder<'a, T: Show> {
     /// A dropdownlist builder method to be implemented by the UiContext.
-    fn drop_down_list(&'a mut self, ui_id: UIID, strings: &'a mut Vec<String>,
-                      selected: &'a mut Option<Idx>) -> DropDownListContext<'a>;
+    /// Each item is labelled with its `Show` output.
+    fn drop_down_list(&'a mut self, ui_id: UIID, items: &'a mut Vec<T>,
+                      selected: &'a mut Option<Idx>) -> DropDownListContext<'a, T>;
 }
 
-impl<'a> DropDownListBuilder<'a> for UiContext {
-    fn drop_down_list(&'a mut self, ui_id: UIID, strings: &'a mut Vec<String>,
-                      selected: &'a mut Option<Idx>) -> DropDownListContext<'a> {
+impl<'a, T: Show> DropDownListBuilder<'a, T> for UiContext {
+    fn drop_down_list(&'a mut self, ui_id: UIID, items: &'a mut Vec<T>,
+                      selected: &'a mut Option<Idx>) -> DropDownListContext<'a, T> {
+        let pos = self.flow_pos();
         DropDownListContext {
             uic: self,
             ui_id: ui_id,
-            strings: strings,
+            items: items,
             selected: selected,
-            pos: [0.0, 0.0],
+            pos: pos,
             dim: [128.0, 32.0],
+            maybe_direction: None,
+            maybe_max_visible: None,
+            maybe_item_kinds: None,
+            is_filterable: false,
+            maybe_checked: None,
+            maybe_sub_items: None,
+            open_duration: 0.15,
             maybe_callback: None,
+            maybe_reorder_callback: None,
             maybe_color: None,
             maybe_frame: None,
             maybe_frame_color: None,
             maybe_label: None,
             maybe_label_color: None,
             maybe_label_font_size: None,
+            maybe_label_min_font_size: None,
+            is_enabled: true,
+            is_focusable: false,
+            depth: 0,
         }
     }
 }
 
-impl_callable!(DropDownListContext, |&mut Option<Idx>, Idx, String|:'a);
-impl_colorable!(DropDownListContext);
-impl_frameable!(DropDownListContext);
-impl_labelable!(DropDownListContext);
-impl_positionable!(DropDownListContext);
-impl_shapeable!(DropDownListContext);
+impl_callable!(DropDownListContext, |&mut Option<Idx>, &[Idx], &T|:'a, T);
+impl_colorable!(DropDownListContext, T);
+impl_enableable!(DropDownListContext, T);
+impl_frameable!(DropDownListContext, T);
+impl_hoverable!(DropDownListContext, T);
+impl_labelable!(DropDownListContext, T);
+impl_layerable!(DropDownListContext, T);
+impl_positionable!(DropDownListContext, T);
+impl_shapeable!(DropDownListContext, T);
 
-impl<'a> ::draw::Drawable for DropDownListContext<'a> {
+impl<'a, T: Show> ::draw::Drawable for DropDownListContext<'a, T> {
+    /// Draw the list. The open list is drawn during the overlay pass so that it covers the
+    /// widgets drawn after it, so the application must call `UiContext::draw_overlay` once
+    /// all widgets have been drawn for the frame, or the open list won't be shown.
     fn draw(&mut self, graphics: &mut Gl) {
 
-        let state = *get_state(self.uic, self.ui_id);
-        let mouse = self.uic.get_mouse_state();
-        let is_over_idx = is_over(self.pos, mouse.pos, self.dim, state, self.strings.len());
-        let new_state = get_new_state(is_over_idx, self.strings.len(), state, mouse);
+        let State { menu: state, progress, maybe_fitted } = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        // A disabled list is closed and hidden from the mouse.
+        let (state, mouse) = match self.is_enabled {
+            true => (state, mouse),
+            false => (Menu::Closed(DrawState::Normal),
+                      Mouse { pos: [-1.0, -1.0], scroll: [0.0, 0.0], ..mouse }),
+        };
+
+        // Edit the filter with the text typed while the list is open. The list captures the
+        // keyboard and takes focus meanwhile, so that the typing neither reaches a TextBox
+        // nor triggers hotkeys and typeable shortcuts.
+        let is_reading_query = self.is_filterable && match state {
+            Menu::Open(_, _, _) => true,
+            Menu::Closed(_) => false,
+        };
+        let (query, is_query_changed) = match is_reading_query {
+            true => {
+                self.uic.capture_text();
+                self.uic.set_focus(Some(self.ui_id));
+                let entered = self.uic.get_entered_text();
+                let is_backspace = self.uic.get_pressed_keys().iter()
+                    .any(|key| match *key { Backspace => true, _ => false });
+                let query = self.uic.text_buffer(self.ui_id);
+                if is_backspace { query.pop(); }
+                for text in entered.iter() { query.push_str(text.as_slice()) }
+                (query.clone(), is_backspace || !entered.is_empty())
+            },
+            false => (String::new(), false),
+        };
+
+        let num_items = self.items.len();
 
-        let sel = match *self.selected {
-            Some(idx) if idx < self.strings.len() => { Some(idx) },
+        // While open, indices refer to positions within the filtered items and are
+        // mapped back to the index of the item for the callback. The items are only
+        // converted to strings to be matched against a query.
+        let filtered: Vec<Idx> = match query.is_empty() {
+            true => range(0u, num_items).collect(),
+            false => range(0u, num_items).filter(|&i| {
+                self.items[i].to_string().as_slice().contains(query.as_slice())
+            }).collect(),
+        };
+        let len = filtered.len();
+        let num_visible = match self.maybe_max_visible {
+            Some(max) if max < len => max,
+            _ => len,
+        };
+        let max_first = len - num_visible;
+        // The filter may have shrunk the items since the previous frame, so the list is
+        // scrolled back within them before the mouse is checked against its rows.
+        let state = match state {
+            Menu::Open(draw_state, first, sub) => {
+                let first = if is_query_changed { 0u } else { ::std::cmp::min(first, max_first) };
+                Menu::Open(draw_state, first, sub)
+            },
+            Menu::Closed(_) => state,
+        };
+        // A filterable list shows the typed text in an extra row in place of the closed list.
+        let num_rows = if self.is_filterable { num_visible + 1u } else { num_visible };
+        let style = self.uic.theme.style(self.uic.theme.maybe_drop_down_list);
+        let frame_w = self.maybe_frame.unwrap_or(style.frame_width);
+        let direction = match self.maybe_direction {
+            Some(direction) => direction,
+            None => match self.pos[1] + self.dim[1] * num_rows as f64 > self.uic.win_h {
+                true => Direction::Up,
+                false => Direction::Down,
+            },
+        };
+        let open_pos = match (self.is_filterable, direction) {
+            (true, Direction::Down) => [self.pos[0], self.pos[1] + self.dim[1] - frame_w],
+            _ => open_pos(self.pos, self.dim, num_rows, frame_w, direction),
+        };
+        let (bar_pos, bar_dim) = scrollbar_rect(open_pos, self.dim, num_visible, frame_w);
+        let is_over_scrollbar = match state {
+            Menu::Open(_, _, _) => num_visible < len && rectangle::is_over(bar_pos, mouse.pos, bar_dim),
+            Menu::Closed(_) => false,
+        };
+        let kinds = self.maybe_item_kinds;
+        let sub_items = self.maybe_sub_items;
+        let (win_w, win_h, dim) = (self.uic.win_w, self.uic.win_h, self.dim);
+        // The open sub-menu (if any) as its parent item, position and number of items.
+        let sub_menu_rect = |first: Idx, parent: Idx| -> Option<(Point, Len)> {
+            match filtered.iter().position(|&i| i == parent) {
+                Some(pos) if pos >= first && pos < first + num_visible => {
+                    let num_sub = num_sub_items(sub_items, parent);
+                    Some((sub_menu_pos(open_pos, dim, pos - first, num_sub, frame_w, win_w, win_h), num_sub))
+                },
+                _ => None,
+            }
+        };
+        let is_over_sub_idx = match state {
+            Menu::Open(_, first, SubMenu::Open(parent, _)) => match sub_menu_rect(first, parent) {
+                Some((sub_pos, num_sub)) => {
+                    let total_h = self.dim[1] * num_sub as f64;
+                    match rectangle::is_over(sub_pos, mouse.pos, [self.dim[0], total_h]) {
+                        true => Some(((mouse.pos[1] - sub_pos[1]) / total_h * num_sub as f64) as uint),
+                        false => None,
+                    }
+                },
+                None => None,
+            },
             _ => None,
         };
-        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let is_over_idx = match is_over_sub_idx {
+            Some(_) => None,
+            None => is_over(self.pos, open_pos, mouse.pos, self.dim, state, num_visible),
+        };
+        // Items with sub items only open their sub-menu and are never selected themselves.
+        let is_over_unselectable = match (state, is_over_idx) {
+            (Menu::Open(_, _, _), _) if is_over_sub_idx.is_some() => true,
+            (Menu::Open(_, _, _), _) if self.is_filterable
+                && rectangle::is_over(self.pos, mouse.pos, self.dim) => true,
+            (Menu::Open(_, _, _), Some(idx)) if idx < len => !is_selectable(kinds, filtered[idx])
+                || num_sub_items(sub_items, filtered[idx]) > 0u,
+            _ => false,
+        };
+        let is_multi_select = self.maybe_checked.is_some();
+        let mut new_state = get_new_state(is_over_idx, is_over_unselectable, is_over_scrollbar,
+                                          is_multi_select, len, state, mouse);
+
+        let mut sel = match *self.selected {
+            Some(idx) if idx < num_items && !is_multi_select => { Some(idx) },
+            _ => None,
+        };
+
+        // Scroll the open list, beginning with the selected item in view.
+        let sel_first = match sel.and_then(|idx| filtered.iter().position(|&i| i == idx)) {
+            Some(idx) => ::std::cmp::min(idx, max_first),
+            None => 0u,
+        };
+        if let Menu::Open(draw_state, first, sub) = new_state {
+            let first = match (state, draw_state) {
+                (Menu::Closed(_), _) => sel_first,
+                _ if is_query_changed => 0u,
+                (_, DrawState::Scrolling) => {
+                    let perc = (mouse.pos[1] - bar_pos[1]) / bar_dim[1];
+                    let centred = perc * len as f64 - num_visible as f64 / 2.0;
+                    ::std::cmp::min(centred.round().max(0.0) as uint, max_first)
+                },
+                _ => match is_over_idx.is_some() || is_over_scrollbar {
+                    true => {
+                        let scrolled = first as f64 - mouse.scroll[1].round();
+                        ::std::cmp::min(scrolled.max(0.0) as uint, max_first)
+                    },
+                    false => ::std::cmp::min(first, max_first),
+                },
+            };
+            new_state = Menu::Open(draw_state, first, sub);
+        }
+
+        // Open the sub-menu of an item once it has been hovered for long enough or pressed,
+        // keeping it open while the mouse travels outside of the list towards it.
+        if let Menu::Open(draw_state, first, sub) = new_state {
+            use mouse::ButtonState::{Down, Up};
+            let now = self.uic.frame_time();
+            let hovered = is_over_idx.and_then(|idx| filtered.get(idx).map(|&i| i));
+            let sub = match (is_over_sub_idx, hovered) {
+                (Some(sub_idx), _) => match sub {
+                    SubMenu::Open(parent, sub_state) => {
+                        let num_sub = num_sub_items(sub_items, parent);
+                        SubMenu::Open(parent, get_new_sub_state(sub_idx, sub_state, num_sub, mouse))
+                    },
+                    _ => sub,
+                },
+                (None, Some(idx)) if num_sub_items(sub_items, idx) > 0u => match sub {
+                    SubMenu::Open(parent, _) if parent == idx => SubMenu::Open(idx, DrawState::Normal),
+                    SubMenu::Pending(parent, start) if parent == idx => match mouse.left {
+                        Down => SubMenu::Open(idx, DrawState::Normal),
+                        Up if now - start >= SUB_MENU_DELAY => SubMenu::Open(idx, DrawState::Normal),
+                        Up => sub,
+                    },
+                    _ => SubMenu::Pending(idx, now),
+                },
+                (None, Some(_)) => SubMenu::Closed,
+                (None, None) => match sub {
+                    SubMenu::Open(parent, _) => SubMenu::Open(parent, DrawState::Normal),
+                    _ => SubMenu::Closed,
+                },
+            };
+            // Close the sub-menu once its item is scrolled out of view.
+            let sub = match sub {
+                SubMenu::Open(parent, _) | SubMenu::Pending(parent, _)
+                if sub_menu_rect(first, parent).is_none() => SubMenu::Closed,
+                _ => sub,
+            };
+            new_state = Menu::Open(draw_state, first, sub);
+        }
+
+        // Call the `callback` closure with the path to the sub item if the mouse was
+        // released on one of the sub-menu items.
+        if let (Menu::Open(_, _, SubMenu::Open(parent, DrawState::Clicked(sub_idx, _))), Some(_))
+            = (state, is_over_sub_idx) {
+            if let ::mouse::ButtonState::Up = mouse.left {
+                match (&mut self.maybe_callback, sub_items) {
+                    (&Some(ref mut callback), Some(sub_items)) =>
+                        (*callback)(self.selected, &[parent, sub_idx], &sub_items[parent][sub_idx]),
+                    _ => (),
+                }
+                if !is_multi_select { new_state = Menu::Closed(DrawState::Normal) }
+            }
+        }
+        if self.is_filterable {
+            if let Menu::Closed(_) = new_state { self.uic.text_buffer(self.ui_id).clear() }
+        }
+        let color = self.maybe_color.unwrap_or(style.shape_color);
+        let color = if self.is_enabled { color } else { color.disabled() };
         let t_size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_medium);
-        let t_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+        // The same size is used for every item, so fit it to the widest.
+        let maybe_fitted = match maybe_fitted {
+            Some((fitted, n, size, w)) if n == num_items && size == t_size && w == self.dim[0] =>
+                Some((fitted, n, size, w)),
+            _ => {
+                // borrowck
+                let DropDownListContext { ref mut uic, ref items, maybe_label, .. } = *self;
+                let fitted = label::fit_font_size(t_size, self.maybe_label_min_font_size,
+                                                  self.dim[0], |size| {
+                    let max_w = items.iter().fold(0.0, |max_w, item| {
+                        max_w.max(label::width(&mut **uic, size, item.to_string().as_slice()))
+                    });
+                    match maybe_label {
+                        Some(text) => max_w.max(label::width(&mut **uic, size, text)),
+                        None => max_w,
+                    }
+                });
+                Some((fitted, num_items, t_size, self.dim[0]))
+            },
+        };
+        let t_size = maybe_fitted.map_or(t_size, |(fitted, _, _, _)| fitted);
+        let t_color = self.maybe_label_color.unwrap_or(style.label_color);
+        let t_color = if self.is_enabled { t_color } else { t_color.disabled() };
+
+        // Toggle the item if the mouse was released on it while multi-selecting.
+        match (state, new_state) {
+            (Menu::Open(DrawState::Clicked(pos, _), _, _), Menu::Open(DrawState::Highlighted(_, _), _, _))
+            if is_multi_select && pos < len && is_selectable(kinds, filtered[pos]) => {
+                let idx = filtered[pos];
+                if let Some(ref mut checked) = self.maybe_checked {
+                    if checked.len() <= idx { checked.grow(idx + 1u - checked.len(), false) }
+                    (**checked)[idx] = !(**checked)[idx];
+                }
+                match self.maybe_callback {
+                    Some(ref mut callback) => (*callback)(self.selected, &[idx], &(*self.items)[idx]),
+                    None => (),
+                }
+            }, _ => (),
+        }
 
         // Call the `callback` closure if mouse was released
         // on one of the DropDownMenu items.
         match (state, new_state) {
-            (State::Open(o_d_state), State::Closed(c_d_state)) => {
+            (Menu::Open(o_d_state, _, _), Menu::Closed(c_d_state)) => {
                 match (o_d_state, c_d_state) {
-                    (DrawState::Clicked(idx, _), DrawState::Normal) => {
+                    (DrawState::Clicked(pos, _), DrawState::Normal)
+                    if pos < len && is_selectable(kinds, filtered[pos]) => {
+                        let idx = filtered[pos];
                         match self.maybe_callback {
-                            Some(ref mut callback) => (*callback)(self.selected, idx, (*self.strings)[idx].clone()),
+                            Some(ref mut callback) => (*callback)(self.selected, &[idx], &(*self.items)[idx]),
                             None => (),
                         }
                     }, _ => (),
@@ -204,34 +713,154 @@ impl<'a> ::draw::Drawable for DropDownListContext<'a> {
             }, _ => (),
         }
 
-        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        // Take focus when opened and lose it when the mouse is pressed elsewhere.
+        let is_pressed = match (state, new_state) {
+            (Menu::Closed(_), Menu::Open(_, _, _)) => true,
+            _ => false,
+        };
+        let is_over_any = rectangle::is_over(self.pos, mouse.pos, self.dim) || is_over_idx.is_some()
+            || is_over_sub_idx.is_some() || is_over_scrollbar;
+        let is_focusable = self.is_enabled && (self.is_focusable || is_reading_query);
+        let is_focused = self.uic.update_focus(self.ui_id, is_focusable, is_pressed, is_over_any);
+
+        // Select the neighbouring item with the Up and Down keys while focused and closed,
+        // or move the selected item with Ctrl+Up / Ctrl+Down if the list is reorderable.
+        let is_reordering = self.maybe_reorder_callback.is_some() && self.uic.get_modifiers().ctrl;
+        if let (true, false, false, Menu::Closed(_)) =
+            (is_focused, is_multi_select, is_reordering, new_state) {
+            let keys = self.uic.get_pressed_keys();
+            let mut maybe_idx = sel;
+            for key in keys.iter() {
+                let forwards = match *key { Key::Down => true, Key::Up => false, _ => continue };
+                let next = next_selectable(kinds, sub_items, maybe_idx, num_items, forwards);
+                if next.is_some() { maybe_idx = next }
+            }
+            if let Some(idx) = maybe_idx {
+                if maybe_idx != sel {
+                    match self.maybe_callback {
+                        Some(ref mut callback) => (*callback)(self.selected, &[idx], &(*self.items)[idx]),
+                        None => (),
+                    }
+                }
+            }
+        }
+        if let (true, true, Menu::Closed(_), Some(idx)) =
+            (is_focused, is_reordering, new_state, sel) {
+            let mut maybe_move = None;
+            for key in self.uic.get_pressed_keys().iter() {
+                match *key {
+                    Key::Up if idx > 0u => maybe_move = Some((idx, idx - 1u)),
+                    Key::Down if idx + 1u < num_items => maybe_move = Some((idx, idx + 1u)),
+                    _ => (),
+                }
+            }
+            if let Some((from, to)) = maybe_move {
+                match self.maybe_reorder_callback {
+                    Some(ref mut callback) => (*callback)(self.items, from, to),
+                    None => (),
+                }
+                *self.selected = Some(to);
+                sel = Some(to);
+            }
+        }
+
         let maybe_frame = match frame_w > 0.0 {
-            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(style.frame_color))),
             false => None,
         };
 
-        match new_state {
+        // Step the open animation towards its target.
+        let step = if self.open_duration > 0.0 { self.uic.frame_dt() / self.open_duration } else { 1.0 };
+        let progress = match new_state {
+            Menu::Open(_, _, _) => clamp(progress + step, 0.0, 1.0),
+            Menu::Closed(_) => clamp(progress - step, 0.0, 1.0),
+        };
 
-            State::Closed(_) => {
-                let rect_state = new_state.as_rect_state();
-                let text = match sel {
-                    Some(idx) => (*self.strings)[idx][],
-                    None => match self.maybe_label {
-                        Some(text) => text,
-                        None => (*self.strings)[0][],
+        // The closed list, which the open list covers once it has fully grown. Its color
+        // fades between states, so the rectangle itself is drawn as Normal.
+        {
+            let transition = self.uic.transition(self.ui_id, new_state.as_rect_state());
+            let rect_state = rectangle::State::Normal;
+            let num_checked = match self.maybe_checked {
+                Some(ref checked) => checked.iter().filter(|&&is_checked| is_checked).count(),
+                None => 0u,
+            };
+            let text = match sel {
+                Some(idx) => self.items[idx].to_string(),
+                None => match (self.maybe_label, num_checked) {
+                    (Some(text), 0u) => text.to_string(),
+                    (_, 0u) if !is_multi_select =>
+                        self.items.get(0).map_or(String::new(), |item| item.to_string()),
+                    _ => format!("{} selected", num_checked),
+                },
+            };
+            rectangle::draw_with_centered_label(
+                self.uic.win_w, self.uic.win_h, graphics, self.uic, rect_state,
+                self.pos, self.dim, maybe_frame, transition.color(color),
+                text.as_slice(), t_size, t_color
+            )
+        }
+
+        // The open list is drawn during the overlay pass so that it covers the
+        // widgets drawn after it. While closing, it shrinks back as it would next open.
+        if progress > 0.0 {
+            let (draw_state, first, sub) = match new_state {
+                Menu::Open(draw_state, first, sub) => (draw_state, first, sub),
+                Menu::Closed(_) => (DrawState::Normal, sel_first, SubMenu::Closed),
+            };
+            // Only the part of the list that has grown so far is shown, beginning from
+            // the closed list.
+            let area_pos = match (self.is_filterable, direction) {
+                (true, Direction::Down) => self.pos,
+                _ => open_pos,
+            };
+            let area_dim = [self.dim[0], (self.dim[1] - frame_w) * num_rows as f64 + frame_w];
+            let clip_dim = [area_dim[0], area_dim[1] * smooth_step(progress)];
+            let clip_pos = match direction {
+                Direction::Down => area_pos,
+                Direction::Up => [area_pos[0], area_pos[1] + area_dim[1] - clip_dim[1]],
+            };
+            let mut primitives = Vec::new();
+            if let Some(shadow) = self.uic.theme.maybe_shadow {
+                primitives.push(Primitive::Shadow(clip_pos, clip_dim, shadow));
+            }
+            primitives.push(Primitive::Clip(clip_pos, clip_dim));
+            if self.is_filterable {
+                let (text, text_color) = match query.is_empty() {
+                    true => {
+                        let mut grey = t_color;
+                        grey.set_a(t_color.a() * 0.4);
+                        ("Type to filter".to_string(), grey)
                     },
+                    false => (query.clone(), t_color),
                 };
-                rectangle::draw_with_centered_label(
-                    self.uic.win_w, self.uic.win_h, graphics, self.uic, rect_state,
-                    self.pos, self.dim, maybe_frame, color,
-                    text, t_size, t_color
-                )
-            },
-
-            State::Open(draw_state) => {
-                for (i, string) in self.strings.iter().enumerate() {
-                    let rect_state = match sel {
-                        None => {
+                primitives.push(Primitive::LabelledRect(
+                    rectangle::State::Normal, self.pos, self.dim, maybe_frame, color,
+                    text, t_size, text_color
+                ));
+            }
+            let frame_color = self.maybe_frame_color.unwrap_or(style.frame_color);
+            for (i, &idx) in filtered.iter().enumerate().skip(first).take(num_visible) {
+                // Only the visible items are converted to strings.
+                let string = self.items[idx].to_string();
+                let rect_state = match sel.and_then(|sel_idx| filtered.iter().position(|&i| i == sel_idx)) {
+                    None => {
+                        match draw_state {
+                            DrawState::Normal => rectangle::State::Normal,
+                            DrawState::Highlighted(idx, _) => {
+                                if i == idx { rectangle::State::Highlighted }
+                                else { rectangle::State::Normal }
+                            },
+                            DrawState::Clicked(idx, _) => {
+                                if i == idx { rectangle::State::Clicked }
+                                else { rectangle::State::Normal }
+                            },
+                            DrawState::Scrolling | DrawState::ClickedOutside => rectangle::State::Normal,
+                        }
+                    },
+                    Some(sel_idx) => {
+                        if sel_idx == i { rectangle::State::Clicked }
+                        else {
                             match draw_state {
                                 DrawState::Normal => rectangle::State::Normal,
                                 DrawState::Highlighted(idx, _) => {
@@ -242,37 +871,116 @@ impl<'a> ::draw::Drawable for DropDownListContext<'a> {
                                     if i == idx { rectangle::State::Clicked }
                                     else { rectangle::State::Normal }
                                 },
+                                DrawState::Scrolling | DrawState::ClickedOutside => rectangle::State::Normal,
                             }
-                        },
-                        Some(sel_idx) => {
-                            if sel_idx == i { rectangle::State::Clicked }
-                            else {
-                                match draw_state {
-                                    DrawState::Normal => rectangle::State::Normal,
-                                    DrawState::Highlighted(idx, _) => {
-                                        if i == idx { rectangle::State::Highlighted }
-                                        else { rectangle::State::Normal }
-                                    },
-                                    DrawState::Clicked(idx, _) => {
-                                        if i == idx { rectangle::State::Clicked }
-                                        else { rectangle::State::Normal }
-                                    },
-                                }
-                            }
-                        },
-                    };
-                    let idx_y = self.dim[1] * i as f64 - i as f64 * frame_w;
-                    let idx_pos = vec2_add(self.pos, [0.0, idx_y]);
-                    rectangle::draw_with_centered_label(
-                        self.uic.win_w, self.uic.win_h, graphics, self.uic, rect_state, idx_pos,
-                        self.dim, maybe_frame, color, string.as_slice(),
-                        t_size, t_color
-                    )
+                        }
+                    },
+                };
+                // The item whose sub-menu is open or opening stays highlighted.
+                let rect_state = match sub {
+                    SubMenu::Pending(parent, _) | SubMenu::Open(parent, _)
+                    if parent == idx && rect_state == rectangle::State::Normal => rectangle::State::Highlighted,
+                    _ => rect_state,
+                };
+                let row = i - first;
+                let idx_y = self.dim[1] * row as f64 - row as f64 * frame_w;
+                let idx_pos = vec2_add(open_pos, [0.0, idx_y]);
+                let kind = match kinds {
+                    Some(kinds) if idx < kinds.len() => kinds[idx],
+                    _ => ItemKind::Normal,
+                };
+                match kind {
+                    ItemKind::Normal => primitives.push(Primitive::LabelledRect(
+                        rect_state, idx_pos, self.dim, maybe_frame, color,
+                        string, t_size, t_color
+                    )),
+                    ItemKind::Disabled => {
+                        let mut grey = t_color;
+                        grey.set_a(t_color.a() * 0.4);
+                        primitives.push(Primitive::LabelledRect(
+                            rectangle::State::Normal, idx_pos, self.dim, maybe_frame, color,
+                            string, t_size, grey
+                        ))
+                    },
+                    ItemKind::Separator => {
+                        primitives.push(Primitive::Rect(
+                            rectangle::State::Normal, idx_pos, self.dim, maybe_frame, color
+                        ));
+                        let y = idx_pos[1] + self.dim[1] / 2.0;
+                        let (x, w) = (idx_pos[0] + self.dim[1] / 4.0, self.dim[0] - self.dim[1] / 2.0);
+                        primitives.push(Primitive::Line([x, y, x + w, y], 0.5, frame_color));
+                    },
                 }
-            },
+                // Items with sub items show an arrow at their right.
+                if num_sub_items(sub_items, idx) > 0u && kind != ItemKind::Separator {
+                    let bar_w = if num_visible < len { SCROLLBAR_WIDTH } else { 0.0 };
+                    let side = self.dim[1] * 0.15;
+                    let tip = [idx_pos[0] + self.dim[0] - bar_w - self.dim[1] * 0.3,
+                               idx_pos[1] + self.dim[1] / 2.0];
+                    primitives.push(Primitive::Line([tip[0] - side, tip[1] - side, tip[0], tip[1]], 0.75, t_color));
+                    primitives.push(Primitive::Line([tip[0] - side, tip[1] + side, tip[0], tip[1]], 0.75, t_color));
+                }
+                if let Some(ref checked) = self.maybe_checked {
+                    if kind != ItemKind::Separator {
+                        let side = self.dim[1] * 0.4;
+                        let box_pos = [idx_pos[0] + side / 2.0, idx_pos[1] + (self.dim[1] - side) / 2.0];
+                        let is_checked = idx < checked.len() && (**checked)[idx];
+                        let box_color = if is_checked { frame_color } else { color };
+                        primitives.push(Primitive::Rect(
+                            rectangle::State::Normal, box_pos, [side, side],
+                            Some((1.0, frame_color)), box_color
+                        ));
+                    }
+                }
+            }
+            if num_visible < len {
+                let handle_h = bar_dim[1] * num_visible as f64 / len as f64;
+                let handle_y = bar_pos[1] + bar_dim[1] * first as f64 / len as f64;
+                let handle_state = match (draw_state, is_over_scrollbar) {
+                    (DrawState::Scrolling, _) => rectangle::State::Clicked,
+                    (_, true) => rectangle::State::Highlighted,
+                    _ => rectangle::State::Normal,
+                };
+                primitives.push(Primitive::Rect(
+                    rectangle::State::Normal, bar_pos, bar_dim, None, color.clicked()
+                ));
+                primitives.push(Primitive::Rect(
+                    handle_state, [bar_pos[0], handle_y], [bar_dim[0], handle_h], None,
+                    color.plain_contrast()
+                ));
+            }
+            primitives.push(Primitive::ClearClip);
+            self.uic.push_overlay_at_depth(self.ui_id, self.depth, clip_pos, clip_dim, primitives);
+
+            // The open sub-menu beside its item.
+            if let (SubMenu::Open(parent, sub_state), Some(sub_items)) = (sub, sub_items) {
+                if let Some((sub_pos, num_sub)) = sub_menu_rect(first, parent) {
+                    let mut sub_primitives = Vec::new();
+                    for (j, sub_item) in sub_items[parent].iter().enumerate() {
+                        let rect_state = match sub_state {
+                            DrawState::Highlighted(idx, _) if idx == j => rectangle::State::Highlighted,
+                            DrawState::Clicked(idx, _) if idx == j => rectangle::State::Clicked,
+                            _ => rectangle::State::Normal,
+                        };
+                        let sub_item_pos = [sub_pos[0], sub_pos[1] + (self.dim[1] - frame_w) * j as f64];
+                        sub_primitives.push(Primitive::LabelledRect(
+                            rect_state, sub_item_pos, self.dim, maybe_frame, color,
+                            sub_item.to_string(), t_size, t_color
+                        ));
+                    }
+                    let sub_dim = [self.dim[0], (self.dim[1] - frame_w) * num_sub as f64 + frame_w];
+                    self.uic.push_overlay_at_depth(self.ui_id, self.depth, sub_pos, sub_dim,
+                                                  sub_primitives);
+                }
+            }
+        }
 
+        if is_focused && self.is_focusable {
+            let ring_color = self.uic.theme.link_color();
+            focus::draw_ring(self.uic.win_w, self.uic.win_h, graphics, self.pos, self.dim, ring_color);
         }
 
+        let new_state = State { menu: new_state, progress: progress, maybe_fitted: maybe_fitted };
         set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
 
     }
@@ -0,0 +1,112 @@
+
+use color::Color;
+use dimensions::Dimensions;
+use focus;
+use mouse::ButtonState;
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use widget::Widget::DropTarget;
+
+/// Represents the state of the DropTarget widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    /// A drag carrying a payload of the target's type is over the target.
+    Hovered,
+}
+
+widget_fns!(DropTarget, State, DropTarget(State::Normal));
+
+/// A context on which the builder pattern can be implemented.
+pub struct DropTargetContext<'a, T:'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    pos: Point,
+    dim: Dimensions,
+    maybe_hover_callback: Option<|&T|:'a>,
+    maybe_callback: Option<|&T|:'a>,
+    maybe_color: Option<Color>,
+}
+
+impl<'a, T> DropTargetContext<'a, T> {
+    /// Call the given closure with the payload during each frame in which a drag carrying
+    /// a payload of the target's type is over the target.
+    #[inline]
+    pub fn hover_callback(self, callback: |&T|:'a) -> DropTargetContext<'a, T> {
+        DropTargetContext { maybe_hover_callback: Some(callback), ..self }
+    }
+}
+
+pub trait DropTargetBuilder<'a, T> {
+    /// A drop_target builder method to be implemented by the UiContext.
+    fn drop_target(&'a mut self, ui_id: UIID) -> DropTargetContext<'a, T>;
+}
+
+impl<'a, T> DropTargetBuilder<'a, T> for UiContext {
+    fn drop_target(&'a mut self, ui_id: UIID) -> DropTargetContext<'a, T> {
+        let pos = self.flow_pos();
+        DropTargetContext {
+            uic: self,
+            ui_id: ui_id,
+            pos: pos,
+            dim: [128.0, 64.0],
+            maybe_hover_callback: None,
+            maybe_callback: None,
+            maybe_color: None,
+        }
+    }
+}
+
+impl_callable!(DropTargetContext, |&T|:'a, T);
+impl_colorable!(DropTargetContext, T);
+impl_hoverable!(DropTargetContext, T);
+impl_positionable!(DropTargetContext, T);
+impl_shapeable!(DropTargetContext, T);
+
+impl<'a, T: 'static> ::draw::Drawable for DropTargetContext<'a, T> {
+    /// Draw an outline around the target while a drag carrying a payload of type `T` is
+    /// over it. The `callback` is called with the payload when the drag is released over
+    /// the target, after which the drag ends. Nothing is drawn otherwise, so the target
+    /// may be placed over the widgets that it accepts drops for.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        // The target mustn't take the mouse from the widgets beneath it.
+        self.uic.set_transparent_to_mouse(self.ui_id);
+        let mouse = self.uic.get_mouse_state();
+        let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
+        let is_dropped = match mouse.left { ButtonState::Up => true, ButtonState::Down => false };
+        let new_state = {
+            let DropTargetContext { // borrowck
+                ref uic,
+                ref mut maybe_hover_callback,
+                ref mut maybe_callback,
+                ..
+            } = *self;
+            match uic.get_drag().and_then(|drag| drag.payload::<T>()) {
+                Some(payload) if is_over => {
+                    match (is_dropped, maybe_hover_callback, maybe_callback) {
+                        (false, &Some(ref mut callback), _) => (*callback)(payload),
+                        (true, _, &Some(ref mut callback)) => (*callback)(payload),
+                        _ => (),
+                    }
+                    State::Hovered
+                },
+                _ => State::Normal,
+            }
+        };
+        if is_dropped && new_state == State::Hovered { self.uic.end_drag() }
+
+        if new_state == State::Hovered {
+            let color = self.maybe_color.unwrap_or(self.uic.theme.link_color());
+            focus::draw_ring(self.uic.win_w, self.uic.win_h, graphics, self.pos, self.dim, color);
+        }
+
+        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+
+    }
+}
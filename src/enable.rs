@@ -0,0 +1,7 @@
+
+/// A trait used for widget contexts that may be disabled. A disabled widget is drawn
+/// with dimmed colors, ignores the mouse and never calls its callback.
+pub trait Enableable {
+    fn enabled(self, enabled: bool) -> Self;
+}
+
@@ -8,13 +8,14 @@ use graphics::{
 };
 use label;
 use label::FontSize;
-use mouse::Mouse;
+use mouse::{FINE_DRAG_SCALE, Mouse};
 use opengl_graphics::Gl;
 use point::Point;
 use rectangle;
 use rectangle::{
     Corner
 };
+use touch::View;
 use ui_context::{
     UIID,
     UiContext,
@@ -211,6 +212,9 @@ pub struct EnvelopeEditorContext<'a, X, Y, E:'a> {
     maybe_label: Option<&'a str>,
     maybe_label_color: Option<Color>,
     maybe_label_font_size: Option<u32>,
+    maybe_label_min_font_size: Option<u32>,
+    maybe_view: Option<&'a mut View>,
+    maybe_pressure_callback: Option<|&mut Vec<E>, uint, f64|:'a>,
 }
 
 impl<'a, X, Y, E> EnvelopeEditorContext<'a, X, Y, E> {
@@ -230,6 +234,20 @@ impl<'a, X, Y, E> EnvelopeEditorContext<'a, X, Y, E> {
     pub fn skew_y(self, skew: f32) -> EnvelopeEditorContext<'a, X, Y, E> {
         EnvelopeEditorContext { skew_y_range: skew, ..self }
     }
+    /// Only show the region of the value ranges within the given View, which is zoomed
+    /// and panned with two finger gestures over the envelope.
+    #[inline]
+    pub fn view(self, view: &'a mut View) -> EnvelopeEditorContext<'a, X, Y, E> {
+        EnvelopeEditorContext { maybe_view: Some(view), ..self }
+    }
+    /// Call the given closure with the envelope, the index of the dragged point and the
+    /// pressure of the pen whenever the pressure changes while a point is dragged, i.e. so
+    /// that each point may store a third value such as velocity.
+    #[inline]
+    pub fn pressure_callback(self, callback: |&mut Vec<E>, uint, f64|:'a)
+        -> EnvelopeEditorContext<'a, X, Y, E> {
+        EnvelopeEditorContext { maybe_pressure_callback: Some(callback), ..self }
+    }
 }
 
 pub trait EnvelopeEditorBuilder
@@ -247,6 +265,7 @@ impl <'a, X: Float + Copy + ToPrimitive + FromPrimitive + PartialOrd + ToString,
     /// An envelope editor builder method to be implemented by the UiContext.
     fn envelope_editor(&'a mut self, ui_id: UIID, env: &'a mut Vec<E>,
                        min_x: X, max_x: X, min_y: Y, max_y: Y) -> EnvelopeEditorContext<'a, X, Y, E> {
+        let pos = self.flow_pos();
         EnvelopeEditorContext {
             uic: self,
             ui_id: ui_id,
@@ -257,7 +276,7 @@ impl <'a, X: Float + Copy + ToPrimitive + FromPrimitive + PartialOrd + ToString,
             pt_radius: 6.0, // Default envelope point radius.
             line_width: 2.0, // Default envelope line width.
             font_size: 18u32,
-            pos: [0.0, 0.0],
+            pos: pos,
             dim: [256.0, 128.0],
             maybe_callback: None,
             maybe_color: None,
@@ -266,6 +285,9 @@ impl <'a, X: Float + Copy + ToPrimitive + FromPrimitive + PartialOrd + ToString,
             maybe_label: None,
             maybe_label_color: None,
             maybe_label_font_size: None,
+            maybe_label_min_font_size: None,
+            maybe_view: None,
+            maybe_pressure_callback: None,
         }
     }
 }
@@ -273,6 +295,7 @@ impl <'a, X: Float + Copy + ToPrimitive + FromPrimitive + PartialOrd + ToString,
 impl_callable!(EnvelopeEditorContext, |&mut Vec<E>, uint|:'a, X, Y, E);
 impl_colorable!(EnvelopeEditorContext, X, Y, E);
 impl_frameable!(EnvelopeEditorContext, X, Y, E);
+impl_hoverable!(EnvelopeEditorContext, X, Y, E);
 impl_labelable!(EnvelopeEditorContext, X, Y, E);
 impl_positionable!(EnvelopeEditorContext, X, Y, E);
 impl_shapeable!(EnvelopeEditorContext, X, Y, E);
@@ -283,7 +306,7 @@ impl<'a, X: Float + Copy + ToPrimitive + FromPrimitive + PartialOrd + ToString +
     #[inline]
     fn draw(&mut self, graphics: &mut Gl) {
         let state = *get_state(self.uic, self.ui_id);
-        let mouse = self.uic.get_mouse_state();
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
         let skew = self.skew_y_range;
         let (min_x, max_x, min_y, max_y) = (self.min_x, self.max_x, self.min_y, self.max_y);
         let pt_radius = self.pt_radius;
@@ -300,6 +323,26 @@ impl<'a, X: Float + Copy + ToPrimitive + FromPrimitive + PartialOrd + ToString +
         let pad_pos = vec2_add(self.pos, [frame_w, ..2]);
         let pad_dim = vec2_sub(self.dim, [frame_w2, ..2]);
 
+        // Zoom and pan the visible ranges with two finger gestures over the envelope, during
+        // which its points aren't pressed.
+        let maybe_gesture = match self.uic.get_gesture() {
+            Some(gesture) if rectangle::is_over(pad_pos, gesture.center, pad_dim) => Some(gesture),
+            _ => None,
+        };
+        let (min_x, max_x, min_y, max_y) = match self.maybe_view {
+            Some(ref mut view) => {
+                if let Some(gesture) = maybe_gesture { view.apply(gesture, pad_dim) }
+                // The greatest y values are shown at the top of the envelope.
+                let (x_start, x_end) = view.visible(0u);
+                let (y_start, y_end) = view.visible(1u);
+                (map_range(x_start, 0.0, 1.0, min_x, max_x),
+                 map_range(x_end, 0.0, 1.0, min_x, max_x),
+                 map_range(y_end, 0.0, 1.0, max_y, min_y),
+                 map_range(y_start, 0.0, 1.0, max_y, min_y))
+            },
+            None => (min_x, max_x, min_y, max_y),
+        };
+
         // Create a vector with each EnvelopePoint value represented as a
         // skewed percentage between 0.0 .. 1.0 .
         let perc_env: Vec<(f32, f32, f32)> = self.env.iter().map(|pt| {
@@ -313,7 +356,26 @@ impl<'a, X: Float + Copy + ToPrimitive + FromPrimitive + PartialOrd + ToString +
             self.pos, mouse.pos, self.dim,
             pad_pos, pad_dim, &perc_env, pt_radius
         );
-        let new_state = get_new_state(is_over_elem, state, mouse);
+        let new_state = match maybe_gesture.is_some() && self.maybe_view.is_some() {
+            true => State::Normal,
+            false => get_new_state(is_over_elem, state, mouse),
+        };
+        // Holding Shift slows the drag of a point for fine adjustment, by moving the point
+        // from its current position rather than to the mouse.
+        let modifiers = self.uic.get_modifiers();
+        let new_state = match (state, new_state) {
+            (State::Clicked(Element::EnvPoint(_, _), MouseButton::Left),
+             State::Clicked(Element::EnvPoint(idx, _), MouseButton::Left))
+            if modifiers.shift && idx < perc_env.len() => {
+                let (x, y, _) = perc_env[idx];
+                let delta = vec2_sub(mouse.pos, mouse.prev_pos);
+                let p_x = map_range(x, 0.0, 1.0, pad_pos[0], pad_pos[0] + pad_dim[0]);
+                let p_y = map_range(y, 0.0, 1.0, pad_pos[1] + pad_dim[1], pad_pos[1]);
+                let p_pos = (p_x + delta[0] * FINE_DRAG_SCALE, p_y + delta[1] * FINE_DRAG_SCALE);
+                State::Clicked(Element::EnvPoint(idx, p_pos), MouseButton::Left)
+            },
+            _ => new_state,
+        };
 
         // Draw rect.
         rectangle::draw(self.uic.win_w, self.uic.win_h, graphics,
@@ -323,6 +385,8 @@ impl<'a, X: Float + Copy + ToPrimitive + FromPrimitive + PartialOrd + ToString +
         // If there's a label, draw it.
         if let Some(l_text) = self.maybe_label {
             let l_size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_medium);
+            let l_size = label::shrink_to_fit(self.uic, l_text, l_size,
+                                              self.maybe_label_min_font_size, pad_dim[0]);
             let l_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
             let l_w = label::width(self.uic, l_size, l_text);
             let l_pos = [pad_pos[0] + (pad_dim[0] - l_w) / 2.0,
@@ -450,11 +514,16 @@ impl<'a, X: Float + Copy + ToPrimitive + FromPrimitive + PartialOrd + ToString +
                 // Call the `callback` closure if mouse was released
                 // on one of the DropDownMenu items.
                 match (state, new_state) {
-                    (State::Clicked(_, m_button), State::Highlighted(_)) | (State::Clicked(_, m_button), State::Normal) => {
+                    (State::Clicked(elem, m_button), State::Highlighted(_)) | (State::Clicked(elem, m_button), State::Normal) => {
                         match m_button {
                             MouseButton::Left => {
-                                // Adjust the point and trigger the callback.
-                                let (new_x, new_y) = get_new_value(&perc_env, idx, mouse.pos[0], mouse.pos[1]);
+                                // Adjust the point and trigger the callback. A point dragged
+                                // with Shift held stays where it was dragged to.
+                                let (p_x, p_y) = match (modifiers.shift, elem) {
+                                    (true, Element::EnvPoint(_, p_pos)) => p_pos,
+                                    _ => (mouse.pos[0], mouse.pos[1]),
+                                };
+                                let (new_x, new_y) = get_new_value(&perc_env, idx, p_x, p_y);
                                 self.env[idx].set_x(new_x);
                                 self.env[idx].set_y(new_y);
                                 match self.maybe_callback {
@@ -473,10 +542,14 @@ impl<'a, X: Float + Copy + ToPrimitive + FromPrimitive + PartialOrd + ToString +
                         }
                     },
 
-                    (State::Clicked(_, prev_m_button), State::Clicked(_, m_button)) => {
+                    (State::Clicked(_, prev_m_button), State::Clicked(elem, m_button)) => {
                         match (prev_m_button, m_button) {
                             (MouseButton::Left, MouseButton::Left) => {
-                                let (new_x, new_y) = get_new_value(&perc_env, idx, mouse.pos[0], mouse.pos[1]);
+                                let (p_x, p_y) = match elem {
+                                    Element::EnvPoint(_, p_pos) => p_pos,
+                                    _ => (mouse.pos[0], mouse.pos[1]),
+                                };
+                                let (new_x, new_y) = get_new_value(&perc_env, idx, p_x, p_y);
                                 let current_x = (*self.env)[idx].get_x();
                                 let current_y = (*self.env)[idx].get_y();
                                 if new_x != current_x || new_y != current_y {
@@ -488,6 +561,13 @@ impl<'a, X: Float + Copy + ToPrimitive + FromPrimitive + PartialOrd + ToString +
                                         None => (),
                                     }
                                 }
+                                if mouse.pressure != mouse.prev_pressure {
+                                    match self.maybe_pressure_callback {
+                                        Some(ref mut callback) =>
+                                            (*callback)(self.env, idx, mouse.pressure),
+                                        None => (),
+                                    }
+                                }
                             }, _ => (),
                         }
                     }, _ => (),
@@ -0,0 +1,25 @@
+
+use color::Color;
+use dimensions::Dimensions;
+use graphics;
+use graphics::Context;
+use opengl_graphics::Gl;
+use point::Point;
+
+/// The gap left between a focused widget and its focus ring.
+static RING_GAP: f64 = 2.0;
+
+/// Draw a ring around the given rectangle to show that it has keyboard focus.
+pub fn draw_ring(win_w: f64, win_h: f64, graphics: &mut Gl,
+                 pos: Point, dim: Dimensions, color: Color) {
+    let context = Context::abs(win_w, win_h);
+    let Color(col) = color;
+    let line = graphics::Line::round(col, 1.0);
+    let (x1, y1) = (pos[0] - RING_GAP, pos[1] - RING_GAP);
+    let (x2, y2) = (pos[0] + dim[0] + RING_GAP, pos[1] + dim[1] + RING_GAP);
+    line.draw([x1, y1, x2, y1], &context, graphics);
+    line.draw([x2, y1, x2, y2], &context, graphics);
+    line.draw([x2, y2, x1, y2], &context, graphics);
+    line.draw([x1, y2, x1, y1], &context, graphics);
+}
+
@@ -0,0 +1,103 @@
+
+use color::Color;
+use dimensions::Dimensions;
+use graphics;
+use graphics::Context;
+use label;
+use opengl_graphics::Gl;
+use point::Point;
+use ui_context::UiContext;
+
+/// The horizontal distance between the start of the top border and the title.
+static TITLE_INDENT: f64 = 10.0;
+/// The gap left between the title and the broken top border on either side.
+static TITLE_GAP: f64 = 4.0;
+
+/// A context on which the builder pattern can be implemented.
+pub struct GroupBoxContext<'a> {
+    uic: &'a mut UiContext,
+    pos: Point,
+    dim: Dimensions,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label: Option<&'a str>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+    maybe_label_min_font_size: Option<u32>,
+}
+
+pub trait GroupBoxBuilder<'a> {
+    /// A group_box builder method to be implemented by the UiContext.
+    fn group_box(&'a mut self) -> GroupBoxContext<'a>;
+}
+
+impl<'a> GroupBoxBuilder<'a> for UiContext {
+    fn group_box(&'a mut self) -> GroupBoxContext<'a> {
+        let pos = self.flow_pos();
+        GroupBoxContext {
+            uic: self,
+            pos: pos,
+            dim: [256.0, 256.0],
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+            maybe_label_min_font_size: None,
+        }
+    }
+}
+
+impl_colorable!(GroupBoxContext);
+impl_frameable!(GroupBoxContext);
+impl_labelable!(GroupBoxContext);
+impl_positionable!(GroupBoxContext);
+impl_shapeable!(GroupBoxContext);
+
+impl<'a> ::draw::Drawable for GroupBoxContext<'a> {
+    /// Draw the group box. The box is only filled if a color was given.
+    fn draw(&mut self, graphics: &mut Gl) {
+        let context = Context::abs(self.uic.win_w, self.uic.win_h);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let Color(frame_col) = self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color);
+        let (x, y, w, h) = (self.pos[0], self.pos[1], self.dim[0], self.dim[1]);
+
+        // Fill.
+        if let Some(Color(col)) = self.maybe_color {
+            graphics::Rectangle::new(col).draw([x, y, w, h], &context, graphics);
+        }
+
+        // Determine where the top border must be broken by the title.
+        let size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_small);
+        let maybe_gap = match self.maybe_label {
+            Some(text) => {
+                let size = label::shrink_to_fit(self.uic, text, size, self.maybe_label_min_font_size,
+                                                w - (TITLE_INDENT + TITLE_GAP) * 2.0);
+                let text_w = label::width(self.uic, size, text);
+                let text_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+                let text_pos = [x + TITLE_INDENT + TITLE_GAP, y - size as f64 / 2.0];
+                self.uic.draw_text(graphics, text_pos, size, text_color, text);
+                Some((x + TITLE_INDENT, x + TITLE_INDENT + text_w + TITLE_GAP * 2.0))
+            },
+            None => None,
+        };
+
+        // Border.
+        if frame_w > 0.0 {
+            let line = graphics::Line::new(frame_col, 0.5 * frame_w);
+            line.draw([x, y, x, y + h], &context, graphics);
+            line.draw([x + w, y, x + w, y + h], &context, graphics);
+            line.draw([x, y + h, x + w, y + h], &context, graphics);
+            match maybe_gap {
+                Some((gap_start, gap_end)) if gap_end < x + w => {
+                    line.draw([x, y, gap_start, y], &context, graphics);
+                    line.draw([gap_end, y, x + w, y], &context, graphics);
+                },
+                Some(_) => (),
+                None => line.draw([x, y, x + w, y], &context, graphics),
+            }
+        }
+    }
+}
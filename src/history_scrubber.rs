@@ -0,0 +1,236 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use graphics;
+use graphics::Context;
+use label;
+use label::FontSize;
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use utils::clamp;
+use widget::Widget::HistoryScrubber;
+
+/// Index into the history.
+pub type Idx = uint;
+pub type Len = uint;
+
+/// The width of the handle marking the current position in the history.
+static HANDLE_WIDTH: f64 = 8.0;
+
+/// Represents the state of the HistoryScrubber widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted,
+    /// The handle is being dragged. Stores the index of the state currently previewed.
+    Clicked(Idx),
+}
+
+impl State {
+    /// Return the associated Rectangle state.
+    fn as_rectangle_state(&self) -> rectangle::State {
+        match self {
+            &State::Normal => rectangle::State::Normal,
+            &State::Highlighted => rectangle::State::Highlighted,
+            &State::Clicked(_) => rectangle::State::Clicked,
+        }
+    }
+}
+
+widget_fns!(HistoryScrubber, State, HistoryScrubber(State::Normal));
+
+/// Map the mouse's x position to the closest index in the history.
+fn idx_at(track_x: f64, track_w: f64, mouse_x: f64, len: Len) -> Idx {
+    if len < 2u { return 0u }
+    let perc = clamp((mouse_x - track_x) / track_w, 0.0, 1.0);
+    (perc * (len - 1u) as f64 + 0.5).floor() as uint
+}
+
+/// Map an index in the history to its x position along the track.
+fn idx_x(track_x: f64, track_w: f64, idx: Idx, len: Len) -> f64 {
+    if len < 2u { return track_x }
+    track_x + track_w * (idx as f64 / (len - 1u) as f64)
+}
+
+/// Check the current state of the scrubber.
+fn get_new_state(is_over: bool,
+                 prev: State,
+                 mouse: Mouse,
+                 mouse_idx: Idx) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::State::{Normal, Highlighted, Clicked};
+    match (is_over, prev, mouse.left) {
+        (true,  Normal,      Down) => Normal,
+        (_,     Clicked(_),  Down) |
+        (true,  Highlighted, Down) => Clicked(mouse_idx),
+        (true,  _,           Up)   => Highlighted,
+        _                          => Normal,
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct HistoryScrubberContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    text_box_id: UIID,
+    text: &'a mut String,
+    snapshots: &'a [(Idx, &'a str)],
+    font_size: FontSize,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<|Idx|:'a>,
+    maybe_preview_callback: Option<|Idx|:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+}
+
+impl<'a> HistoryScrubberContext<'a> {
+    /// Labeled states in the history which are marked with a tick and their label.
+    #[inline]
+    pub fn snapshots(self, snapshots: &'a [(Idx, &'a str)]) -> HistoryScrubberContext<'a> {
+        HistoryScrubberContext { snapshots: snapshots, ..self }
+    }
+    /// Called with the index of the state under the handle whenever the drag previews a
+    /// different state.
+    #[inline]
+    pub fn preview_callback(self, callback: |Idx|:'a) -> HistoryScrubberContext<'a> {
+        HistoryScrubberContext { maybe_preview_callback: Some(callback), ..self }
+    }
+    #[inline]
+    pub fn value_font_size(self, size: FontSize) -> HistoryScrubberContext<'a> {
+        HistoryScrubberContext { font_size: size, ..self }
+    }
+}
+
+pub trait HistoryScrubberBuilder<'a> {
+    /// A history_scrubber builder method to be implemented by the UiContext. The scrubber
+    /// is bound to the edit history that the UiContext keeps for the TextBox of the given
+    /// UIID (see `UiContext::edit_history`), whose `text` it reverts.
+    fn history_scrubber(&'a mut self, ui_id: UIID, text_box_id: UIID,
+                        text: &'a mut String) -> HistoryScrubberContext<'a>;
+}
+
+impl<'a> HistoryScrubberBuilder<'a> for UiContext {
+    fn history_scrubber(&'a mut self, ui_id: UIID, text_box_id: UIID,
+                        text: &'a mut String) -> HistoryScrubberContext<'a> {
+        let pos = self.flow_pos();
+        HistoryScrubberContext {
+            uic: self,
+            ui_id: ui_id,
+            text_box_id: text_box_id,
+            text: text,
+            snapshots: &[],
+            font_size: 14u32,
+            pos: pos,
+            dim: [256.0, 48.0],
+            maybe_callback: None,
+            maybe_preview_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+        }
+    }
+}
+
+impl_callable!(HistoryScrubberContext, |Idx|:'a);
+impl_colorable!(HistoryScrubberContext);
+impl_frameable!(HistoryScrubberContext);
+impl_hoverable!(HistoryScrubberContext);
+impl_positionable!(HistoryScrubberContext);
+impl_shapeable!(HistoryScrubberContext);
+
+impl<'a> ::draw::Drawable for HistoryScrubberContext<'a> {
+    /// Draw the scrubber. Dragging the handle previews a state by undoing or redoing the
+    /// TextBox's edits until it is current, so that the TextBox shows the previewed text.
+    /// Releasing the handle commits the state and calls the `callback` with its index,
+    /// after which the states beyond it may still be redone until the text is edited.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let (len, current) = {
+            let history = self.uic.edit_history(self.text_box_id);
+            (history.len(), history.current())
+        };
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let track_x = self.pos[0] + frame_w + HANDLE_WIDTH / 2.0;
+        let track_w = self.dim[0] - frame_w * 2.0 - HANDLE_WIDTH;
+        let mouse_idx = idx_at(track_x, track_w, mouse.pos[0], len);
+        let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
+        let new_state = get_new_state(is_over, state, mouse, mouse_idx);
+
+        // Preview the state under the handle while dragging.
+        if let State::Clicked(idx) = new_state {
+            let end = self.text.len();
+            let restored = self.uic.edit_history(self.text_box_id)
+                .seek(self.text.as_slice(), end, end, idx);
+            if let Some((text, _, _)) = restored {
+                *self.text = text;
+                match self.maybe_preview_callback {
+                    Some(ref mut callback) => (*callback)(idx),
+                    None => (),
+                }
+            }
+        }
+
+        // Commit on release.
+        match (state, new_state) {
+            (State::Clicked(idx), State::Highlighted) | (State::Clicked(idx), State::Normal) => {
+                match self.maybe_callback {
+                    Some(ref mut callback) => (*callback)(idx),
+                    None => (),
+                }
+            },
+            _ => (),
+        }
+
+        // Draw the track.
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let frame_color = self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, frame_color)),
+            false => None,
+        };
+        rectangle::draw(self.uic.win_w, self.uic.win_h, graphics, rectangle::State::Normal,
+                        self.pos, self.dim, maybe_frame, color);
+
+        // Draw a small tick for every state and a tall, labeled tick for each snapshot.
+        let context = Context::abs(self.uic.win_w, self.uic.win_h);
+        let Color(tick_col) = color.plain_contrast();
+        let tick_line = graphics::Line::new(tick_col, 0.5);
+        let top = self.pos[1] + frame_w;
+        let track_h = self.dim[1] - frame_w * 2.0;
+        if track_w / len as f64 > 2.0 {
+            for i in range(0u, len) {
+                let x = idx_x(track_x, track_w, i, len);
+                tick_line.draw([x, top, x, top + track_h * 0.25], &context, graphics);
+            }
+        }
+        for &(idx, text) in self.snapshots.iter() {
+            if idx >= len { continue }
+            let x = idx_x(track_x, track_w, idx, len);
+            tick_line.draw([x, top, x, top + track_h * 0.5], &context, graphics);
+            let text_w = label::width(self.uic, self.font_size, text);
+            let text_x = clamp(x - text_w / 2.0, self.pos[0], self.pos[0] + self.dim[0] - text_w);
+            let text_y = top + track_h - self.font_size as f64;
+            self.uic.draw_text(graphics, [text_x, text_y], self.font_size,
+                               color.plain_contrast(), text);
+        }
+
+        // Draw the handle at the previewed (or current) state.
+        let handle_idx = match new_state { State::Clicked(idx) => idx, _ => current };
+        let handle_x = idx_x(track_x, track_w, handle_idx, len) - HANDLE_WIDTH / 2.0;
+        rectangle::draw(self.uic.win_w, self.uic.win_h, graphics, new_state.as_rectangle_state(),
+                        [handle_x, top], [HANDLE_WIDTH, track_h], None, color.plain_contrast());
+
+        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+
+    }
+}
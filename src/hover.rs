@@ -0,0 +1,9 @@
+
+/// A trait used for widget contexts that may react to the mouse entering or leaving them,
+/// i.e. for live previews or sounds. The callbacks are called straight away by the builder
+/// method during the frame in which the mouse entered or left the widget's area.
+pub trait Hoverable {
+    fn on_hover_enter(self, callback: ||) -> Self;
+    fn on_hover_leave(self, callback: ||) -> Self;
+}
+
@@ -0,0 +1,159 @@
+
+use input::keyboard::Key;
+
+/// The time (in seconds) for which an editing key must be held before it repeats.
+pub static REPEAT_DELAY: f64 = 0.5;
+/// The time (in seconds) between the repeats of a held editing key.
+pub static REPEAT_INTERVAL: f64 = 0.04;
+
+/// The state of the keyboard's modifier keys.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl Modifiers {
+
+    /// Constructor for a Modifiers struct with no keys held.
+    pub fn new() -> Modifiers {
+        Modifiers { ctrl: false, shift: false, alt: false }
+    }
+
+    /// Update the modifier state for a key that was pressed (`is_down == true`)
+    /// or released.
+    pub fn update(&mut self, key: Key, is_down: bool) {
+        match key {
+            Key::LCtrl | Key::RCtrl => self.ctrl = is_down,
+            Key::LShift | Key::RShift => self.shift = is_down,
+            Key::LAlt | Key::RAlt => self.alt = is_down,
+            _ => (),
+        }
+    }
+
+}
+
+/// An event from an input method that composes text (i.e. for Chinese, Japanese or Korean)
+/// from several key presses.
+#[deriving(Show, PartialEq, Clone)]
+pub enum Composition {
+    /// The text composed so far, which is shown at the cursor but not yet entered. An
+    /// empty string ends the composition without entering any text.
+    Preedit(String),
+    /// The finished composition, which is entered as text.
+    Commit(String),
+}
+
+/// Identifies an application-level shortcut registered with the UiContext.
+pub type ShortcutId = uint;
+
+/// A key pressed with an exact combination of modifier keys, i.e. Ctrl+S.
+#[deriving(PartialEq, Clone, Copy)]
+pub struct Shortcut {
+    pub key: Key,
+    pub modifiers: Modifiers,
+}
+
+impl Shortcut {
+
+    /// Constructor for a Shortcut pressed without any modifier keys.
+    pub fn new(key: Key) -> Shortcut {
+        Shortcut { key: key, modifiers: Modifiers::new() }
+    }
+
+    /// Require Ctrl to be held.
+    pub fn ctrl(self) -> Shortcut {
+        Shortcut { modifiers: Modifiers { ctrl: true, ..self.modifiers }, ..self }
+    }
+
+    /// Require Shift to be held.
+    pub fn shift(self) -> Shortcut {
+        Shortcut { modifiers: Modifiers { shift: true, ..self.modifiers }, ..self }
+    }
+
+    /// Require Alt to be held.
+    pub fn alt(self) -> Shortcut {
+        Shortcut { modifiers: Modifiers { alt: true, ..self.modifiers }, ..self }
+    }
+
+    /// Whether or not pressing the key while holding the given modifiers triggers the
+    /// shortcut.
+    pub fn is_triggered_by(&self, key: Key, modifiers: Modifiers) -> bool {
+        self.key == key && self.modifiers == modifiers
+    }
+
+    /// Whether or not the shortcut could be typed into a text field, in which case it is
+    /// left to a captured TextBox.
+    pub fn is_typeable(&self) -> bool {
+        !self.modifiers.ctrl && !self.modifiers.alt
+    }
+
+}
+
+/// Whether or not the key repeats while held, as the keys for editing text do.
+fn is_repeatable(key: Key) -> bool {
+    match key {
+        Key::Backspace | Key::Delete | Key::Left | Key::Right | Key::Up | Key::Down => true,
+        _ => false,
+    }
+}
+
+/// Generates repeated presses of the editing keys while they are held, so that text
+/// editing behaves the same whether or not the window backend repeats keys itself.
+pub struct KeyRepeat {
+    /// The held keys, each with the time of its next repeat.
+    held: Vec<(Key, f64)>,
+}
+
+impl KeyRepeat {
+
+    /// Constructor for a KeyRepeat with no keys held.
+    pub fn new() -> KeyRepeat {
+        KeyRepeat { held: Vec::new() }
+    }
+
+    /// Register a key that was pressed at the given time. Returns false if the press is a
+    /// repeat of a key that is already held, which should be ignored in favour of the
+    /// repeats returned by `repeats`.
+    pub fn press(&mut self, key: Key, time: f64) -> bool {
+        if !is_repeatable(key) { return true }
+        if self.held.iter().any(|&(held, _)| held == key) { return false }
+        self.held.push((key, time + REPEAT_DELAY));
+        true
+    }
+
+    /// Register a key that was released.
+    pub fn release(&mut self, key: Key) {
+        self.held.retain(|&(held, _)| held != key);
+    }
+
+    /// Return the held keys that are due to repeat at the given time.
+    pub fn repeats(&mut self, time: f64) -> Vec<Key> {
+        let mut keys = Vec::new();
+        for &mut (key, ref mut next) in self.held.iter_mut() {
+            if time >= *next {
+                keys.push(key);
+                *next = time + REPEAT_INTERVAL;
+            }
+        }
+        keys
+    }
+
+}
+
+/// Return the name of the key as shown to the user, i.e. within a button's label.
+pub fn key_name(key: Key) -> String {
+    match key {
+        Key::Return => "Enter".to_string(),
+        Key::Escape => "Esc".to_string(),
+        _ => {
+            let name = format!("{}", key);
+            // The number keys are named "D0" to "D9".
+            match name.as_slice().starts_with("D") && name.len() == 2u {
+                true => name.as_slice().slice_from(1u).to_string(),
+                false => name,
+            }
+        },
+    }
+}
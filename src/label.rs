@@ -1,8 +1,12 @@
 
 use color::Color;
+use dimensions::Dimensions;
 use opengl_graphics::Gl;
 use point::Point;
-use ui_context::UiContext;
+use ui_context::{
+    UIID,
+    UiContext,
+};
 
 pub type FontSize = u32;
 
@@ -12,6 +16,26 @@ pub enum Labeling<'a> {
     NoLabel,
 }
 
+/// Return the largest font size between `maybe_min_size` and `size` at which the width
+/// returned by `width_at` fits within `max_w`. Returns `size` if no minimum is given.
+pub fn fit_font_size(size: FontSize, maybe_min_size: Option<FontSize>, max_w: f64,
+                     width_at: |FontSize| -> f64) -> FontSize {
+    match maybe_min_size {
+        None => size,
+        Some(min_size) => {
+            let mut fitted = size;
+            while fitted > min_size && width_at(fitted) > max_w { fitted -= 1 }
+            fitted
+        },
+    }
+}
+
+/// Return the font size at which the text fits within `max_w` (see `fit_font_size`).
+pub fn shrink_to_fit(uic: &mut UiContext, text: &str, size: FontSize,
+                     maybe_min_size: Option<FontSize>, max_w: f64) -> FontSize {
+    fit_font_size(size, maybe_min_size, max_w, |size| width(uic, size, text))
+}
+
 /// Determine the pixel width of the final text bitmap.
 #[inline]
 pub fn width(uic: &mut UiContext, size: FontSize, text: &str) -> f64 {
@@ -21,6 +45,60 @@ pub fn width(uic: &mut UiContext, size: FontSize, text: &str) -> f64 {
     }) as f64
 }
 
+/// Break the text into lines no wider than `max_w`, splitting at whitespace. A word
+/// wider than `max_w` is given a line of its own.
+pub fn wrap_lines(uic: &mut UiContext, text: &str, size: FontSize, max_w: f64) -> Vec<String> {
+    let space_w = width(uic, size, " ");
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_w = 0.0;
+    for word in text.words() {
+        let word_w = width(uic, size, word);
+        if !line.is_empty() && line_w + space_w + word_w > max_w {
+            lines.push(line);
+            line = String::new();
+            line_w = 0.0;
+        }
+        if !line.is_empty() {
+            line.push(' ');
+            line_w += space_w;
+        }
+        line.push_str(word);
+        line_w += word_w;
+    }
+    if !line.is_empty() { lines.push(line) }
+    lines
+}
+
+/// Return the font size at which the wrapped text fits within `max_h` (see `fit_font_size`).
+pub fn shrink_wrapped_to_fit(uic: &mut UiContext, text: &str, size: FontSize,
+                             maybe_min_size: Option<FontSize>, max_w: f64, max_h: f64) -> FontSize {
+    fit_font_size(size, maybe_min_size, max_h, |size| {
+        wrap_lines(uic, text, size, max_w).len() as f64 * size as f64
+    })
+}
+
+/// Wrap the text to the width of the given rect and draw it with each line centered
+/// horizontally and the block of lines centered vertically.
+pub fn draw_wrapped(uic: &mut UiContext, graphics: &mut Gl, text: &str, pos: Point,
+                    dim: Dimensions, size: FontSize, color: Color) {
+    let lines = wrap_lines(uic, text, size, dim[0]);
+    let line_h = size as f64;
+    let mut y = pos[1] + (dim[1] - lines.len() as f64 * line_h) / 2.0;
+    for line in lines.iter() {
+        let line_w = width(uic, size, line.as_slice());
+        uic.draw_text(graphics, [pos[0] + (dim[0] - line_w) / 2.0, y], size, color, line.as_slice());
+        y += line_h;
+    }
+}
+
+/// Return the y position of the baseline of text drawn at the given y position
+/// (see `UiContext::draw_text`).
+#[inline]
+pub fn baseline(y: f64, size: FontSize) -> f64 {
+    y + size as f64
+}
+
 /// Determine a suitable FontSize from a given rectangle height.
 #[inline]
 pub fn auto_size_from_rect_height(rect_height: f64) -> FontSize {
@@ -34,11 +112,24 @@ pub trait Labelable<'a> {
     fn label_color(self, color: Color) -> Self;
     fn label_rgba(self, r: f32, g: f32, b: f32, a: f32) -> Self;
     fn label_font_size(self, size: FontSize) -> Self;
+    /// Reduce the label's font size (down to `min_size`) when it would overflow the widget.
+    fn shrink_label_to_fit(self, min_size: FontSize) -> Self;
     fn small_font(self) -> Self;
     fn medium_font(self) -> Self;
     fn large_font(self) -> Self;
 }
 
+/// The space left between an auto sized widget's frame and its label.
+pub static AUTO_SIZE_PADDING: f64 = 8.0;
+
+/// A trait used for labelled widget contexts that may be sized to fit their label.
+pub trait AutoSizable {
+    /// Size the widget to fit its label at its font size, plus the frame and
+    /// `AUTO_SIZE_PADDING` at either side. This should follow the label and font size
+    /// methods, as the size is measured when called.
+    fn auto_size(self) -> Self;
+}
+
 
 
 
@@ -58,6 +149,16 @@ impl<'a> LabelContext<'a> {
     pub fn size(self, size: FontSize) -> LabelContext<'a> {
         LabelContext { size: size, ..self }
     }
+    /// Move the label vertically so that its baseline lines up with that of the text
+    /// drawn by the given widget (i.e. a TextBox's text or a Slider's label). This should
+    /// follow `size`. The label is not moved if the widget hasn't drawn any text.
+    pub fn align_baseline_with(self, ui_id: UIID) -> LabelContext<'a> {
+        let pos = match self.uic.get_baseline(ui_id) {
+            Some(y) => [self.pos[0], y - self.size as f64],
+            None => self.pos,
+        };
+        LabelContext { pos: pos, ..self }
+    }
 }
 
 pub trait LabelBuilder<'a> {
@@ -69,10 +170,11 @@ impl<'a> LabelBuilder<'a> for UiContext {
 
     /// A label builder method to be implemented on the UiContext.
     fn label(&'a mut self, text: &'a str) -> LabelContext<'a> {
+        let pos = self.flow_pos();
         LabelContext {
             uic: self,
             text: text,
-            pos: [0.0, 0.0],
+            pos: pos,
             size: 24u32,
             maybe_color: None,
         }
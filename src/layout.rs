@@ -0,0 +1,358 @@
+use std::num::Float;
+use dimensions::Dimensions;
+use point::Point;
+use position::Edge;
+use ui_context::UiContext;
+
+/// Divides a rect into regions docked to its edges, i.e. a toolbar along the top and
+/// a sidebar at the left, leaving the rest to `fill`. Each call to `dock` takes its
+/// region from what remains after the previous calls, so the order of the calls decides
+/// which regions span the corners. A returned rect may be given to `DockLayout::new` to
+/// dock regions within it. The layout should be rebuilt each frame so that it follows
+/// the size of the window.
+#[deriving(Show, Clone, Copy)]
+pub struct DockLayout {
+    pos: Point,
+    dim: Dimensions,
+    spacing: f64,
+}
+
+impl DockLayout {
+
+    /// A layout dividing the given rect.
+    pub fn new(pos: Point, dim: Dimensions) -> DockLayout {
+        DockLayout { pos: pos, dim: dim, spacing: 0.0 }
+    }
+
+    /// A layout dividing the whole window.
+    pub fn window(uic: &UiContext) -> DockLayout {
+        DockLayout::new([0.0, 0.0], [uic.win_w, uic.win_h])
+    }
+
+    /// The space left between each docked region and the rest of the layout.
+    pub fn spacing(self, spacing: f64) -> DockLayout {
+        DockLayout { spacing: spacing, ..self }
+    }
+
+    /// Take a region of the given thickness along the given edge of the remaining rect
+    /// and return its position and dimensions. The thickness is limited to the space left.
+    pub fn dock(&mut self, edge: Edge, size: f64) -> (Point, Dimensions) {
+        let (pos, dim, gap) = (self.pos, self.dim, self.spacing);
+        let along_x = match edge { Edge::Left | Edge::Right => true, Edge::Top | Edge::Bottom => false };
+        let available = if along_x { dim[0] } else { dim[1] };
+        let size = if size < available { size } else { available };
+        let taken = if size + gap < available { size + gap } else { available };
+        let region = match edge {
+            Edge::Left => (pos, [size, dim[1]]),
+            Edge::Right => ([pos[0] + dim[0] - size, pos[1]], [size, dim[1]]),
+            Edge::Top => (pos, [dim[0], size]),
+            Edge::Bottom => ([pos[0], pos[1] + dim[1] - size], [dim[0], size]),
+        };
+        match edge {
+            Edge::Left => { self.pos[0] += taken; self.dim[0] -= taken },
+            Edge::Right => self.dim[0] -= taken,
+            Edge::Top => { self.pos[1] += taken; self.dim[1] -= taken },
+            Edge::Bottom => self.dim[1] -= taken,
+        }
+        region
+    }
+
+    /// Return the position and dimensions of the rect left after all docked regions.
+    pub fn fill(&self) -> (Point, Dimensions) {
+        (self.pos, self.dim)
+    }
+
+}
+
+/// Places rects left to right, wrapping onto a new row whenever the next rect would
+/// extend beyond the given width, i.e. for tag clouds and palettes of any number of
+/// buttons. Each call to `place` returns the position for a rect of the given dimensions.
+#[deriving(Show, Clone, Copy)]
+pub struct WrapLayout {
+    pos: Point,
+    width: f64,
+    spacing: f64,
+    /// The position at which the next rect will be placed if it fits the current row.
+    next: Point,
+    /// The height of the tallest rect in the current row.
+    row_h: f64,
+}
+
+impl WrapLayout {
+
+    /// A layout placing rects within the given width, starting at the given position.
+    pub fn new(pos: Point, width: f64) -> WrapLayout {
+        WrapLayout { pos: pos, width: width, spacing: 0.0, next: pos, row_h: 0.0 }
+    }
+
+    /// The space left between neighbouring rects and between rows.
+    pub fn spacing(self, spacing: f64) -> WrapLayout {
+        WrapLayout { spacing: spacing, ..self }
+    }
+
+    /// Return the position for a rect of the given dimensions. A rect wider than the
+    /// layout is given a row of its own.
+    pub fn place(&mut self, dim: Dimensions) -> Point {
+        let is_row_empty = self.next[0] == self.pos[0];
+        if !is_row_empty && self.next[0] + dim[0] > self.pos[0] + self.width {
+            self.next = [self.pos[0], self.next[1] + self.row_h + self.spacing];
+            self.row_h = 0.0;
+        }
+        let pos = self.next;
+        self.next[0] += dim[0] + self.spacing;
+        if dim[1] > self.row_h { self.row_h = dim[1] }
+        pos
+    }
+
+    /// Return the height covered by the rects placed so far.
+    pub fn height(&self) -> f64 {
+        self.next[1] + self.row_h - self.pos[1]
+    }
+
+}
+
+/// Identifies a rect within a `ConstraintLayout`.
+pub type RectId = uint;
+
+/// The rect covering the window, which is never moved by the solver.
+pub static WINDOW: RectId = 0u;
+
+/// The number of passes over the constraints after which `solve` gives up.
+static MAX_ITERATIONS: uint = 64;
+/// The error below which a constraint is considered satisfied.
+static EPSILON: f64 = 0.01;
+
+/// An edge, center or length of a rect.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Attr {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Width,
+    Height,
+    CenterX,
+    CenterY,
+}
+
+/// How a constrained attribute relates to its expression.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Relation {
+    Equal,
+    AtLeast,
+    AtMost,
+}
+
+/// `target relation source + constant`, or `target relation constant` without a source.
+#[deriving(Show, Clone, Copy)]
+struct Constraint {
+    target: (RectId, Attr),
+    relation: Relation,
+    maybe_source: Option<(RectId, Attr)>,
+    constant: f64,
+}
+
+/// Rects whose edges are related by linear constraints, i.e. `b.left == a.right + 10.0`
+/// and `b.width >= 100.0`. Each rect starts at the position and dimensions it is added
+/// with, and `solve` adjusts them until the constraints hold. A constraint on a left, top
+/// or center moves the target rect, while one on a right, bottom, width or height resizes
+/// it. Constraints are satisfied by relaxation in the order they were added, so when they
+/// conflict those added later take precedence and `solve` reports that they don't all
+/// hold. Attributes that aren't constrained keep the values they were added with.
+///
+/// The UiContext owns a layout (see `UiContext::constraint_layout`) to which rects and
+/// constraints are added once, and solves it against the window's size at each render
+/// event. Widgets are placed within its solved rects with `Shapeable::layout_rect`.
+pub struct ConstraintLayout {
+    rects: Vec<(Point, Dimensions)>,
+    /// The position and dimensions each rect was added with, from which each solve starts.
+    initial_rects: Vec<(Point, Dimensions)>,
+    constraints: Vec<Constraint>,
+    /// Rects whose height is kept at their width divided by the given ratio.
+    aspects: Vec<(RectId, f64)>,
+}
+
+impl ConstraintLayout {
+
+    /// A layout containing only the window's rect.
+    pub fn new(win_w: f64, win_h: f64) -> ConstraintLayout {
+        let window = ([0.0, 0.0], [win_w, win_h]);
+        ConstraintLayout {
+            rects: vec![window],
+            initial_rects: vec![window],
+            constraints: Vec::new(),
+            aspects: Vec::new(),
+        }
+    }
+
+    /// Add a rect, starting at the given position and dimensions.
+    pub fn add_rect(&mut self, pos: Point, dim: Dimensions) -> RectId {
+        self.rects.push((pos, dim));
+        self.initial_rects.push((pos, dim));
+        self.rects.len() - 1u
+    }
+
+    /// Resize the window's rect and return every other rect to the position and
+    /// dimensions it was added with, ready to be solved again.
+    pub fn resize_window(&mut self, win_w: f64, win_h: f64) {
+        self.initial_rects[WINDOW] = ([0.0, 0.0], [win_w, win_h]);
+        self.rects = self.initial_rects.clone();
+    }
+
+    /// Remove every rect but the window's, along with all constraints.
+    pub fn clear(&mut self) {
+        self.rects.truncate(1u);
+        self.initial_rects.truncate(1u);
+        self.constraints.clear();
+        self.aspects.clear();
+    }
+
+    /// Constrain `target` to relate to `source` plus `constant`.
+    pub fn constrain(&mut self, target: (RectId, Attr), relation: Relation,
+                     source: (RectId, Attr), constant: f64) {
+        self.constraints.push(Constraint {
+            target: target,
+            relation: relation,
+            maybe_source: Some(source),
+            constant: constant,
+        });
+    }
+
+    /// Constrain `target` to relate to `constant`.
+    pub fn constrain_to(&mut self, target: (RectId, Attr), relation: Relation, constant: f64) {
+        self.constraints.push(Constraint {
+            target: target,
+            relation: relation,
+            maybe_source: None,
+            constant: constant,
+        });
+    }
+
+    /// Keep the rect's height at its width divided by the given ratio of width to height.
+    pub fn keep_aspect(&mut self, id: RectId, ratio: f64) {
+        self.aspects.push((id, ratio));
+    }
+
+    /// Adjust the rects until all constraints hold (or the iteration limit is reached).
+    /// Returns whether or not they all hold, i.e. false if any of them conflict.
+    pub fn solve(&mut self) -> bool {
+        for _ in range(0u, MAX_ITERATIONS) {
+            let mut max_error = 0.0;
+            for i in range(0u, self.constraints.len()) {
+                let constraint = self.constraints[i];
+                let (target_id, target_attr) = constraint.target;
+                let expected = constraint.constant + match constraint.maybe_source {
+                    Some((id, attr)) => attr_value(self.rects[id], attr),
+                    None => 0.0,
+                };
+                let value = attr_value(self.rects[target_id], target_attr);
+                let error = match constraint.relation {
+                    Relation::Equal => expected - value,
+                    Relation::AtLeast => if value < expected { expected - value } else { 0.0 },
+                    Relation::AtMost => if value > expected { expected - value } else { 0.0 },
+                };
+                if target_id == WINDOW || error == 0.0 { continue }
+                max_error = if error.abs() > max_error { error.abs() } else { max_error };
+                match &mut self.rects[target_id] {
+                    &(ref mut pos, ref mut dim) => match target_attr {
+                        Attr::Left | Attr::CenterX => pos[0] += error,
+                        Attr::Top | Attr::CenterY => pos[1] += error,
+                        Attr::Right | Attr::Width => dim[0] += error,
+                        Attr::Bottom | Attr::Height => dim[1] += error,
+                    },
+                }
+            }
+            for &(id, ratio) in self.aspects.iter() {
+                if id == WINDOW { continue }
+                match &mut self.rects[id] {
+                    &(_, ref mut dim) => {
+                        let error = dim[0] / ratio - dim[1];
+                        max_error = if error.abs() > max_error { error.abs() } else { max_error };
+                        dim[1] += error;
+                    },
+                }
+            }
+            if max_error < EPSILON { return true }
+        }
+        false
+    }
+
+    /// Return the position and dimensions of the given rect.
+    pub fn rect(&self, id: RectId) -> (Point, Dimensions) {
+        self.rects[id]
+    }
+
+}
+
+/// Return the value of the given attribute of a rect.
+fn attr_value((pos, dim): (Point, Dimensions), attr: Attr) -> f64 {
+    match attr {
+        Attr::Left => pos[0],
+        Attr::Right => pos[0] + dim[0],
+        Attr::Top => pos[1],
+        Attr::Bottom => pos[1] + dim[1],
+        Attr::Width => dim[0],
+        Attr::Height => dim[1],
+        Attr::CenterX => pos[0] + dim[0] / 2.0,
+        Attr::CenterY => pos[1] + dim[1] / 2.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::Float;
+    use super::{Attr, ConstraintLayout, Relation, WINDOW};
+
+    #[test]
+    fn satisfiable_constraints_hold() {
+        let mut layout = ConstraintLayout::new(800.0, 600.0);
+        let a = layout.add_rect([0.0, 0.0], [100.0, 50.0]);
+        let b = layout.add_rect([0.0, 0.0], [100.0, 50.0]);
+        layout.constrain((a, Attr::Left), Relation::Equal, (WINDOW, Attr::Left), 10.0);
+        layout.constrain((b, Attr::Left), Relation::Equal, (a, Attr::Right), 10.0);
+        layout.constrain((b, Attr::Right), Relation::Equal, (WINDOW, Attr::Right), -10.0);
+        layout.constrain_to((b, Attr::Width), Relation::AtLeast, 200.0);
+        assert!(layout.solve());
+        let (a_pos, _) = layout.rect(a);
+        let (b_pos, b_dim) = layout.rect(b);
+        assert!((a_pos[0] - 10.0).abs() < 0.1);
+        assert!((b_pos[0] - 120.0).abs() < 0.1);
+        assert!((b_pos[0] + b_dim[0] - 790.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn conflicting_constraints_are_reported_and_the_latest_wins() {
+        let mut layout = ConstraintLayout::new(800.0, 600.0);
+        let a = layout.add_rect([0.0, 0.0], [100.0, 50.0]);
+        layout.constrain_to((a, Attr::Width), Relation::Equal, 100.0);
+        layout.constrain_to((a, Attr::Width), Relation::AtLeast, 200.0);
+        assert!(!layout.solve());
+        let (_, a_dim) = layout.rect(a);
+        assert!((a_dim[0] - 200.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn unconstrained_attributes_keep_their_initial_values() {
+        let mut layout = ConstraintLayout::new(800.0, 600.0);
+        let a = layout.add_rect([30.0, 40.0], [100.0, 50.0]);
+        layout.constrain_to((a, Attr::Left), Relation::Equal, 10.0);
+        assert!(layout.solve());
+        let (a_pos, a_dim) = layout.rect(a);
+        assert!((a_pos[0] - 10.0).abs() < 0.1);
+        assert_eq!(a_pos[1], 40.0);
+        assert_eq!(a_dim, [100.0, 50.0]);
+    }
+
+    #[test]
+    fn resolving_after_resizing_the_window_follows_its_new_size() {
+        let mut layout = ConstraintLayout::new(800.0, 600.0);
+        let a = layout.add_rect([0.0, 0.0], [100.0, 50.0]);
+        layout.constrain((a, Attr::Right), Relation::Equal, (WINDOW, Attr::Right), -10.0);
+        assert!(layout.solve());
+        layout.resize_window(400.0, 300.0);
+        assert_eq!(layout.rect(a), ([0.0, 0.0], [100.0, 50.0]));
+        assert!(layout.solve());
+        let (a_pos, a_dim) = layout.rect(a);
+        assert!((a_pos[0] + a_dim[0] - 390.0).abs() < 0.1);
+    }
+}
@@ -0,0 +1,137 @@
+
+use button::ButtonBuilder;
+use callback::Callable;
+use color::{Color, Colorable};
+use draw::Drawable;
+use label::{LabelBuilder, Labelable};
+use opengl_graphics::Gl;
+use position::Positionable;
+use serialize::{
+    json,
+    Encodable,
+    Decodable
+};
+use shape::Shapeable;
+use slider::SliderBuilder;
+use std::io::File;
+use std::str;
+use toggle::ToggleBuilder;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+
+/// A widget declared within a layout file.
+#[deriving(Show, Clone, Encodable, Decodable)]
+pub struct WidgetDesc {
+    /// One of "button", "toggle", "slider" or "label".
+    pub kind: String,
+    pub id: UIID,
+    pub x: f64,
+    pub y: f64,
+    pub w: f64,
+    pub h: f64,
+    pub label: Option<String>,
+    pub color: Option<Color>,
+    /// A slider's value, or a toggle's value as 0.0 or 1.0. This is updated as the
+    /// widget is interacted with.
+    pub value: Option<f64>,
+    /// The range of a slider's value, which defaults to 0.0 to 1.0.
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// Something that happened to one of a layout's widgets during `UiLayout::draw`.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Event {
+    Clicked,
+    Toggled(bool),
+    Changed(f64),
+}
+
+/// A list of widgets loaded from a JSON file, so that the arrangement of a UI may
+/// be changed without recompiling.
+#[deriving(Show, Clone, Encodable, Decodable)]
+pub struct UiLayout {
+    pub widgets: Vec<WidgetDesc>,
+}
+
+static KINDS: &'static [&'static str] = &["button", "toggle", "slider", "label"];
+
+impl UiLayout {
+
+    /// Load a layout from file. Fails if any widget is of an unknown kind.
+    pub fn load(path: &str) -> Result<UiLayout, String> {
+        let contents = match File::open(&Path::new(path)).read_to_end() {
+            Ok(buf) => buf,
+            Err(e) => return Err(format!("Failed to load UiLayout correctly: {}", e)),
+        };
+        let layout: UiLayout = match str::from_utf8(contents.as_slice()).map(|s| json::decode(s)) {
+            Some(Ok(layout)) => layout,
+            Some(Err(e)) => return Err(format!("Failed to load UiLayout correctly: {}", e)),
+            None => return Err(String::from_str("Failed to load UiLayout correctly")),
+        };
+        match layout.widgets.iter().find(|w| !KINDS.contains(&w.kind.as_slice())) {
+            Some(w) => Err(format!("Failed to load UiLayout correctly: unknown kind \"{}\"", w.kind)),
+            None => Ok(layout),
+        }
+    }
+
+    /// Save a layout to file (i.e. with the widgets' latest values).
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let json_string = json::encode(self);
+        let mut file = File::create(&Path::new(path));
+        match file.write(json_string.as_bytes()) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(format!("UiLayout failed to save correctly: {}", e)),
+        }
+    }
+
+    /// Draw each widget with the corresponding builder. The `callback` is called with
+    /// the id of each widget that was clicked, toggled or changed.
+    pub fn draw(&mut self, uic: &mut UiContext, graphics: &mut Gl, callback: |UIID, Event|) {
+        for desc in self.widgets.iter_mut() {
+            let color = desc.color.unwrap_or(uic.theme.shape_color);
+            let label_color = desc.color.unwrap_or(uic.theme.label_color);
+            let label = desc.label.clone().unwrap_or(String::new());
+            let mut maybe_event = None;
+            match desc.kind.as_slice() {
+                "button" => uic.button(desc.id)
+                    .position(desc.x, desc.y)
+                    .dimensions(desc.w, desc.h)
+                    .color(color)
+                    .label(label.as_slice())
+                    .callback(|| maybe_event = Some(Event::Clicked))
+                    .draw(graphics),
+                "toggle" => uic.toggle(desc.id, desc.value.unwrap_or(0.0) != 0.0)
+                    .position(desc.x, desc.y)
+                    .dimensions(desc.w, desc.h)
+                    .color(color)
+                    .label(label.as_slice())
+                    .callback(|value| maybe_event = Some(Event::Toggled(value)))
+                    .draw(graphics),
+                "slider" => {
+                    let (min, max) = (desc.min.unwrap_or(0.0), desc.max.unwrap_or(1.0));
+                    uic.slider(desc.id, desc.value.unwrap_or(min), min, max)
+                        .position(desc.x, desc.y)
+                        .dimensions(desc.w, desc.h)
+                        .color(color)
+                        .label(label.as_slice())
+                        .callback(|value| maybe_event = Some(Event::Changed(value)))
+                        .draw(graphics)
+                },
+                _ => uic.label(label.as_slice())
+                    .position(desc.x, desc.y)
+                    .color(label_color)
+                    .draw(graphics),
+            }
+            match maybe_event {
+                Some(Event::Toggled(value)) => desc.value = Some(if value { 1.0 } else { 0.0 }),
+                Some(Event::Changed(value)) => desc.value = Some(value),
+                _ => (),
+            }
+            if let Some(event) = maybe_event { callback(desc.id, event) }
+        }
+    }
+
+}
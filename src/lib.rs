@@ -11,56 +11,143 @@ extern crate serialize;
 extern crate time;
 extern crate vecmath;
 
+// The gl bindings loaded by opengl_graphics, so that our own gl calls (i.e. clipping) go
+// through the same function pointers rather than a second, unloaded instance of the crate.
+use opengl_graphics::gl;
+
+pub use ab_toggle::ABToggleBuilder as ABToggle;
 pub use background::BackgroundBuilder as Background;
 pub use button::ButtonBuilder as Button;
+pub use canvas::CanvasBuilder as Canvas;
+pub use compound::CompoundBuilder as Compound;
+pub use dock::DockBuilder as Dock;
+pub use drawer::DrawerBuilder as Drawer;
 pub use drop_down_list::DropDownListBuilder as DropDownList;
+pub use drop_target::DropTargetBuilder as DropTarget;
 pub use envelope_editor::EnvelopeEditorBuilder as EnvelopeEditor;
 pub use envelope_editor::EnvelopePoint;
+pub use group_box::GroupBoxBuilder as GroupBox;
+pub use history_scrubber::HistoryScrubberBuilder as HistoryScrubber;
 pub use label::LabelBuilder as Label;
+pub use link::LinkBuilder as Link;
+pub use minimap::MinimapBuilder as Minimap;
+pub use node_graph::NodeGraphBuilder as NodeGraph;
 pub use number_dialer::NumberDialerBuilder as NumberDialer;
+pub use pagination::PaginationBuilder as Pagination;
+pub use picture_in_picture::PictureInPictureBuilder as PictureInPicture;
+pub use progress_circle::ProgressCircleBuilder as ProgressCircle;
+pub use rating::RatingBuilder as Rating;
+pub use reorder_list::ReorderListBuilder as ReorderList;
+pub use resizable_panel::ResizablePanelBuilder as ResizablePanel;
+pub use scroll_area::ScrollAreaBuilder as ScrollArea;
+pub use separator::SeparatorBuilder as Separator;
 pub use slider::SliderBuilder as Slider;
+pub use split::SplitBuilder as Split;
+pub use stack::StackBuilder as Stack;
+pub use stack::Size as StackSize;
+pub use stepper::StepperBuilder as Stepper;
+pub use stopwatch::StopwatchBuilder as Stopwatch;
 pub use text_box::TextBoxBuilder as TextBox;
+pub use text_box::History as TextBoxHistory;
+pub use timeline::TimelineBuilder as Timeline;
 pub use toggle::ToggleBuilder as Toggle;
+pub use virtual_keyboard::VirtualKeyboardBuilder as VirtualKeyboard;
 pub use widget_matrix::WidgetMatrixBuilder as WidgetMatrix;
+pub use window::WindowBuilder as Window;
 pub use xy_pad::XYPadBuilder as XYPad;
 
+pub use badge::Badgeable;
 pub use callback::Callable;
+pub use clipboard::Clipboard;
 pub use color::{Color, Colorable};
+pub use controller::ControllerButton;
 pub use dimensions::Dimensions;
 pub use draw::Drawable;
+pub use enable::Enableable;
 pub use frame::{Framing, Frameable};
-pub use label::Labelable;
+pub use hover::Hoverable;
+pub use keyboard::{Composition, Shortcut};
+pub use label::{AutoSizable, Labelable};
+pub use layout::{ConstraintLayout, DockLayout, WrapLayout};
+pub use layout_file::UiLayout;
+pub use overlay::Layerable;
+pub use padding::Paddable;
 pub use point::Point;
-pub use position::Positionable;
+pub use position::{Anchor, Constraints, Positionable};
+pub use rectangle::Gradient;
+pub use shadow::Shadow;
 pub use shape::Shapeable;
-pub use theme::Theme;
+pub use theme::{Theme, WidgetTheme};
+pub use touch::View;
 pub use ui_context::UiContext;
 pub use widget::Widget;
 
 pub mod macros;
 
+pub mod ab_toggle;
 pub mod background;
+pub mod badge;
 pub mod button;
 pub mod callback;
+pub mod canvas;
+pub mod clipboard;
 pub mod color;
+pub mod color_blind;
+pub mod compound;
+pub mod controller;
 pub mod dimensions;
+pub mod dock;
+pub mod drag;
 pub mod draw;
+pub mod drawer;
 pub mod drop_down_list;
+pub mod drop_target;
+pub mod enable;
+pub mod focus;
 pub mod envelope_editor;
 pub mod frame;
+pub mod group_box;
+pub mod history_scrubber;
+pub mod hover;
+pub mod keyboard;
 pub mod label;
+pub mod layout;
+pub mod layout_file;
+pub mod link;
+pub mod minimap;
 pub mod mouse;
+pub mod node_graph;
 pub mod number_dialer;
+pub mod overlay;
+pub mod padding;
+pub mod pagination;
+pub mod picture_in_picture;
 pub mod point;
 pub mod position;
+pub mod progress_circle;
+pub mod rating;
 pub mod rectangle;
+pub mod reorder_list;
+pub mod resizable_panel;
+pub mod scroll_area;
+pub mod separator;
+pub mod shadow;
 pub mod shape;
 pub mod slider;
+pub mod snap;
+pub mod split;
+pub mod stack;
+pub mod stepper;
+pub mod stopwatch;
 pub mod text_box;
 pub mod theme;
+pub mod timeline;
 pub mod toggle;
+pub mod touch;
 pub mod ui_context;
 pub mod utils;
+pub mod virtual_keyboard;
 pub mod widget;
 pub mod widget_matrix;
+pub mod window;
 pub mod xy_pad;
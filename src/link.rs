@@ -0,0 +1,132 @@
+
+use color::Color;
+use dimensions::Dimensions;
+use graphics;
+use graphics::Context;
+use label;
+use label::FontSize;
+use mouse::{
+    Cursor,
+    Mouse,
+};
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use widget::Widget::Link;
+
+/// Represents the state of the Link widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted,
+    Clicked,
+}
+
+widget_fns!(Link, State, Link(State::Normal));
+
+/// Check the current state of the link.
+fn get_new_state(is_over: bool,
+                 prev: State,
+                 mouse: Mouse) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::State::{Normal, Highlighted, Clicked};
+    match (is_over, prev, mouse.left) {
+        (true,  Normal,  Down) => Normal,
+        (true,  _,       Down) => Clicked,
+        (true,  _,       Up)   => Highlighted,
+        (false, Clicked, Down) => Clicked,
+        _                      => Normal,
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct LinkContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    text: &'a str,
+    size: FontSize,
+    pos: Point,
+    maybe_callback: Option<||:'a>,
+    maybe_color: Option<Color>,
+}
+
+impl<'a> LinkContext<'a> {
+    /// A builder method for specifying font_size.
+    #[inline]
+    pub fn size(self, size: FontSize) -> LinkContext<'a> {
+        LinkContext { size: size, ..self }
+    }
+}
+
+pub trait LinkBuilder<'a> {
+    /// A link builder method to be implemented by the UiContext.
+    fn link(&'a mut self, ui_id: UIID, text: &'a str) -> LinkContext<'a>;
+}
+
+impl<'a> LinkBuilder<'a> for UiContext {
+    fn link(&'a mut self, ui_id: UIID, text: &'a str) -> LinkContext<'a> {
+        let size = self.theme.font_size_small;
+        let pos = self.flow_pos();
+        LinkContext {
+            uic: self,
+            ui_id: ui_id,
+            text: text,
+            size: size,
+            pos: pos,
+            maybe_callback: None,
+            maybe_color: None,
+        }
+    }
+}
+
+impl_callable!(LinkContext, ||:'a);
+impl_colorable!(LinkContext);
+impl_hoverable!(LinkContext);
+impl_positionable!(LinkContext);
+
+impl<'a> ::draw::Drawable for LinkContext<'a> {
+    /// Draw the link text, underlined while hovered. The `callback` is called
+    /// when the link is clicked.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let dim: Dimensions = [label::width(self.uic, self.size, self.text), self.size as f64];
+        let is_over = rectangle::is_over(self.pos, mouse.pos, dim);
+        let new_state = get_new_state(is_over, state, mouse);
+
+        // Callback.
+        match (is_over, state, new_state) {
+            (true, State::Clicked, State::Highlighted) => match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(), None => (),
+            }, _ => (),
+        }
+
+        // Draw.
+        let color = self.maybe_color.unwrap_or(self.uic.theme.link_color());
+        let color = match new_state {
+            State::Normal => color,
+            State::Highlighted => color.highlighted(),
+            State::Clicked => color.clicked(),
+        };
+        self.uic.draw_text(graphics, self.pos, self.size, color, self.text);
+        match new_state {
+            State::Normal => (),
+            State::Highlighted | State::Clicked => {
+                self.uic.set_cursor(Cursor::Hand);
+                let context = Context::abs(self.uic.win_w, self.uic.win_h);
+                let Color(col) = color;
+                let y = self.pos[1] + dim[1] + 1.0;
+                graphics::Line::round(col, 0.5)
+                    .draw([self.pos[0], y, self.pos[0] + dim[0], y], &context, graphics);
+            },
+        }
+
+        set_state(self.uic, self.ui_id, new_state, self.pos, dim);
+
+    }
+}
@@ -53,6 +53,45 @@ macro_rules! impl_callable(
     )
 );
 
+/// Simplify implementation of the `AutoSizable` trait.
+macro_rules! impl_auto_sizable(
+    ($context:ident $(, $t:ident)*) => (
+        impl<'a $(, $t)*> ::label::AutoSizable for $context<'a $(, $t)*> {
+            #[inline]
+            fn auto_size(self) -> $context<'a $(, $t)*> {
+                let size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_medium);
+                let text_w = match self.maybe_label {
+                    Some(text) => ::label::width(self.uic, size, text),
+                    None => 0.0,
+                };
+                let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+                let pad = (frame_w + ::label::AUTO_SIZE_PADDING) * 2.0;
+                $context { dim: [text_w + pad, size as f64 + pad], ..self }
+            }
+        }
+    )
+);
+
+/// Simplify implementation of the `Badgeable` trait.
+macro_rules! impl_badgeable(
+    ($context:ident $(, $t:ident)*) => (
+        impl<'a $(, $t)*> ::badge::Badgeable for $context<'a $(, $t)*> {
+            #[inline]
+            fn badge(self, count: u32) -> $context<'a $(, $t)*> {
+                $context { maybe_badge: Some(count), ..self }
+            }
+            #[inline]
+            fn badge_color(self, color: ::color::Color) -> $context<'a $(, $t)*> {
+                $context { maybe_badge_color: Some(color), ..self }
+            }
+            #[inline]
+            fn badge_rgba(self, r: f32, g: f32, b: f32, a: f32) -> $context<'a $(, $t)*> {
+                $context { maybe_badge_color: Some(::color::Color::new(r, g, b, a)), ..self }
+            }
+        }
+    )
+);
+
 /// Simplify implementation of the `Colorable` trait.
 macro_rules! impl_colorable(
     ($context:ident $(, $t:ident)*) => (
@@ -69,6 +108,18 @@ macro_rules! impl_colorable(
     )
 );
 
+/// Simplify implementation of the `Enableable` trait.
+macro_rules! impl_enableable(
+    ($context:ident $(, $t:ident)*) => (
+        impl<'a $(, $t)*> ::enable::Enableable for $context<'a $(, $t)*> {
+            #[inline]
+            fn enabled(self, enabled: bool) -> $context<'a $(, $t)*> {
+                $context { is_enabled: enabled, ..self }
+            }
+        }
+    )
+);
+
 /// Simplify implementation of the `Frameable` trait.
 macro_rules! impl_frameable(
     ($context:ident $(, $t:ident)*) => (
@@ -89,6 +140,38 @@ macro_rules! impl_frameable(
     )
 );
 
+/// Simplify implementation of the `Layerable` trait.
+macro_rules! impl_layerable(
+    ($context:ident $(, $t:ident)*) => (
+        impl<'a $(, $t)*> ::overlay::Layerable for $context<'a $(, $t)*> {
+            #[inline]
+            fn depth(self, depth: i32) -> $context<'a $(, $t)*> {
+                $context { depth: depth, ..self }
+            }
+        }
+    )
+);
+
+/// Simplify implementation of the `Hoverable` trait.
+macro_rules! impl_hoverable(
+    ($context:ident $(, $t:ident)*) => (
+        impl<'a $(, $t)*> ::hover::Hoverable for $context<'a $(, $t)*> {
+            #[inline]
+            fn on_hover_enter(self, callback: ||) -> $context<'a $(, $t)*> {
+                let mut callback = callback;
+                if self.uic.hover_entered(self.ui_id) { callback() }
+                self
+            }
+            #[inline]
+            fn on_hover_leave(self, callback: ||) -> $context<'a $(, $t)*> {
+                let mut callback = callback;
+                if self.uic.hover_left(self.ui_id) { callback() }
+                self
+            }
+        }
+    )
+);
+
 /// Simplify implementation of the `Labelable` trait.
 macro_rules! impl_labelable(
     ($context:ident $(, $t:ident)*) => (
@@ -110,6 +193,10 @@ macro_rules! impl_labelable(
                 $context { maybe_label_font_size: Some(size), ..self }
             }
             #[inline]
+            fn shrink_label_to_fit(self, min_size: u32) -> $context<'a $(, $t)*> {
+                $context { maybe_label_min_font_size: Some(min_size), ..self }
+            }
+            #[inline]
             fn small_font(self) -> $context<'a $(, $t)*> {
                 let size = self.uic.theme.font_size_small;
                 $context { maybe_label_font_size: Some(size), ..self }
@@ -128,6 +215,22 @@ macro_rules! impl_labelable(
     )
 );
 
+/// Simplify implementation of the `Paddable` trait.
+macro_rules! impl_paddable(
+    ($context:ident $(, $t:ident)*) => (
+        impl<'a $(, $t)*> ::padding::Paddable for $context<'a $(, $t)*> {
+            #[inline]
+            fn padding(self, padding: f64) -> $context<'a $(, $t)*> {
+                $context { maybe_padding: Some(padding), ..self }
+            }
+            #[inline]
+            fn margin(self, margin: f64) -> $context<'a $(, $t)*> {
+                $context { maybe_margin: Some(margin), ..self }
+            }
+        }
+    )
+);
+
 /// Simplify implementation of the `Positionable` trait.
 macro_rules! impl_positionable(
     ($context:ident $(, $t:ident)*) => (
@@ -135,12 +238,20 @@ macro_rules! impl_positionable(
 
             #[inline]
             fn point(self, pos: Point) -> $context<'a $(,$t)*> {
+                let pos = self.uic.to_window(pos);
                 $context { pos: pos, ..self }
             }
 
             #[inline]
             fn position(self, x: f64, y: f64) -> $context<'a $(,$t)*> {
-                $context { pos: [x, y], ..self }
+                let pos = self.uic.to_window([x, y]);
+                $context { pos: pos, ..self }
+            }
+
+            #[inline]
+            fn position_percent(self, x: f64, y: f64) -> $context<'a $(,$t)*> {
+                let pos = [self.uic.win_w * x / 100.0, self.uic.win_h * y / 100.0];
+                $context { pos: pos, ..self }
             }
 
             #[inline]
@@ -185,6 +296,17 @@ macro_rules! impl_positionable(
                 $context { pos: [x, y], ..self }
             }
 
+            #[inline]
+            fn align_left_with(self, uiid: u64) -> $context<'a $(,$t)*> {
+                let (x, y) = self.uic.get_placing(uiid).align_left(self.pos);
+                $context { pos: [x, y], ..self }
+            }
+            #[inline]
+            fn align_top_with(self, uiid: u64) -> $context<'a $(,$t)*> {
+                let (x, y) = self.uic.get_placing(uiid).align_top(self.pos);
+                $context { pos: [x, y], ..self }
+            }
+
         }
     )
 );
@@ -209,6 +331,73 @@ macro_rules! impl_shapeable(
             fn height(self, height: f64) -> $context<'a $(, $t)*> {
                 $context { dim: [self.dim[0], height], ..self }
             }
+            #[inline]
+            fn dimensions_with_aspect(self, width: f64, ratio: f64) -> $context<'a $(, $t)*> {
+                $context { dim: [width, width / ratio], ..self }
+            }
+            #[inline]
+            fn dim_percent(self, width: f64, height: f64) -> $context<'a $(, $t)*> {
+                let dim = [self.uic.win_w * width / 100.0, self.uic.win_h * height / 100.0];
+                $context { dim: dim, ..self }
+            }
+            #[inline]
+            fn width_percent(self, width: f64) -> $context<'a $(, $t)*> {
+                let width = self.uic.win_w * width / 100.0;
+                $context { dim: [width, self.dim[1]], ..self }
+            }
+            #[inline]
+            fn height_percent(self, height: f64) -> $context<'a $(, $t)*> {
+                let height = self.uic.win_h * height / 100.0;
+                $context { dim: [self.dim[0], height], ..self }
+            }
+            #[inline]
+            fn anchor(self, anchor: ::position::Anchor, offset: ::point::Point) -> $context<'a $(, $t)*> {
+                let pos = anchor.position(offset, self.dim, self.uic.win_w, self.uic.win_h);
+                $context { pos: pos, ..self }
+            }
+            #[inline]
+            fn center_horizontally(self) -> $context<'a $(, $t)*> {
+                let x = (self.uic.win_w - self.dim[0]) / 2.0;
+                $context { pos: [x, self.pos[1]], ..self }
+            }
+            #[inline]
+            fn center_vertically(self) -> $context<'a $(, $t)*> {
+                let y = (self.uic.win_h - self.dim[1]) / 2.0;
+                $context { pos: [self.pos[0], y], ..self }
+            }
+            #[inline]
+            fn center_of_window(self) -> $context<'a $(, $t)*> {
+                let pos = [(self.uic.win_w - self.dim[0]) / 2.0, (self.uic.win_h - self.dim[1]) / 2.0];
+                $context { pos: pos, ..self }
+            }
+            #[inline]
+            fn align_left_of_window(self, padding: f64) -> $context<'a $(, $t)*> {
+                $context { pos: [padding, self.pos[1]], ..self }
+            }
+            #[inline]
+            fn align_right_of_window(self, padding: f64) -> $context<'a $(, $t)*> {
+                let x = self.uic.win_w - self.dim[0] - padding;
+                $context { pos: [x, self.pos[1]], ..self }
+            }
+            #[inline]
+            fn align_top_of_window(self, padding: f64) -> $context<'a $(, $t)*> {
+                $context { pos: [self.pos[0], padding], ..self }
+            }
+            #[inline]
+            fn align_bottom_of_window(self, padding: f64) -> $context<'a $(, $t)*> {
+                let y = self.uic.win_h - self.dim[1] - padding;
+                $context { pos: [self.pos[0], y], ..self }
+            }
+            #[inline]
+            fn constrain(self, constraints: ::position::Constraints) -> $context<'a $(, $t)*> {
+                let (pos, dim) = constraints.resolve(self.uic.win_w, self.uic.win_h);
+                $context { pos: pos, dim: dim, ..self }
+            }
+            #[inline]
+            fn layout_rect(self, id: ::layout::RectId) -> $context<'a $(, $t)*> {
+                let (pos, dim) = self.uic.get_layout_rect(id);
+                $context { pos: pos, dim: dim, ..self }
+            }
         }
     )
 );
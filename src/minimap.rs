@@ -0,0 +1,197 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use graphics;
+use graphics::{
+    Context,
+    RelativeTransform,
+};
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use utils::clamp;
+use vecmath::{
+    vec2_add,
+    vec2_sub,
+};
+use widget::Widget::Minimap;
+
+/// Represents the state of the Minimap widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted,
+    /// The viewport is being dragged. Stores the mouse offset from the
+    /// viewport rect's position on the minimap.
+    Dragging(Point),
+}
+
+widget_fns!(Minimap, State, Minimap(State::Normal));
+
+/// A context on which the builder pattern can be implemented.
+pub struct MinimapContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    content_dim: Dimensions,
+    view_dim: Dimensions,
+    scroll: &'a mut Point,
+    pos: Point,
+    dim: Dimensions,
+    maybe_draw_fn: Option<|&mut Gl, &Context|:'a>,
+    maybe_callback: Option<|Point|:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+}
+
+impl<'a> MinimapContext<'a> {
+    /// The closure used to draw the overview of the content. The given Context is
+    /// scaled so that the content may be drawn using its own co-ordinates.
+    #[inline]
+    pub fn draw_fn(self, draw_fn: |&mut Gl, &Context|:'a) -> MinimapContext<'a> {
+        MinimapContext { maybe_draw_fn: Some(draw_fn), ..self }
+    }
+}
+
+pub trait MinimapBuilder<'a> {
+    /// A minimap builder method to be implemented by the UiContext. `content_dim`
+    /// is the size of the whole scrollable area, `view_dim` the size of the part of
+    /// it that is visible and `scroll` the offset of the visible part.
+    fn minimap(&'a mut self, ui_id: UIID, content_dim: Dimensions, view_dim: Dimensions,
+               scroll: &'a mut Point) -> MinimapContext<'a>;
+}
+
+impl<'a> MinimapBuilder<'a> for UiContext {
+    fn minimap(&'a mut self, ui_id: UIID, content_dim: Dimensions, view_dim: Dimensions,
+               scroll: &'a mut Point) -> MinimapContext<'a> {
+        let pos = self.flow_pos();
+        MinimapContext {
+            uic: self,
+            ui_id: ui_id,
+            content_dim: content_dim,
+            view_dim: view_dim,
+            scroll: scroll,
+            pos: pos,
+            dim: [128.0, 128.0],
+            maybe_draw_fn: None,
+            maybe_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+        }
+    }
+}
+
+impl_callable!(MinimapContext, |Point|:'a);
+impl_colorable!(MinimapContext);
+impl_frameable!(MinimapContext);
+impl_hoverable!(MinimapContext);
+impl_positionable!(MinimapContext);
+impl_shapeable!(MinimapContext);
+
+/// Check the current state of the minimap.
+fn get_new_state(is_over: bool,
+                 is_over_view: bool,
+                 prev: State,
+                 view_pos: Point,
+                 view_dim: Dimensions,
+                 mouse: Mouse) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::State::{Normal, Highlighted, Dragging};
+    match (is_over, prev, mouse.left) {
+        (_,    Dragging(grab), Down) => Dragging(grab),
+        // Pressing outside of the viewport rect centers the viewport upon the mouse.
+        (true, Highlighted,    Down) => match is_over_view {
+            true => Dragging(vec2_sub(mouse.pos, view_pos)),
+            false => Dragging([view_dim[0] / 2.0, view_dim[1] / 2.0]),
+        },
+        (true, _,              Up)   => Highlighted,
+        _                            => Normal,
+    }
+}
+
+impl<'a> ::draw::Drawable for MinimapContext<'a> {
+    /// Draw the minimap. Dragging the viewport rect writes the new offset to
+    /// `scroll` and calls the `callback` with it.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let inner_pos = vec2_add(self.pos, [frame_w, frame_w]);
+        let inner_dim = vec2_sub(self.dim, [frame_w * 2.0, frame_w * 2.0]);
+        let inner_dim = [inner_dim[0].max(0.0), inner_dim[1].max(0.0)];
+        // Treat empty content as a single pixel so that the scale stays finite.
+        let content_dim = [self.content_dim[0].max(1.0), self.content_dim[1].max(1.0)];
+        let scale = [inner_dim[0] / content_dim[0], inner_dim[1] / content_dim[1]];
+        let to_map = |p: Point| [inner_pos[0] + p[0] * scale[0], inner_pos[1] + p[1] * scale[1]];
+        let view_map_dim = [self.view_dim[0] * scale[0], self.view_dim[1] * scale[1]];
+        let view_map_pos = to_map(*self.scroll);
+        let is_over = rectangle::is_over(inner_pos, mouse.pos, inner_dim);
+        let is_over_view = rectangle::is_over(view_map_pos, mouse.pos, view_map_dim);
+        let new_state = get_new_state(is_over, is_over_view, state,
+                                      view_map_pos, view_map_dim, mouse);
+
+        // Edit the scroll position while dragging, unless the map has no area to drag across.
+        let is_scalable = scale[0] > 0.0 && scale[1] > 0.0;
+        if let (State::Dragging(grab), true) = (new_state, is_scalable) {
+            let max_x = (self.content_dim[0] - self.view_dim[0]).max(0.0);
+            let max_y = (self.content_dim[1] - self.view_dim[1]).max(0.0);
+            let new_map_pos = vec2_sub(mouse.pos, grab);
+            let new_scroll = [clamp((new_map_pos[0] - inner_pos[0]) / scale[0], 0.0, max_x),
+                              clamp((new_map_pos[1] - inner_pos[1]) / scale[1], 0.0, max_y)];
+            if new_scroll != *self.scroll {
+                *self.scroll = new_scroll;
+                match self.maybe_callback {
+                    Some(ref mut callback) => (*callback)(new_scroll),
+                    None => (),
+                }
+            }
+        }
+
+        // Draw the backdrop and the scaled overview of the content.
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let frame_color = self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, frame_color)),
+            false => None,
+        };
+        rectangle::draw(self.uic.win_w, self.uic.win_h, graphics, rectangle::State::Normal,
+                        self.pos, self.dim, maybe_frame, color);
+        match self.maybe_draw_fn {
+            Some(ref mut draw_fn) => {
+                let context = Context::abs(self.uic.win_w, self.uic.win_h)
+                    .trans(inner_pos[0], inner_pos[1])
+                    .scale(scale[0], scale[1]);
+                self.uic.push_clip(inner_pos, inner_dim);
+                (*draw_fn)(graphics, &context);
+                self.uic.pop_clip();
+            },
+            None => (),
+        }
+
+        // Draw the viewport rect.
+        let view_map_pos = to_map(*self.scroll);
+        let view_color = match new_state {
+            State::Normal => color.plain_contrast(),
+            State::Highlighted => color.plain_contrast().highlighted(),
+            State::Dragging(_) => color.plain_contrast().clicked(),
+        };
+        let context = Context::abs(self.uic.win_w, self.uic.win_h);
+        let Color(col) = view_color;
+        let line = graphics::Line::new(col, 0.75);
+        let (x, y, w, h) = (view_map_pos[0], view_map_pos[1], view_map_dim[0], view_map_dim[1]);
+        line.draw([x, y, x + w, y], &context, graphics);
+        line.draw([x + w, y, x + w, y + h], &context, graphics);
+        line.draw([x, y + h, x + w, y + h], &context, graphics);
+        line.draw([x, y, x, y + h], &context, graphics);
+
+        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+
+    }
+}
@@ -1,6 +1,20 @@
 
 use point::Point;
 
+/// The longest time (in seconds) between two presses of the left button for them
+/// to be recognised as a double click.
+pub static DOUBLE_CLICK_SECS: f64 = 0.3;
+/// The furthest the mouse may move between the two presses of a double click.
+pub static DOUBLE_CLICK_DISTANCE: f64 = 4.0;
+/// The default distance the mouse must move with the left button held before the press
+/// becomes a drag.
+pub static DEFAULT_DRAG_THRESHOLD: f64 = 4.0;
+/// The factor by which the drag threshold is scaled while a finger drives the mouse (see
+/// `UiContext::handle_touch`), as a finger moves further than a mouse during a tap.
+pub static TOUCH_DRAG_THRESHOLD_SCALE: f64 = 3.0;
+/// The factor by which widgets slow a drag while Shift is held, for fine adjustment.
+pub static FINE_DRAG_SCALE: f64 = 0.1;
+
 /// Represents the current state of a mouse button.
 #[deriving(Show, Clone, Copy)]
 pub enum ButtonState {
@@ -12,9 +26,26 @@ pub enum ButtonState {
 #[deriving(Copy)]
 pub struct Mouse {
     pub pos: Point,
+    /// The position of the mouse during the previous frame.
+    pub prev_pos: Point,
+    /// The left button, with which widgets are pressed.
     pub left: ButtonState,
+    /// The middle button, i.e. for panning.
     pub middle: ButtonState,
+    /// The right button, i.e. for opening context menus.
     pub right: ButtonState,
+    /// The mouse wheel movement along each axis accumulated during the current frame.
+    pub scroll: Point,
+    /// Whether the left button was pressed for the second time of a double click
+    /// during the current frame.
+    pub is_double_click: bool,
+    /// The pressure with which a pen is pressed against a tablet, from 0.0 to 1.0. This is
+    /// always 1.0 for a mouse.
+    pub pressure: f64,
+    /// The pressure of the pen during the previous frame.
+    pub prev_pressure: f64,
+    /// The tilt of a pen along each axis, from -1.0 to 1.0 (zero while upright or for a mouse).
+    pub tilt: [f64, ..2],
 }
 
 impl Mouse {
@@ -23,6 +54,45 @@ impl Mouse {
                left: ButtonState,
                middle: ButtonState,
                right: ButtonState) -> Mouse {
-        Mouse { pos: pos, left: left, middle: middle, right: right }
+        Mouse {
+            pos: pos,
+            prev_pos: pos,
+            left: left,
+            middle: middle,
+            right: right,
+            scroll: [0.0, 0.0],
+            is_double_click: false,
+            pressure: 1.0,
+            prev_pressure: 1.0,
+            tilt: [0.0, 0.0],
+        }
+    }
+
+    /// Return the mouse with its positions relative to the given point, i.e. the top left
+    /// corner of a widget.
+    pub fn relative_to(&self, origin: Point) -> Mouse {
+        Mouse {
+            pos: [self.pos[0] - origin[0], self.pos[1] - origin[1]],
+            prev_pos: [self.prev_pos[0] - origin[0], self.prev_pos[1] - origin[1]],
+            ..*self
+        }
     }
 }
+
+/// The mouse cursor requested by the widget under the mouse. Conrod doesn't own the
+/// window, so the application should apply `UiContext::get_cursor` after drawing.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Cursor {
+    Default,
+    /// Over something that may be clicked, i.e. a Button or Link.
+    Hand,
+    /// An I-beam over editable text.
+    Text,
+    Move,
+    /// Over something that is resized from side to side, i.e. a horizontal Split's divider.
+    ResizeHorizontal,
+    /// Over something that is resized up and down.
+    ResizeVertical,
+    /// Over something resized from its bottom right corner.
+    ResizeDiagonal,
+}
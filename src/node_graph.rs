@@ -0,0 +1,360 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use graphics;
+use graphics::Context;
+use label;
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use vecmath::{
+    vec2_add,
+    vec2_sub,
+};
+use widget::Widget::NodeGraph;
+
+/// The width of every node.
+static NODE_WIDTH: f64 = 120.0;
+/// The height of a node's title bar.
+static TITLE_HEIGHT: f64 = 20.0;
+/// The height of each row of ports beneath the title bar.
+static PORT_ROW_HEIGHT: f64 = 18.0;
+/// The radius of a port.
+static PORT_RADIUS: f64 = 5.0;
+/// The number of line segments used to draw each connection curve.
+static CURVE_SEGMENTS: uint = 24;
+
+/// Index of a node within the graph / index of a port within a node.
+pub type NodeIdx = uint;
+pub type PortIdx = uint;
+
+/// A node within the graph. The node's position is relative to the top left of the widget.
+#[deriving(Show, Clone)]
+pub struct Node {
+    pub pos: Point,
+    pub title: String,
+    pub num_inputs: uint,
+    pub num_outputs: uint,
+}
+
+impl Node {
+    /// Constructor for a Node.
+    pub fn new(title: &str, pos: Point, num_inputs: uint, num_outputs: uint) -> Node {
+        Node { pos: pos, title: title.to_string(), num_inputs: num_inputs, num_outputs: num_outputs }
+    }
+    /// The node's dimensions, which depend upon its number of ports.
+    fn dim(&self) -> Dimensions {
+        let rows = if self.num_inputs > self.num_outputs { self.num_inputs } else { self.num_outputs };
+        [NODE_WIDTH, TITLE_HEIGHT + PORT_ROW_HEIGHT * rows as f64 + PORT_RADIUS]
+    }
+}
+
+/// A connection from a node's output port to another node's input port.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct Connection {
+    pub from: (NodeIdx, PortIdx),
+    pub to: (NodeIdx, PortIdx),
+}
+
+/// The events for which the `callback` is called.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// The node was dropped at the given position.
+    Moved(NodeIdx, Point),
+    Connected(Connection),
+    Disconnected(Connection),
+}
+
+/// Represents the elements that make up the NodeGraph.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Element {
+    Node(NodeIdx),
+    Input(NodeIdx, PortIdx),
+    Output(NodeIdx, PortIdx),
+}
+
+/// Represents the interaction with the NodeGraph.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted(Element),
+    /// A node is being dragged. Stores the mouse offset from the node's position.
+    DraggingNode(NodeIdx, Point),
+    /// A connection is being dragged from the given output port.
+    DraggingWire(NodeIdx, PortIdx),
+}
+
+widget_fns!(NodeGraph, State, NodeGraph(State::Normal));
+
+/// Return the position of the given port in window co-ordinates.
+fn port_pos(origin: Point, node: &Node, is_output: bool, port: PortIdx) -> Point {
+    let x = if is_output { node.pos[0] + NODE_WIDTH } else { node.pos[0] };
+    let y = node.pos[1] + TITLE_HEIGHT + PORT_ROW_HEIGHT * (port as f64 + 0.5);
+    vec2_add(origin, [x, y])
+}
+
+/// Whether or not the point lies within the given port.
+fn is_over_port(port: Point, p: Point) -> bool {
+    let d = vec2_sub(p, port);
+    d[0] * d[0] + d[1] * d[1] <= PORT_RADIUS * PORT_RADIUS * 2.0
+}
+
+/// Return the element under the mouse (if any). Nodes later in the list are drawn on
+/// top, so they are checked first.
+fn is_over(origin: Point, mouse_pos: Point, nodes: &Vec<Node>) -> Option<Element> {
+    for (i, node) in nodes.iter().enumerate().rev() {
+        for port in range(0u, node.num_inputs) {
+            if is_over_port(port_pos(origin, node, false, port), mouse_pos) {
+                return Some(Element::Input(i, port))
+            }
+        }
+        for port in range(0u, node.num_outputs) {
+            if is_over_port(port_pos(origin, node, true, port), mouse_pos) {
+                return Some(Element::Output(i, port))
+            }
+        }
+        if rectangle::is_over(vec2_add(origin, node.pos), mouse_pos, node.dim()) {
+            return Some(Element::Node(i))
+        }
+    }
+    None
+}
+
+/// Check the current state of the NodeGraph. Dragging from an input port is handled
+/// separately, as it detaches the input's connection.
+fn get_new_state(is_over_elem: Option<Element>,
+                 prev: State,
+                 origin: Point,
+                 nodes: &Vec<Node>,
+                 mouse: Mouse) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::State::{Normal, Highlighted, DraggingNode, DraggingWire};
+    match (is_over_elem, prev, mouse.left) {
+        (_,          DraggingNode(i, grab), Down) => DraggingNode(i, grab),
+        (_,          DraggingWire(i, p),    Down) => DraggingWire(i, p),
+        (Some(Element::Node(i)), Highlighted(Element::Node(h)), Down) if i == h =>
+            DraggingNode(i, vec2_sub(mouse.pos, vec2_add(origin, nodes[i].pos))),
+        (Some(Element::Output(i, p)), Highlighted(Element::Output(h, hp)), Down) if i == h && p == hp =>
+            DraggingWire(i, p),
+        (Some(elem), _,                     Up)   => Highlighted(elem),
+        _                                         => Normal,
+    }
+}
+
+/// Draw a curve between two points that leaves and arrives horizontally.
+fn draw_curve(context: &Context, graphics: &mut Gl, a: Point, b: Point, line: &graphics::Line) {
+    let handle = ((b[0] - a[0]) / 2.0).abs().max(40.0);
+    let (c1, c2) = ([a[0] + handle, a[1]], [b[0] - handle, b[1]]);
+    let point_at = |t: f64| -> Point {
+        let u = 1.0 - t;
+        let (w0, w1, w2, w3) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+        [w0 * a[0] + w1 * c1[0] + w2 * c2[0] + w3 * b[0],
+         w0 * a[1] + w1 * c1[1] + w2 * c2[1] + w3 * b[1]]
+    };
+    let mut prev = a;
+    for i in range(1u, CURVE_SEGMENTS + 1u) {
+        let next = point_at(i as f64 / CURVE_SEGMENTS as f64);
+        line.draw([prev[0], prev[1], next[0], next[1]], context, graphics);
+        prev = next;
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct NodeGraphContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    nodes: &'a mut Vec<Node>,
+    connections: &'a mut Vec<Connection>,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<|Event|:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+}
+
+impl<'a> NodeGraphContext<'a> {
+    #[inline]
+    pub fn label_color(self, color: Color) -> NodeGraphContext<'a> {
+        NodeGraphContext { maybe_label_color: Some(color), ..self }
+    }
+    #[inline]
+    pub fn label_font_size(self, size: u32) -> NodeGraphContext<'a> {
+        NodeGraphContext { maybe_label_font_size: Some(size), ..self }
+    }
+    /// Call the callback (if there is one) with the given event.
+    fn event(&mut self, event: Event) {
+        match self.maybe_callback {
+            Some(ref mut callback) => (*callback)(event),
+            None => (),
+        }
+    }
+}
+
+pub trait NodeGraphBuilder<'a> {
+    /// A node_graph builder method to be implemented by the UiContext.
+    fn node_graph(&'a mut self, ui_id: UIID,
+                  nodes: &'a mut Vec<Node>,
+                  connections: &'a mut Vec<Connection>) -> NodeGraphContext<'a>;
+}
+
+impl<'a> NodeGraphBuilder<'a> for UiContext {
+    fn node_graph(&'a mut self, ui_id: UIID,
+                  nodes: &'a mut Vec<Node>,
+                  connections: &'a mut Vec<Connection>) -> NodeGraphContext<'a> {
+        let pos = self.flow_pos();
+        NodeGraphContext {
+            uic: self,
+            ui_id: ui_id,
+            nodes: nodes,
+            connections: connections,
+            pos: pos,
+            dim: [512.0, 384.0],
+            maybe_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+        }
+    }
+}
+
+impl_callable!(NodeGraphContext, |Event|:'a);
+impl_colorable!(NodeGraphContext);
+impl_frameable!(NodeGraphContext);
+impl_hoverable!(NodeGraphContext);
+impl_positionable!(NodeGraphContext);
+impl_shapeable!(NodeGraphContext);
+
+impl<'a> ::draw::Drawable for NodeGraphContext<'a> {
+    /// Draw the graph. Nodes are moved by dragging their body, outputs are connected by
+    /// dragging from an output port to an input port, and connections are detached by
+    /// dragging them away from their input port. The `callback` is called with each Event.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let origin = self.pos;
+        let is_over_graph = rectangle::is_over(self.pos, mouse.pos, self.dim);
+        let is_over_elem = if is_over_graph { is_over(origin, mouse.pos, self.nodes) } else { None };
+        let mut new_state = get_new_state(is_over_elem, state, origin, self.nodes, mouse);
+
+        // Pressing a connected input port picks up its connection from the input end.
+        if let (State::Highlighted(Element::Input(n, p)), State::Normal) = (state, new_state) {
+            if let ::mouse::ButtonState::Down = mouse.left {
+                if let Some(idx) = self.connections.iter().position(|c| c.to == (n, p)) {
+                    let connection = self.connections.remove(idx).unwrap();
+                    let (from_n, from_p) = connection.from;
+                    self.event(Event::Disconnected(connection));
+                    new_state = State::DraggingWire(from_n, from_p);
+                }
+            }
+        }
+
+        // Handle drops.
+        match (state, new_state) {
+            (State::DraggingNode(i, grab), State::DraggingNode(_, _)) => {
+                self.nodes[i].pos = vec2_sub(vec2_sub(mouse.pos, grab), origin);
+            },
+            (State::DraggingNode(i, grab), _) => {
+                let pos = vec2_sub(vec2_sub(mouse.pos, grab), origin);
+                self.nodes[i].pos = pos;
+                self.event(Event::Moved(i, pos));
+            },
+            (State::DraggingWire(_, _), State::DraggingWire(_, _)) => (),
+            (State::DraggingWire(n, p), _) => match is_over_elem {
+                Some(Element::Input(to_n, to_p)) if to_n != n => {
+                    // Each input accepts a single connection, so replace any existing one.
+                    if let Some(idx) = self.connections.iter().position(|c| c.to == (to_n, to_p)) {
+                        let old = self.connections.remove(idx).unwrap();
+                        self.event(Event::Disconnected(old));
+                    }
+                    let connection = Connection { from: (n, p), to: (to_n, to_p) };
+                    self.connections.push(connection);
+                    self.event(Event::Connected(connection));
+                },
+                _ => (),
+            },
+            _ => (),
+        }
+
+        // Draw.
+        let (win_w, win_h) = (self.uic.win_w, self.uic.win_h);
+        let context = Context::abs(win_w, win_h);
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let frame_color = self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, frame_color)),
+            false => None,
+        };
+        rectangle::draw(win_w, win_h, graphics, rectangle::State::Normal,
+                        self.pos, self.dim, maybe_frame, color * Color::new(0.6, 0.6, 0.6, 1.0));
+        self.uic.push_clip(self.pos, self.dim);
+
+        // Connections.
+        let Color(wire_col) = frame_color.plain_contrast();
+        let wire = graphics::Line::round(wire_col, 1.0);
+        for connection in self.connections.iter() {
+            let (from_n, from_p) = connection.from;
+            let (to_n, to_p) = connection.to;
+            if from_n >= self.nodes.len() || to_n >= self.nodes.len() { continue }
+            let a = port_pos(origin, &self.nodes[from_n], true, from_p);
+            let b = port_pos(origin, &self.nodes[to_n], false, to_p);
+            draw_curve(&context, graphics, a, b, &wire);
+        }
+        if let State::DraggingWire(n, p) = new_state {
+            let a = port_pos(origin, &self.nodes[n], true, p);
+            draw_curve(&context, graphics, a, mouse.pos, &wire);
+        }
+
+        // Nodes.
+        let t_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+        let t_size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_small);
+        for i in range(0u, self.nodes.len()) {
+            let (node_pos, node_dim) = (vec2_add(origin, self.nodes[i].pos), self.nodes[i].dim());
+            let title_state = match new_state {
+                State::Highlighted(Element::Node(h)) if h == i => rectangle::State::Highlighted,
+                State::DraggingNode(d, _) if d == i => rectangle::State::Clicked,
+                _ => rectangle::State::Normal,
+            };
+            rectangle::draw(win_w, win_h, graphics, rectangle::State::Normal,
+                            node_pos, node_dim, maybe_frame, color);
+            let title = self.nodes[i].title.clone();
+            let title_size = label::shrink_to_fit(self.uic, title.as_slice(), t_size, Some(8),
+                                                  NODE_WIDTH - PORT_RADIUS * 2.0);
+            rectangle::draw_with_centered_label(
+                win_w, win_h, graphics, self.uic, title_state, node_pos, [NODE_WIDTH, TITLE_HEIGHT],
+                maybe_frame, color.clicked(), title.as_slice(), title_size, t_color
+            );
+            let node = &self.nodes[i];
+            for &(is_output, num_ports) in [(false, node.num_inputs), (true, node.num_outputs)].iter() {
+                for port in range(0u, num_ports) {
+                    let elem = if is_output { Element::Output(i, port) } else { Element::Input(i, port) };
+                    let Color(port_col) = match new_state {
+                        State::Highlighted(h) if h == elem => frame_color.highlighted(),
+                        State::DraggingWire(n, p) if is_output && n == i && p == port => frame_color.clicked(),
+                        _ => frame_color,
+                    };
+                    let c = port_pos(origin, node, is_output, port);
+                    graphics::Ellipse::new(port_col).draw(
+                        [c[0] - PORT_RADIUS, c[1] - PORT_RADIUS, PORT_RADIUS * 2.0, PORT_RADIUS * 2.0],
+                        &context, graphics
+                    );
+                }
+            }
+        }
+
+        self.uic.pop_clip();
+        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+
+    }
+}
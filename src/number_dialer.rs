@@ -294,6 +294,7 @@ pub struct NumberDialerContext<'a, T> {
     maybe_label: Option<&'a str>,
     maybe_label_color: Option<Color>,
     maybe_label_font_size: Option<u32>,
+    maybe_label_min_font_size: Option<u32>,
     maybe_callback: Option<|T|:'a>,
 }
 
@@ -309,13 +310,14 @@ NumberDialerBuilder<'a, T> for UiContext {
     /// A number_dialer builder method to be implemented by the UiContext.
     fn number_dialer(&'a mut self, ui_id: UIID, value: T, min: T, max: T,
                      precision: u8) -> NumberDialerContext<'a, T> {
+        let pos = self.flow_pos();
         NumberDialerContext {
             uic: self,
             ui_id: ui_id,
             value: clamp(value, min, max),
             min: min,
             max: max,
-            pos: [0.0, 0.0],
+            pos: pos,
             dim: [128.0, 48.0],
             precision: precision,
             maybe_color: None,
@@ -324,6 +326,7 @@ NumberDialerBuilder<'a, T> for UiContext {
             maybe_label: None,
             maybe_label_color: None,
             maybe_label_font_size: None,
+            maybe_label_min_font_size: None,
             maybe_callback: None,
         }
     }
@@ -332,6 +335,7 @@ NumberDialerBuilder<'a, T> for UiContext {
 impl_callable!(NumberDialerContext, |T|:'a, T);
 impl_colorable!(NumberDialerContext, T);
 impl_frameable!(NumberDialerContext, T);
+impl_hoverable!(NumberDialerContext, T);
 impl_labelable!(NumberDialerContext, T);
 impl_positionable!(NumberDialerContext, T);
 impl_shapeable!(NumberDialerContext, T);
@@ -345,11 +349,12 @@ impl<'a, T: Float + Copy + Primitive + FromPrimitive + ToPrimitive + ToString>
     fn draw(&mut self, graphics: &mut Gl) {
 
         let state = *get_state(self.uic, self.ui_id);
-        let mouse = self.uic.get_mouse_state();
-        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let style = self.uic.theme.style(self.uic.theme.maybe_number_dialer);
+        let frame_w = self.maybe_frame.unwrap_or(style.frame_width);
         let frame_w2 = frame_w * 2.0;
         let maybe_frame = match frame_w > 0.0 {
-            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(style.frame_color))),
             false => None,
         };
         let pad_h = self.dim[1] - frame_w2;
@@ -358,13 +363,19 @@ impl<'a, T: Float + Copy + Primitive + FromPrimitive + ToPrimitive + ToString>
             Some(text) => format!("{}: ", text),
             None => String::new(),
         };
+        let val_string_len = self.max.to_string().len() + if self.precision == 0 { 0u }
+                                                          else { 1u + self.precision as uint };
+        let mut val_string = create_val_string(self.value, val_string_len, self.precision);
+        let font_size = {
+            let uic = &mut *self.uic;
+            label::fit_font_size(font_size, self.maybe_label_min_font_size, self.dim[0] - frame_w2, |size| {
+                label::width(uic, size, label_string[]) + val_string_width(size, &val_string)
+            })
+        };
         let label_dim = match label_string.len() {
             0u => [0.0, 0.0],
             _ => [label::width(self.uic, font_size, label_string[]), font_size as f64],
         };
-        let val_string_len = self.max.to_string().len() + if self.precision == 0 { 0u }
-                                                          else { 1u + self.precision as uint };
-        let mut val_string = create_val_string(self.value, val_string_len, self.precision);
         let (val_string_w, val_string_h) = (val_string_width(font_size, &val_string), font_size as f64);
         let label_x = self.pos[0] + (self.dim[0] - (label_dim[0] + val_string_w)) / 2.0;
         let label_y = self.pos[1] + (self.dim[1] - font_size as f64) / 2.0;
@@ -373,14 +384,14 @@ impl<'a, T: Float + Copy + Primitive + FromPrimitive + ToPrimitive + ToString>
                                    label_pos, label_dim, val_string_w, val_string_h,
                                    val_string.len());
         let new_state = get_new_state(is_over_elem, state, mouse);
-        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let color = self.maybe_color.unwrap_or(style.shape_color);
 
         // Draw the widget rectangle.
         rectangle::draw(self.uic.win_w, self.uic.win_h, graphics, rectangle::State::Normal,
                         self.pos, self.dim, maybe_frame, color);
 
         // If there's a label, draw it.
-        let val_string_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+        let val_string_color = self.maybe_label_color.unwrap_or(style.label_color);
         if self.maybe_label.is_some() {
             self.uic.draw_text(graphics, label_pos, font_size, val_string_color, label_string[]);
         };
@@ -397,6 +408,13 @@ impl<'a, T: Float + Copy + Primitive + FromPrimitive + ToPrimitive + ToString>
             }, _ => self.value,
         };
 
+        // Turning the mouse wheel over a glyph steps the value by that glyph's unit.
+        let new_val = match (new_state, mouse.scroll[1]) {
+            (State::Highlighted(Element::ValueGlyph(idx, _)), dy) if dy != 0.0 =>
+                get_new_value(new_val, self.min, self.max, idx, compare_f64s(0.0, dy), &val_string),
+            _ => new_val,
+        };
+
         // If the value has changed, create a new string for val_string.
         if self.value != new_val {
             val_string = create_val_string(new_val, val_string_len, self.precision)
@@ -0,0 +1,85 @@
+
+use color::Color;
+use dimensions::Dimensions;
+use gl;
+use graphics;
+use graphics::{
+    Context,
+    RelativeTransform,
+};
+use label::FontSize;
+use opengl_graphics::{
+    Gl,
+    Texture,
+};
+use point::Point;
+use rectangle;
+use shadow::Shadow;
+use ui_context::UiContext;
+
+/// A shape drawn by a popup (i.e. an open DropDownList) during the overlay pass, so
+/// that it appears above all widgets drawn after the popup within the same frame.
+pub enum Primitive {
+    /// A rectangle drawn with `rectangle::draw`.
+    Rect(rectangle::State, Point, Dimensions, Option<(f64, Color)>, Color),
+    /// A rectangle drawn with `rectangle::draw_with_centered_label`.
+    LabelledRect(rectangle::State, Point, Dimensions, Option<(f64, Color)>, Color,
+                 String, FontSize, Color),
+    /// A line between the two points `[x1, y1, x2, y2]` with the given radius.
+    Line([f64, ..4], f64, Color),
+    /// A "not allowed" indicator drawn with `drag::draw_not_allowed` about the given point.
+    NotAllowed(Point),
+    /// A shadow drawn with `shadow::draw` beneath the given rect.
+    Shadow(Point, Dimensions, Shadow),
+    /// The texture with the given GL id, width and height stretched over the given rect.
+    /// The texture must live until the overlay has been drawn.
+    Image(gl::types::GLuint, u32, u32, Point, Dimensions),
+    /// Restrict the following primitives to the given rectangle.
+    Clip(Point, Dimensions),
+    /// Remove the latest clipping rectangle, restoring the one beneath it (if any).
+    ClearClip,
+}
+
+/// A trait used for widget context types that are drawn during the overlay pass, where
+/// those of a greater depth are drawn above those of a lower one whatever order they are
+/// drawn in (see `UiContext::push_overlay_at_depth`). All other widgets are drawn
+/// immediately, in the order that they're drawn.
+pub trait Layerable {
+    fn depth(self, depth: i32) -> Self;
+}
+
+/// Draw the given primitives in order.
+pub fn draw(uic: &mut UiContext, graphics: &mut Gl, primitives: &[Primitive]) {
+    let (win_w, win_h) = (uic.win_w, uic.win_h);
+    let context = Context::abs(win_w, win_h);
+    for primitive in primitives.iter() {
+        match *primitive {
+            Primitive::Rect(state, pos, dim, maybe_frame, color) =>
+                rectangle::draw(win_w, win_h, graphics, state, pos, dim, maybe_frame, color),
+            Primitive::LabelledRect(state, pos, dim, maybe_frame, color,
+                                    ref text, font_size, text_color) =>
+                rectangle::draw_with_centered_label(
+                    win_w, win_h, graphics, uic, state, pos, dim, maybe_frame, color,
+                    text.as_slice(), font_size, text_color
+                ),
+            Primitive::Line(line, radius, color) => {
+                let Color(col) = color;
+                graphics::Line::new(col, radius).draw(line, &context, graphics);
+            },
+            Primitive::NotAllowed(center) =>
+                ::drag::draw_not_allowed(win_w, win_h, graphics, center),
+            Primitive::Shadow(pos, dim, shadow) =>
+                ::shadow::draw(win_w, win_h, graphics, pos, dim, shadow),
+            Primitive::Image(id, tex_w, tex_h, pos, dim) => if tex_w > 0 && tex_h > 0 {
+                let image_context = context.trans(pos[0], pos[1])
+                    .scale(dim[0] / tex_w as f64, dim[1] / tex_h as f64);
+                // The Texture would delete the GL texture when dropped, which its owner does.
+                let texture = Texture::new(id, tex_w, tex_h);
+                graphics::Image::new().draw(&texture, &image_context, graphics);
+                unsafe { ::std::mem::forget(texture) }
+            },
+            Primitive::Clip(pos, dim) => uic.push_clip(pos, dim),
+            Primitive::ClearClip => uic.pop_clip(),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+
+use dimensions::Dimensions;
+use point::Point;
+
+/// A trait used for widget contexts whose content may be inset from their frame and whose
+/// rect may be inset from the area they are placed in.
+pub trait Paddable {
+    /// The space between the widget's frame and its content (i.e. label or value text).
+    fn padding(self, padding: f64) -> Self;
+    /// The space between the widget and the edges of the area it is placed in. The area
+    /// including the margin is stored as the widget's Placing, so that widgets positioned
+    /// relative to this one are spaced from it.
+    fn margin(self, margin: f64) -> Self;
+}
+
+/// Return the given rect shrunk by `by` on every side.
+pub fn inset(pos: Point, dim: Dimensions, by: f64) -> (Point, Dimensions) {
+    ([pos[0] + by, pos[1] + by], [dim[0] - by * 2.0, dim[1] - by * 2.0])
+}
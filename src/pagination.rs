@@ -0,0 +1,231 @@
+
+use color::Color;
+use dimensions::Dimensions;
+use label::FontSize;
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use widget::Widget::Pagination;
+
+/// Page index / number of pages.
+pub type Idx = uint;
+pub type Len = uint;
+
+/// Represents the clickable elements of the Pagination widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Element {
+    Prev,
+    Page(Idx),
+    Next,
+}
+
+/// Represents the state of the Pagination widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted(Element),
+    Clicked(Element),
+}
+
+widget_fns!(Pagination, State, Pagination(State::Normal));
+
+/// A single slot within the pagination bar.
+#[deriving(Show, PartialEq, Clone, Copy)]
+enum Slot {
+    Button(Element),
+    Ellipsis,
+}
+
+/// Determine the slots to display for the given page, number of pages and
+/// maximum number of page slots. The first and last pages are always shown
+/// and gaps in the sequence are represented by an ellipsis.
+fn page_slots(page: Idx, num_pages: Len, max_slots: uint) -> Vec<Slot> {
+    let mut slots = vec![Slot::Button(Element::Prev)];
+    if num_pages <= max_slots || max_slots < 5u {
+        for i in range(0u, num_pages) {
+            slots.push(Slot::Button(Element::Page(i)));
+        }
+    } else {
+        // Leave room for the first and last pages and an ellipsis either side.
+        let window = max_slots - 4u;
+        let last = num_pages - 1u;
+        let start = if page < 1u + window / 2u { 1u }
+                    else if page + window - window / 2u > last { last - window }
+                    else { page - window / 2u };
+        let end = start + window;
+        slots.push(Slot::Button(Element::Page(0u)));
+        if start > 1u { slots.push(Slot::Ellipsis) }
+        for i in range(start, end) {
+            slots.push(Slot::Button(Element::Page(i)));
+        }
+        if end < last { slots.push(Slot::Ellipsis) }
+        slots.push(Slot::Button(Element::Page(last)));
+    }
+    slots.push(Slot::Button(Element::Next));
+    slots
+}
+
+/// Return the element under the mouse (if any).
+fn is_over(pos: Point, mouse_pos: Point, dim: Dimensions, slots: &Vec<Slot>) -> Option<Element> {
+    match rectangle::is_over(pos, mouse_pos, dim) {
+        false => None,
+        true => {
+            let slot_w = dim[0] / slots.len() as f64;
+            let idx = ((mouse_pos[0] - pos[0]) / slot_w) as uint;
+            match slots.get(idx) {
+                Some(&Slot::Button(elem)) => Some(elem),
+                _ => None,
+            }
+        },
+    }
+}
+
+/// Check and return the current state of the Pagination widget.
+fn get_new_state(is_over_elem: Option<Element>,
+                 prev: State,
+                 mouse: Mouse) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::State::{Normal, Highlighted, Clicked};
+    match (is_over_elem, prev, mouse.left) {
+        (Some(_),    Normal,          Down) => Normal,
+        (Some(elem), _,               Up)   => Highlighted(elem),
+        (Some(elem), Highlighted(_),  Down) => Clicked(elem),
+        (_,          Clicked(p_elem), Down) => Clicked(p_elem),
+        _                                   => Normal,
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct PaginationContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    page: Idx,
+    num_pages: Len,
+    max_slots: uint,
+    font_size: FontSize,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<|Idx|:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+}
+
+impl<'a> PaginationContext<'a> {
+    /// The maximum number of page slots to display (including ellipses) before
+    /// the page numbers are abbreviated.
+    #[inline]
+    pub fn max_pages_shown(self, max: uint) -> PaginationContext<'a> {
+        PaginationContext { max_slots: max, ..self }
+    }
+    #[inline]
+    pub fn font_size(self, size: FontSize) -> PaginationContext<'a> {
+        PaginationContext { font_size: size, ..self }
+    }
+}
+
+pub trait PaginationBuilder<'a> {
+    /// A pagination builder method to be implemented by the UiContext.
+    fn pagination(&'a mut self, ui_id: UIID, page: Idx, num_pages: Len) -> PaginationContext<'a>;
+}
+
+impl<'a> PaginationBuilder<'a> for UiContext {
+    fn pagination(&'a mut self, ui_id: UIID, page: Idx, num_pages: Len) -> PaginationContext<'a> {
+        let pos = self.flow_pos();
+        PaginationContext {
+            uic: self,
+            ui_id: ui_id,
+            page: page,
+            num_pages: num_pages,
+            max_slots: 9u,
+            font_size: 18u32,
+            pos: pos,
+            dim: [320.0, 32.0],
+            maybe_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+        }
+    }
+}
+
+impl_callable!(PaginationContext, |Idx|:'a);
+impl_colorable!(PaginationContext);
+impl_frameable!(PaginationContext);
+impl_hoverable!(PaginationContext);
+impl_positionable!(PaginationContext);
+impl_shapeable!(PaginationContext);
+
+impl<'a> ::draw::Drawable for PaginationContext<'a> {
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let slots = page_slots(self.page, self.num_pages, self.max_slots);
+        let is_over_elem = is_over(self.pos, mouse.pos, self.dim, &slots);
+        let new_state = get_new_state(is_over_elem, state, mouse);
+
+        // Callback if an element was released upon.
+        let maybe_new_page = match (state, new_state) {
+            (State::Clicked(elem), State::Highlighted(new_elem)) if elem == new_elem => match elem {
+                Element::Prev if self.page > 0u => Some(self.page - 1u),
+                Element::Next if self.page + 1u < self.num_pages => Some(self.page + 1u),
+                Element::Page(idx) if idx != self.page => Some(idx),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(page) = maybe_new_page {
+            match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(page),
+                None => (),
+            }
+        }
+
+        // Draw.
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let t_color = self.uic.theme.label_color;
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            false => None,
+        };
+        let slot_dim = [self.dim[0] / slots.len() as f64, self.dim[1]];
+        for (i, slot) in slots.iter().enumerate() {
+            let slot_pos = [self.pos[0] + slot_dim[0] * i as f64, self.pos[1]];
+            let (elem, text) = match *slot {
+                Slot::Ellipsis => {
+                    let size = self.font_size;
+                    rectangle::draw_with_centered_label(
+                        self.uic.win_w, self.uic.win_h, graphics, self.uic,
+                        rectangle::State::Normal, slot_pos, slot_dim, maybe_frame,
+                        color, "...", size, t_color
+                    );
+                    continue;
+                },
+                Slot::Button(Element::Prev) => (Element::Prev, "<".to_string()),
+                Slot::Button(Element::Next) => (Element::Next, ">".to_string()),
+                Slot::Button(Element::Page(idx)) => (Element::Page(idx), (idx + 1u).to_string()),
+            };
+            let rect_state = match new_state {
+                _ if elem == Element::Page(self.page) => rectangle::State::Clicked,
+                State::Highlighted(h_elem) if h_elem == elem => rectangle::State::Highlighted,
+                State::Clicked(c_elem) if c_elem == elem => rectangle::State::Clicked,
+                _ => rectangle::State::Normal,
+            };
+            let size = self.font_size;
+            rectangle::draw_with_centered_label(
+                self.uic.win_w, self.uic.win_h, graphics, self.uic, rect_state,
+                slot_pos, slot_dim, maybe_frame, color, text.as_slice(), size, t_color
+            );
+        }
+
+        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+
+    }
+}
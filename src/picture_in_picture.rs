@@ -0,0 +1,254 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use graphics::ImageSize;
+use mouse::Mouse;
+use opengl_graphics::{
+    Gl,
+    Texture,
+};
+use overlay::Primitive;
+use point::Point;
+use rectangle;
+use rectangle::Corner;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use utils::clamp;
+use vecmath::vec2_sub;
+use widget::Widget::PictureInPicture;
+
+/// The gap left between the thumbnail and the window edges.
+static SNAP_MARGIN: f64 = 10.0;
+/// The width and height of the resize grip.
+static GRIP_SIZE: f64 = 12.0;
+/// The smallest width the thumbnail may be resized to.
+static MIN_WIDTH: f64 = 48.0;
+
+/// The elements of the PictureInPicture widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Element {
+    Body,
+    Grip,
+}
+
+/// Represents the interaction with the widget. `Clicked` also stores the
+/// offset of the mouse from the thumbnail's position at the time of the press.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    Highlighted(Element),
+    Clicked(Element, Point),
+}
+
+/// Represents the state of the PictureInPicture widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct State {
+    interaction: Interaction,
+    /// The window corner to which the thumbnail is snapped.
+    corner: Corner,
+    /// The current width of the thumbnail, or 0.0 if not yet sized.
+    width: f64,
+}
+
+widget_fns!(PictureInPicture, State, PictureInPicture(State {
+    interaction: Interaction::Normal,
+    corner: Corner::BottomRight,
+    width: 0.0,
+}));
+
+/// Return the position of a thumbnail of the given dimensions snapped to the given corner.
+fn corner_pos(corner: Corner, dim: Dimensions, win_w: f64, win_h: f64) -> Point {
+    match corner {
+        Corner::TopLeft => [SNAP_MARGIN, SNAP_MARGIN],
+        Corner::TopRight => [win_w - dim[0] - SNAP_MARGIN, SNAP_MARGIN],
+        Corner::BottomLeft => [SNAP_MARGIN, win_h - dim[1] - SNAP_MARGIN],
+        Corner::BottomRight => [win_w - dim[0] - SNAP_MARGIN, win_h - dim[1] - SNAP_MARGIN],
+    }
+}
+
+/// Return the corner of the window closest to the center of the given rect.
+fn nearest_corner(pos: Point, dim: Dimensions, win_w: f64, win_h: f64) -> Corner {
+    let center = [pos[0] + dim[0] / 2.0, pos[1] + dim[1] / 2.0];
+    match (center[0] < win_w / 2.0, center[1] < win_h / 2.0) {
+        (true, true) => Corner::TopLeft,
+        (false, true) => Corner::TopRight,
+        (true, false) => Corner::BottomLeft,
+        (false, false) => Corner::BottomRight,
+    }
+}
+
+/// The grip sits on the thumbnail's corner that faces the middle of the window.
+fn grip_pos(corner: Corner, pos: Point, dim: Dimensions) -> Point {
+    match corner {
+        Corner::TopLeft => [pos[0] + dim[0] - GRIP_SIZE, pos[1] + dim[1] - GRIP_SIZE],
+        Corner::TopRight => [pos[0], pos[1] + dim[1] - GRIP_SIZE],
+        Corner::BottomLeft => [pos[0] + dim[0] - GRIP_SIZE, pos[1]],
+        Corner::BottomRight => pos,
+    }
+}
+
+/// Return the element under the mouse (if any).
+fn is_over(corner: Corner, pos: Point, mouse_pos: Point, dim: Dimensions) -> Option<Element> {
+    match rectangle::is_over(pos, mouse_pos, dim) {
+        false => None,
+        true => match rectangle::is_over(grip_pos(corner, pos, dim), mouse_pos, [GRIP_SIZE, GRIP_SIZE]) {
+            true => Some(Element::Grip),
+            false => Some(Element::Body),
+        },
+    }
+}
+
+/// Determine the new interaction from the previous one and the mouse.
+fn get_new_interaction(is_over_elem: Option<Element>,
+                       prev: Interaction,
+                       pos: Point,
+                       mouse: Mouse) -> Interaction {
+    use mouse::ButtonState::{Down, Up};
+    use self::Interaction::{Normal, Highlighted, Clicked};
+    match (is_over_elem, prev, mouse.left) {
+        (Some(_),    Normal,              Down) => Normal,
+        (Some(elem), _,                   Up)   => Highlighted(elem),
+        (Some(elem), Highlighted(_),      Down) => Clicked(elem, vec2_sub(mouse.pos, pos)),
+        (_,          Clicked(elem, grab), Down) => Clicked(elem, grab),
+        _                                       => Normal,
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct PictureInPictureContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    texture: &'a Texture,
+    width: f64,
+    depth: i32,
+    maybe_callback: Option<||:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+}
+
+impl<'a> PictureInPictureContext<'a> {
+    /// The initial width of the thumbnail. The height is determined by the
+    /// aspect ratio of the texture.
+    #[inline]
+    pub fn initial_width(self, width: f64) -> PictureInPictureContext<'a> {
+        PictureInPictureContext { width: width, ..self }
+    }
+}
+
+pub trait PictureInPictureBuilder<'a> {
+    /// A picture_in_picture builder method to be implemented by the UiContext.
+    fn picture_in_picture(&'a mut self, ui_id: UIID,
+                          texture: &'a Texture) -> PictureInPictureContext<'a>;
+}
+
+impl<'a> PictureInPictureBuilder<'a> for UiContext {
+    fn picture_in_picture(&'a mut self, ui_id: UIID,
+                          texture: &'a Texture) -> PictureInPictureContext<'a> {
+        PictureInPictureContext {
+            uic: self,
+            ui_id: ui_id,
+            texture: texture,
+            width: 160.0,
+            // Above all other popups by default.
+            depth: ::std::i32::MAX,
+            maybe_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+        }
+    }
+}
+
+impl_callable!(PictureInPictureContext, ||:'a);
+impl_colorable!(PictureInPictureContext);
+impl_frameable!(PictureInPictureContext);
+impl_hoverable!(PictureInPictureContext);
+impl_layerable!(PictureInPictureContext);
+
+impl<'a> ::draw::Drawable for PictureInPictureContext<'a> {
+    /// Draw the thumbnail. Double-clicking it calls the `callback`. The thumbnail is drawn
+    /// during the overlay pass (see `UiContext::draw_overlay`) above everything else, so the
+    /// texture must live until then.
+    fn draw(&mut self, _graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let (win_w, win_h) = (self.uic.win_w, self.uic.win_h);
+        let (tex_w, tex_h) = self.texture.get_size();
+        let aspect = if tex_w > 0 && tex_h > 0 { tex_w as f64 / tex_h as f64 } else { 1.0 };
+        let width = if state.width > 0.0 { state.width } else { self.width };
+        let dim = [width, width / aspect];
+        let pos = match state.interaction {
+            Interaction::Clicked(Element::Body, grab) => vec2_sub(mouse.pos, grab),
+            _ => corner_pos(state.corner, dim, win_w, win_h),
+        };
+        let is_over_elem = is_over(state.corner, pos, mouse.pos, dim);
+        let new_interaction = get_new_interaction(is_over_elem, state.interaction, pos, mouse);
+
+        // Drag, resize, snap and check for double-clicks.
+        let mut new_state = State { interaction: new_interaction, ..state };
+        let max_w = win_w / 2.0;
+        match (state.interaction, new_interaction) {
+            (Interaction::Clicked(Element::Grip, _), Interaction::Clicked(Element::Grip, _)) => {
+                // Resize about the snapped corner so that it remains in place.
+                let anchor = match state.corner {
+                    Corner::TopLeft | Corner::BottomLeft => pos[0],
+                    Corner::TopRight | Corner::BottomRight => pos[0] + dim[0],
+                };
+                new_state.width = clamp((mouse.pos[0] - anchor).abs(), MIN_WIDTH, max_w);
+            },
+            (Interaction::Highlighted(_), Interaction::Clicked(Element::Body, _))
+            if mouse.is_double_click => match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(),
+                None => (),
+            },
+            (Interaction::Clicked(Element::Body, _), Interaction::Highlighted(_))
+            | (Interaction::Clicked(Element::Body, _), Interaction::Normal) => {
+                // A click leaves the thumbnail in its corner, while a drag snaps it to the
+                // nearest one.
+                if self.uic.is_past_drag_threshold() {
+                    new_state.corner = nearest_corner(pos, dim, win_w, win_h);
+                }
+            },
+            _ => (),
+        }
+        new_state.width = clamp(if new_state.width > 0.0 { new_state.width } else { width },
+                                MIN_WIDTH, max_w);
+
+        // Draw.
+        let dim = [new_state.width, new_state.width / aspect];
+        let pos = match new_interaction {
+            Interaction::Clicked(Element::Body, grab) => vec2_sub(mouse.pos, grab),
+            _ => corner_pos(new_state.corner, dim, win_w, win_h),
+        };
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let frame_color = self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color);
+        let inner_dim = [dim[0] - frame_w * 2.0, dim[1] - frame_w * 2.0];
+        let mut primitives = vec![
+            Primitive::Rect(rectangle::State::Normal, pos, dim,
+                            Some((frame_w, frame_color)), color),
+            Primitive::Image(self.texture.get_id(), tex_w, tex_h,
+                             [pos[0] + frame_w, pos[1] + frame_w], inner_dim),
+        ];
+        match new_interaction {
+            Interaction::Highlighted(elem) | Interaction::Clicked(elem, _) => {
+                let grip_state = match new_interaction {
+                    Interaction::Clicked(Element::Grip, _) => rectangle::State::Clicked,
+                    _ if elem == Element::Grip => rectangle::State::Highlighted,
+                    _ => rectangle::State::Normal,
+                };
+                primitives.push(Primitive::Rect(grip_state, grip_pos(new_state.corner, pos, dim),
+                                                [GRIP_SIZE, GRIP_SIZE], None, frame_color));
+            },
+            Interaction::Normal => (),
+        }
+        self.uic.push_overlay_at_depth(self.ui_id, self.depth, pos, dim, primitives);
+
+        set_state(self.uic, self.ui_id, new_state, pos, dim);
+
+    }
+}
@@ -1,12 +1,139 @@
 
+use dimensions::Dimensions;
 use point::Point;
 use ui_context::UIID;
 
+/// One of the four edges of a rectangle (or of the window).
+#[deriving(PartialEq, Show, Clone, Copy)]
+pub enum Edge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// A point on the window's edges (or its center) to which a widget may be anchored.
+#[deriving(PartialEq, Show, Clone, Copy)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Return the position of a rect with the given dimensions anchored within the window,
+    /// `offset` pixels in from the anchored edges.
+    pub fn position(&self, offset: Point, dim: Dimensions, win_w: f64, win_h: f64) -> Point {
+        let left = offset[0];
+        let center_x = (win_w - dim[0]) / 2.0 + offset[0];
+        let right = win_w - dim[0] - offset[0];
+        let top = offset[1];
+        let center_y = (win_h - dim[1]) / 2.0 + offset[1];
+        let bottom = win_h - dim[1] - offset[1];
+        match *self {
+            Anchor::TopLeft     => [left, top],
+            Anchor::Top         => [center_x, top],
+            Anchor::TopRight    => [right, top],
+            Anchor::Left        => [left, center_y],
+            Anchor::Center      => [center_x, center_y],
+            Anchor::Right       => [right, center_y],
+            Anchor::BottomLeft  => [left, bottom],
+            Anchor::Bottom      => [center_x, bottom],
+            Anchor::BottomRight => [right, bottom],
+        }
+    }
+}
+
+/// A horizontal or vertical coordinate given relative to the window.
+#[deriving(PartialEq, Show, Clone, Copy)]
+pub enum Coord {
+    /// Pixels from the left (or top) of the window.
+    Pixels(f64),
+    /// A percentage of the window's width (or height).
+    Percent(f64),
+    /// Pixels between the widget's far edge and the right (or bottom) of the window.
+    FromEnd(f64),
+    /// Centered within the window.
+    Center,
+}
+
+/// A width or height given relative to the window.
+#[deriving(PartialEq, Show, Clone, Copy)]
+pub enum Length {
+    Pixels(f64),
+    /// A percentage of the window's width (or height).
+    Percent(f64),
+    /// Extend to the given number of pixels from the right (or bottom) of the window. When
+    /// the coordinate is `FromEnd` or `Center`, the same space is left at either side.
+    Fill(f64),
+    /// The other length multiplied (for a width) or divided (for a height) by the given
+    /// ratio of width to height. Only one of the two lengths may be an `Aspect`.
+    Aspect(f64),
+}
+
+/// A rect described relative to the window, which is resolved against the window's
+/// current size each frame so that the UI reflows when the window is resized.
+#[deriving(PartialEq, Show, Clone, Copy)]
+pub struct Constraints {
+    pub x: Coord,
+    pub y: Coord,
+    pub w: Length,
+    pub h: Length,
+}
+
+/// Resolve a length of a `Constraints` given the window's length along its axis.
+/// An `Aspect` length depends on the other axis, so is resolved by `Constraints::resolve`.
+fn resolve_length(length: Length, coord: Coord, win_len: f64) -> f64 {
+    match (length, coord) {
+        (Length::Pixels(len), _) => len,
+        (Length::Percent(perc), _) => win_len * perc / 100.0,
+        (Length::Fill(margin), Coord::FromEnd(_)) | (Length::Fill(margin), Coord::Center) =>
+            win_len - margin * 2.0,
+        (Length::Fill(margin), Coord::Pixels(px)) => win_len - px - margin,
+        (Length::Fill(margin), Coord::Percent(perc)) => win_len - win_len * perc / 100.0 - margin,
+        (Length::Aspect(_), _) => 0.0,
+    }
+}
+
+/// Resolve a coordinate of a `Constraints` given the resolved length along its axis.
+fn resolve_coord(coord: Coord, len: f64, win_len: f64) -> f64 {
+    match coord {
+        Coord::Pixels(px) => px,
+        Coord::Percent(perc) => win_len * perc / 100.0,
+        Coord::FromEnd(px) => win_len - len - px,
+        Coord::Center => (win_len - len) / 2.0,
+    }
+}
+
+impl Constraints {
+    /// Return the position and dimensions of the rect within a window of the given size.
+    pub fn resolve(&self, win_w: f64, win_h: f64) -> (Point, Dimensions) {
+        let w = resolve_length(self.w, self.x, win_w);
+        let h = resolve_length(self.h, self.y, win_h);
+        let (w, h) = match (self.w, self.h) {
+            (_, Length::Aspect(ratio)) => (w, w / ratio),
+            (Length::Aspect(ratio), _) => (h * ratio, h),
+            _ => (w, h),
+        };
+        ([resolve_coord(self.x, w, win_w), resolve_coord(self.y, h, win_h)], [w, h])
+    }
+}
+
 /// A trait that indicates whether or not a widget
 /// builder is positionable.
 pub trait Positionable {
+    /// Position the widget relative to the current container (see
+    /// `UiContext::begin_container`), or to the window if there is none.
     fn point(self, pos: Point) -> Self;
     fn position(self, x: f64, y: f64) -> Self;
+    /// Position the widget at the given percentages of the window's width and height.
+    fn position_percent(self, x: f64, y: f64) -> Self;
     fn down(self, padding: f64) -> Self;
     fn up(self, padding: f64) -> Self;
     fn left(self, padding: f64) -> Self;
@@ -15,5 +142,9 @@ pub trait Positionable {
     fn up_from(self, ui_id: UIID, padding: f64) -> Self;
     fn left_from(self, ui_id: UIID, padding: f64) -> Self;
     fn right_from(self, ui_id: UIID, padding: f64) -> Self;
+    /// Align the widget's left edge with that of the given widget, keeping its y position.
+    fn align_left_with(self, ui_id: UIID) -> Self;
+    /// Align the widget's top edge with that of the given widget, keeping its x position.
+    fn align_top_with(self, ui_id: UIID) -> Self;
 }
 
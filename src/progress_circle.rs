@@ -0,0 +1,112 @@
+use std::f64::consts::PI;
+use std::num::Float;
+use std::num::FloatMath;
+use color::Color;
+use dimensions::Dimensions;
+use graphics;
+use graphics::Context;
+use label;
+use opengl_graphics::Gl;
+use point::Point;
+use ui_context::UiContext;
+
+/// The number of line segments used to draw a full circle.
+static SEGMENTS: uint = 64;
+
+/// A context on which the builder pattern can be implemented.
+pub struct ProgressCircleContext<'a> {
+    uic: &'a mut UiContext,
+    value: f64,
+    thickness: f64,
+    pos: Point,
+    dim: Dimensions,
+    maybe_color: Option<Color>,
+    maybe_label: Option<&'a str>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+    maybe_label_min_font_size: Option<u32>,
+}
+
+impl<'a> ProgressCircleContext<'a> {
+    /// The thickness of the arc.
+    #[inline]
+    pub fn thickness(self, thickness: f64) -> ProgressCircleContext<'a> {
+        ProgressCircleContext { thickness: thickness, ..self }
+    }
+}
+
+pub trait ProgressCircleBuilder<'a> {
+    /// A progress_circle builder method to be implemented by the UiContext.
+    /// The value is clamped to the range 0.0 to 1.0.
+    fn progress_circle(&'a mut self, value: f64) -> ProgressCircleContext<'a>;
+}
+
+impl<'a> ProgressCircleBuilder<'a> for UiContext {
+    fn progress_circle(&'a mut self, value: f64) -> ProgressCircleContext<'a> {
+        let pos = self.flow_pos();
+        ProgressCircleContext {
+            uic: self,
+            value: value.max(0.0).min(1.0),
+            thickness: 6.0,
+            pos: pos,
+            dim: [64.0, 64.0],
+            maybe_color: None,
+            maybe_label: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+            maybe_label_min_font_size: None,
+        }
+    }
+}
+
+impl_colorable!(ProgressCircleContext);
+impl_labelable!(ProgressCircleContext);
+impl_positionable!(ProgressCircleContext);
+impl_shapeable!(ProgressCircleContext);
+
+/// Draw an arc clockwise from the top of the circle, covering `perc` of its circumference.
+fn draw_arc(context: &Context, graphics: &mut Gl,
+            center: Point, radius: f64, perc: f64, line: &graphics::Line) {
+    let num_segments = (SEGMENTS as f64 * perc).ceil() as uint;
+    let point_at = |i: uint| -> [f64, ..2] {
+        let angle = -PI / 2.0 + 2.0 * PI * perc * i as f64 / num_segments as f64;
+        [center[0] + radius * angle.cos(), center[1] + radius * angle.sin()]
+    };
+    for i in range(0u, num_segments) {
+        let (a, b) = (point_at(i), point_at(i + 1u));
+        line.draw([a[0], a[1], b[0], b[1]], context, graphics);
+    }
+}
+
+impl<'a> ::draw::Drawable for ProgressCircleContext<'a> {
+    /// Draw the progress circle. The label defaults to the value as a percentage.
+    fn draw(&mut self, graphics: &mut Gl) {
+        let context = Context::abs(self.uic.win_w, self.uic.win_h);
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let track_color = color * Color::new(0.3, 0.3, 0.3, 1.0);
+        let center = [self.pos[0] + self.dim[0] / 2.0, self.pos[1] + self.dim[1] / 2.0];
+        let radius = (self.dim[0].min(self.dim[1]) - self.thickness) / 2.0;
+
+        // Track and arc.
+        let Color(track_col) = track_color;
+        let Color(col) = color;
+        let half_thickness = self.thickness / 2.0;
+        draw_arc(&context, graphics, center, radius, 1.0,
+                 &graphics::Line::round(track_col, half_thickness));
+        if self.value > 0.0 {
+            draw_arc(&context, graphics, center, radius, self.value,
+                     &graphics::Line::round(col, half_thickness));
+        }
+
+        // Label.
+        let percentage = format!("{}%", (self.value * 100.0).round() as uint);
+        let text = self.maybe_label.unwrap_or(percentage.as_slice());
+        let size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_small);
+        let size = label::shrink_to_fit(self.uic, text, size, self.maybe_label_min_font_size,
+                                        (radius - half_thickness) * 2.0);
+        let text_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+        let text_w = label::width(self.uic, size, text);
+        let text_pos = [center[0] - text_w / 2.0, center[1] - size as f64 / 2.0];
+        self.uic.draw_text(graphics, text_pos, size, text_color, text);
+    }
+}
@@ -0,0 +1,188 @@
+use std::cmp;
+use std::f64::consts::PI;
+use std::num::Float;
+use std::num::FloatMath;
+use color::Color;
+use dimensions::Dimensions;
+use graphics;
+use graphics::Context;
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use widget::Widget::Rating;
+
+/// The radius of a star's inner points relative to its outer points.
+static INNER_RADIUS: f64 = 0.4;
+
+/// Represents the state of the Rating widget. Highlighted and Clicked
+/// store the rating that would be set by releasing the mouse.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted(u8),
+    Clicked(u8),
+}
+
+widget_fns!(Rating, State, Rating(State::Normal));
+
+/// Return the rating under the mouse (if any). When `half_stars` is true
+/// the rating is measured in half stars.
+fn is_over(pos: Point, mouse_pos: Point, dim: Dimensions, max: u8, half_stars: bool) -> Option<u8> {
+    match rectangle::is_over(pos, mouse_pos, dim) {
+        false => None,
+        true => {
+            let steps = if half_stars { max as uint * 2u } else { max as uint };
+            let step = ((mouse_pos[0] - pos[0]) / dim[0] * steps as f64) as uint;
+            Some(cmp::min(step, steps - 1u) as u8 + 1)
+        },
+    }
+}
+
+/// Check the current state of the rating.
+fn get_new_state(is_over_rating: Option<u8>,
+                 prev: State,
+                 mouse: Mouse) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::State::{Normal, Highlighted, Clicked};
+    match (is_over_rating, prev, mouse.left) {
+        (Some(_),      Normal,         Down) => Normal,
+        (Some(rating), _,              Up)   => Highlighted(rating),
+        (Some(rating), Highlighted(_), Down) => Clicked(rating),
+        (Some(rating), Clicked(_),     Down) => Clicked(rating),
+        _                                    => Normal,
+    }
+}
+
+/// Return the points of a five pointed star from its top point clockwise.
+fn star_points(center: Point, radius: f64) -> Vec<[f64, ..2]> {
+    range(0u, 10u).map(|i| {
+        let r = if i % 2u == 0u { radius } else { radius * INNER_RADIUS };
+        let angle = -PI / 2.0 + PI / 5.0 * i as f64;
+        [center[0] + r * angle.cos(), center[1] + r * angle.sin()]
+    }).collect()
+}
+
+/// Draw a star, or only its left half if `is_half` is true. The star is drawn
+/// as a fan around its center as it is not convex.
+fn draw_star(context: &Context, graphics: &mut Gl,
+             center: Point, radius: f64, color: Color, is_half: bool) {
+    let points = star_points(center, radius);
+    let mut fan = vec![center];
+    match is_half {
+        true => for i in range(5u, 11u) { fan.push(points[i % 10u]) },
+        false => for i in range(0u, 11u) { fan.push(points[i % 10u]) },
+    }
+    let Color(col) = color;
+    graphics::Polygon::new(col).draw(fan.as_slice(), context, graphics);
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct RatingContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    value: &'a mut u8,
+    max: u8,
+    half_stars: bool,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<|u8|:'a>,
+    maybe_color: Option<Color>,
+}
+
+impl<'a> RatingContext<'a> {
+    /// Allow ratings to be set in half stars, in which case the value
+    /// is measured in half stars (i.e. 7 is three and a half stars).
+    #[inline]
+    pub fn half_stars(self) -> RatingContext<'a> {
+        RatingContext { half_stars: true, ..self }
+    }
+}
+
+pub trait RatingBuilder<'a> {
+    /// A rating builder method to be implemented by the UiContext. Panics if `max` is zero,
+    /// as a rating needs at least one star.
+    fn rating(&'a mut self, ui_id: UIID, value: &'a mut u8, max: u8) -> RatingContext<'a>;
+}
+
+impl<'a> RatingBuilder<'a> for UiContext {
+    fn rating(&'a mut self, ui_id: UIID, value: &'a mut u8, max: u8) -> RatingContext<'a> {
+        assert!(max > 0, "A Rating must have a `max` of at least one star.");
+        let pos = self.flow_pos();
+        RatingContext {
+            uic: self,
+            ui_id: ui_id,
+            value: value,
+            max: max,
+            half_stars: false,
+            pos: pos,
+            dim: [160.0, 32.0],
+            maybe_callback: None,
+            maybe_color: None,
+        }
+    }
+}
+
+impl_callable!(RatingContext, |u8|:'a);
+impl_colorable!(RatingContext);
+impl_hoverable!(RatingContext);
+impl_positionable!(RatingContext);
+impl_shapeable!(RatingContext);
+
+impl<'a> ::draw::Drawable for RatingContext<'a> {
+    /// Draw the stars. Hovering previews a rating, while releasing the mouse
+    /// sets the value and calls the `callback` with the new rating.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let max = self.max;
+        let is_over_rating = is_over(self.pos, mouse.pos, self.dim, max, self.half_stars);
+        let new_state = get_new_state(is_over_rating, state, mouse);
+
+        // Set the rating on release.
+        match (state, new_state) {
+            (State::Clicked(rating), State::Highlighted(h_rating)) if rating == h_rating => {
+                *self.value = rating;
+                match self.maybe_callback {
+                    Some(ref mut callback) => (*callback)(rating),
+                    None => (),
+                }
+            },
+            _ => (),
+        }
+
+        // Draw the previewed rating if there is one, otherwise the value.
+        let shown = match new_state {
+            State::Highlighted(rating) | State::Clicked(rating) => rating,
+            State::Normal => *self.value,
+        };
+        let halves = if self.half_stars { shown as uint } else { shown as uint * 2u };
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let color = match new_state {
+            State::Normal => color,
+            State::Highlighted(_) => color.highlighted(),
+            State::Clicked(_) => color.clicked(),
+        };
+        let empty_color = color * Color::new(0.3, 0.3, 0.3, 1.0);
+        let context = Context::abs(self.uic.win_w, self.uic.win_h);
+        let star_w = self.dim[0] / max as f64;
+        let radius = star_w.min(self.dim[1]) / 2.0;
+        for i in range(0u, max as uint) {
+            let center = [self.pos[0] + star_w * (i as f64 + 0.5), self.pos[1] + self.dim[1] / 2.0];
+            draw_star(&context, graphics, center, radius, empty_color, false);
+            if halves >= (i + 1u) * 2u {
+                draw_star(&context, graphics, center, radius, color, false);
+            } else if halves == i * 2u + 1u {
+                draw_star(&context, graphics, center, radius, color, true);
+            }
+        }
+
+        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+
+    }
+}
@@ -1,4 +1,5 @@
-
+use std::f64::consts::PI;
+use std::num::Float;
 use color::Color;
 use dimensions::Dimensions;
 use graphics;
@@ -12,6 +13,13 @@ use point::Point;
 use ui_context::UiContext;
 use utils::map_range;
 
+/// The number of segments with which each rounded corner is drawn.
+static CORNER_SEGMENTS: uint = 8;
+/// The number of bands of color with which a gradient is drawn.
+static GRADIENT_BANDS: uint = 32;
+/// The default time (in seconds) over which a widget's color fades between states.
+pub static DEFAULT_TRANSITION_SECS: f64 = 0.1;
+
 /// Represents the state of the Button widget.
 #[deriving(PartialEq, Show, Copy)]
 pub enum State {
@@ -20,6 +28,83 @@ pub enum State {
     Clicked,
 }
 
+impl State {
+    /// Return the color in which a rectangle of the given color is drawn in this state.
+    pub fn color(&self, color: Color) -> Color {
+        match *self {
+            State::Normal => color,
+            State::Highlighted => color.highlighted(),
+            State::Clicked => color.clicked(),
+        }
+    }
+    fn idx(&self) -> uint {
+        match *self { State::Normal => 0u, State::Highlighted => 1u, State::Clicked => 2u }
+    }
+}
+
+/// How much of each State a widget's color is made of while it fades from one state to
+/// another, as given by `UiContext::transition`.
+#[deriving(Show, Clone, Copy)]
+pub struct Transition {
+    weights: [f64, ..3],
+}
+
+impl Transition {
+    /// Constructor for a Transition that is entirely in the given state.
+    pub fn new(state: State) -> Transition {
+        let mut weights = [0.0, ..3];
+        weights[state.idx()] = 1.0;
+        Transition { weights: weights }
+    }
+    /// Return the transition moved towards the given state by the given amount, where 1.0
+    /// completes the transition.
+    pub fn step(&self, state: State, amount: f64) -> Transition {
+        let mut weights = self.weights;
+        for (i, weight) in weights.iter_mut().enumerate() {
+            *weight = match i == state.idx() {
+                true => (*weight + amount).min(1.0),
+                false => (*weight - amount).max(0.0),
+            };
+        }
+        Transition { weights: weights }
+    }
+    /// Return the given color blended between its color in each state.
+    pub fn color(&self, color: Color) -> Color {
+        let states = [State::Normal, State::Highlighted, State::Clicked];
+        let total = self.weights.iter().fold(0.0, |total, &weight| total + weight);
+        let (mut r, mut g, mut b, mut a) = (0.0, 0.0, 0.0, 0.0);
+        for (state, &weight) in states.iter().zip(self.weights.iter()) {
+            let c = state.color(color);
+            let weight = (weight / total) as f32;
+            r += c.r() * weight;
+            g += c.g() * weight;
+            b += c.b() * weight;
+            a += c.a() * weight;
+        }
+        Color::new(r, g, b, a)
+    }
+}
+
+/// A gradient from the first color to the second, with which a rectangle may be filled
+/// in place of a single color.
+#[deriving(Show, Clone, Copy, Encodable, Decodable)]
+pub enum Gradient {
+    /// From the top edge to the bottom edge.
+    Vertical(Color, Color),
+    /// From the left edge to the right edge.
+    Horizontal(Color, Color),
+}
+
+impl Gradient {
+    /// Return the gradient with the given function applied to both of its colors.
+    pub fn map(&self, f: |Color| -> Color) -> Gradient {
+        match *self {
+            Gradient::Vertical(a, b) => Gradient::Vertical(f(a), f(b)),
+            Gradient::Horizontal(a, b) => Gradient::Horizontal(f(a), f(b)),
+        }
+    }
+}
+
 /// Draw a basic rectangle. The primary purpose
 /// of this is to be used as a building block for
 /// other widgets.
@@ -32,13 +117,97 @@ pub fn draw(
     dim: Dimensions,
     maybe_frame: Option<(f64, Color)>,
     color: Color
+) {
+    draw_rounded(win_w, win_h, graphics, state, pos, dim, maybe_frame, color, None, 0.0);
+}
+
+/// Draw a rectangle with its corners rounded to the given radius, which is limited to
+/// half of the shorter side. A radius of zero draws a basic rectangle. The gradient (if
+/// any) is drawn in place of the color.
+pub fn draw_rounded(
+    win_w: f64,
+    win_h: f64,
+    graphics: &mut Gl,
+    state: State,
+    pos: Point,
+    dim: Dimensions,
+    maybe_frame: Option<(f64, Color)>,
+    color: Color,
+    maybe_gradient: Option<Gradient>,
+    radius: f64
 ) {
     let context = &Context::abs(win_w, win_h);
     if let Some((_, f_color)) = maybe_frame {
-        draw_frame(context, graphics, pos, dim, f_color)
+        draw_frame(context, graphics, pos, dim, radius, f_color)
     }
     let f_width = if let Some((f_width, _)) = maybe_frame { f_width } else { 0.0 };
-    draw_normal(context, graphics, state, pos, dim, f_width, color);
+    draw_normal(context, graphics, state, pos, dim, f_width, radius, color, maybe_gradient);
+}
+
+/// Fill the given area, with its corners rounded if the radius is greater than zero.
+fn fill(
+    context: &Context,
+    graphics: &mut Gl,
+    pos: Point,
+    dim: Dimensions,
+    radius: f64,
+    color: Color
+) {
+    let Color(col) = color;
+    let radius = radius.min(dim[0] / 2.0).min(dim[1] / 2.0);
+    if radius <= 0.0 {
+        graphics::Rectangle::new(col)
+            .draw([pos[0], pos[1], dim[0], dim[1]], context, graphics);
+        return
+    }
+    // Trace each corner's arc clockwise, starting from the top right.
+    let centers = [[pos[0] + dim[0] - radius, pos[1] + radius],
+                   [pos[0] + dim[0] - radius, pos[1] + dim[1] - radius],
+                   [pos[0] + radius, pos[1] + dim[1] - radius],
+                   [pos[0] + radius, pos[1] + radius]];
+    let mut points = Vec::with_capacity(4 * (CORNER_SEGMENTS + 1));
+    for (i, center) in centers.iter().enumerate() {
+        for j in range(0u, CORNER_SEGMENTS + 1) {
+            let angle = PI * (i as f64 - 1.0 + j as f64 / CORNER_SEGMENTS as f64) / 2.0;
+            points.push([center[0] + radius * angle.cos(), center[1] + radius * angle.sin()]);
+        }
+    }
+    graphics::Polygon::new(col).draw(points.as_slice(), context, graphics);
+}
+
+/// Fill the given area with a gradient, with its corners rounded if the radius is greater
+/// than zero. The gradient is drawn as bands of color, which are narrowed to fit within
+/// the rounded corners.
+fn fill_gradient(
+    context: &Context,
+    graphics: &mut Gl,
+    pos: Point,
+    dim: Dimensions,
+    radius: f64,
+    gradient: Gradient
+) {
+    let radius = radius.max(0.0).min(dim[0] / 2.0).min(dim[1] / 2.0);
+    let (start, end, is_vertical) = match gradient {
+        Gradient::Vertical(start, end) => (start, end, true),
+        Gradient::Horizontal(start, end) => (start, end, false),
+    };
+    let len = if is_vertical { dim[1] } else { dim[0] };
+    let band = len / GRADIENT_BANDS as f64;
+    for i in range(0u, GRADIENT_BANDS) {
+        let perc = (i as f64 + 0.5) / GRADIENT_BANDS as f64;
+        let edge_dist = (perc * len).min(len - perc * len);
+        let inset = match edge_dist < radius {
+            true => radius - (radius * radius - (radius - edge_dist).powi(2)).sqrt(),
+            false => 0.0,
+        };
+        let offset = band * i as f64;
+        let rect = match is_vertical {
+            true => [pos[0] + inset, pos[1] + offset, dim[0] - inset * 2.0, band],
+            false => [pos[0] + offset, pos[1] + inset, band, dim[1] - inset * 2.0],
+        };
+        let Color(col) = start.mix(end, perc as f32);
+        graphics::Rectangle::new(col).draw(rect, context, graphics);
+    }
 }
 
 /// Draw the button border.
@@ -47,11 +216,10 @@ fn draw_frame(
     graphics: &mut Gl,
     pos: Point,
     dim: Dimensions,
+    radius: f64,
     color: Color
 ) {
-    let Color(col) = color;
-    graphics::Rectangle::new(col)
-        .draw([pos[0], pos[1], dim[0], dim[1]], context, graphics);
+    fill(context, graphics, pos, dim, radius, color);
 }
 
 /// Draw the rectangle while considering frame
@@ -63,20 +231,23 @@ fn draw_normal(
     pos: Point,
     dim: Dimensions,
     frame_width: f64,
-    color: Color
+    radius: f64,
+    color: Color,
+    maybe_gradient: Option<Gradient>
 ) {
-    let Color(col) = match state {
-        State::Normal => color,
-        State::Highlighted => color.highlighted(),
-        State::Clicked => color.clicked(),
-    };
-    graphics::Rectangle::new(col)
-        .draw([pos[0] + frame_width,
-            pos[1] + frame_width,
-            dim[0] - frame_width * 2.0,
-            dim[1] - frame_width * 2.0],
-        context,
-        graphics);
+    let inner_pos = [pos[0] + frame_width, pos[1] + frame_width];
+    let inner_dim = [dim[0] - frame_width * 2.0, dim[1] - frame_width * 2.0];
+    let inner_radius = (radius - frame_width).max(0.0);
+    match maybe_gradient {
+        Some(gradient) => {
+            let gradient = gradient.map(|color| state.color(color));
+            fill_gradient(context, graphics, inner_pos, inner_dim, inner_radius, gradient)
+        },
+        None => {
+            let color = state.color(color);
+            fill(context, graphics, inner_pos, inner_dim, inner_radius, color)
+        },
+    }
 }
 
 /// Return whether or not the widget has been hit by a mouse_press.
@@ -106,18 +277,13 @@ pub fn draw_with_centered_label(
     font_size: FontSize,
     text_color: Color
 ) {
-    let context = &Context::abs(win_w, win_h);
-    if let Some((_, f_color)) = maybe_frame {
-        draw_frame(context, graphics, pos, dim, f_color)
-    }
-    let f_width = if let Some((f_width, _)) = maybe_frame { f_width } else { 0.0 };
-    draw_normal(context, graphics, state, pos, dim, f_width, color);
+    draw_rounded(win_w, win_h, graphics, state, pos, dim, maybe_frame, color, None, 0.0);
     let text_w = label::width(uic, font_size, text);
     let l_pos = [pos[0] + (dim[0] - text_w) / 2.0, pos[1] + (dim[1] - font_size as f64) / 2.0];
     uic.draw_text(graphics, l_pos, font_size, text_color, text);
 }
 
-#[deriving(Copy)]
+#[deriving(PartialEq, Show, Clone, Copy)]
 pub enum Corner {
     TopLeft,
     TopRight,
@@ -0,0 +1,283 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use drag::NOT_ALLOWED_RADIUS;
+use graphics;
+use graphics::Context;
+use input::keyboard::Key;
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use overlay::Primitive;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use utils::clamp;
+use vecmath::{
+    vec2_add,
+    vec2_sub,
+};
+use widget::Widget::ReorderList;
+
+/// Tuple / Callback params.
+pub type Idx = uint;
+pub type Len = uint;
+
+/// Represents the interaction with the ReorderList widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    Highlighted(Idx),
+    /// An item has been picked up. Stores the item's index and the offset of
+    /// the mouse from the top left of the item when it was grabbed.
+    Dragging(Idx, Point),
+}
+
+/// Represents the state of the ReorderList widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct State {
+    interaction: Interaction,
+    /// The item last clicked, which may be moved using Ctrl+Up / Ctrl+Down.
+    selected: Option<Idx>,
+}
+
+widget_fns!(ReorderList, State, ReorderList(State {
+    interaction: Interaction::Normal,
+    selected: None,
+}));
+
+/// Return the index of the item under the mouse (if any).
+fn is_over(pos: Point, mouse_pos: Point, dim: Dimensions, len: Len) -> Option<Idx> {
+    let total_h = dim[1] * len as f64;
+    match rectangle::is_over(pos, mouse_pos, [dim[0], total_h]) {
+        false => None,
+        true => Some((((mouse_pos[1] - pos[1]) / total_h) * len as f64) as uint),
+    }
+}
+
+/// Determine the new interaction from the previous interaction and the mouse.
+fn get_new_interaction(is_over_idx: Option<Idx>,
+                       prev: Interaction,
+                       pos: Point,
+                       dim: Dimensions,
+                       mouse: Mouse) -> Interaction {
+    use mouse::ButtonState::{Down, Up};
+    use self::Interaction::{Normal, Highlighted, Dragging};
+    match (is_over_idx, prev, mouse.left) {
+        (_,        Dragging(idx, grab_y), Down) => Dragging(idx, grab_y),
+        (Some(idx), Highlighted(_),      Down) => {
+            Dragging(idx, vec2_sub(mouse.pos, [pos[0], pos[1] + dim[1] * idx as f64]))
+        },
+        (Some(idx), _,                   Up)   => Highlighted(idx),
+        _                                      => Normal,
+    }
+}
+
+/// Return the slot (between 0 and `len`) at which the dragged item would be inserted.
+pub fn insertion_slot(pos: Point, item_h: f64, mouse_y: f64, len: Len) -> uint {
+    let slot = ((mouse_y - pos[1]) / item_h + 0.5).floor();
+    clamp(slot, 0.0, len as f64) as uint
+}
+
+/// Convert an insertion slot to the index the item will have once moved.
+fn slot_to_idx(from: Idx, slot: uint) -> Idx {
+    if slot > from { slot - 1u } else { slot }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct ReorderListContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    strings: &'a mut Vec<String>,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<|&mut Vec<String>, Idx, Idx|:'a>,
+    maybe_drag_ghost: Option<|Point, Dimensions, Idx|:'a -> Vec<Primitive>>,
+    depth: i32,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+}
+
+impl<'a> ReorderListContext<'a> {
+    /// Draw a custom ghost for the dragged item in place of the default copy of
+    /// its row. The closure is given the position and dimensions of the ghost
+    /// (which follows the cursor) along with the index of the dragged item, and returns
+    /// the shapes of the ghost, which are drawn above everything else during the overlay
+    /// pass (see `UiContext::draw_overlay`).
+    #[inline]
+    pub fn drag_ghost(self, ghost: |Point, Dimensions, Idx|:'a -> Vec<Primitive>)
+                      -> ReorderListContext<'a> {
+        ReorderListContext { maybe_drag_ghost: Some(ghost), ..self }
+    }
+    #[inline]
+    pub fn label_color(self, color: Color) -> ReorderListContext<'a> {
+        ReorderListContext { maybe_label_color: Some(color), ..self }
+    }
+    #[inline]
+    pub fn label_font_size(self, size: u32) -> ReorderListContext<'a> {
+        ReorderListContext { maybe_label_font_size: Some(size), ..self }
+    }
+}
+
+pub trait ReorderListBuilder<'a> {
+    /// A reorder_list builder method to be implemented by the UiContext.
+    fn reorder_list(&'a mut self, ui_id: UIID,
+                    strings: &'a mut Vec<String>) -> ReorderListContext<'a>;
+}
+
+impl<'a> ReorderListBuilder<'a> for UiContext {
+    fn reorder_list(&'a mut self, ui_id: UIID,
+                    strings: &'a mut Vec<String>) -> ReorderListContext<'a> {
+        let pos = self.flow_pos();
+        ReorderListContext {
+            uic: self,
+            ui_id: ui_id,
+            strings: strings,
+            pos: pos,
+            dim: [128.0, 32.0],
+            maybe_callback: None,
+            maybe_drag_ghost: None,
+            // The ghost is drawn above all popups by default.
+            depth: ::std::i32::MAX,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+        }
+    }
+}
+
+impl_callable!(ReorderListContext, |&mut Vec<String>, Idx, Idx|:'a);
+impl_colorable!(ReorderListContext);
+impl_frameable!(ReorderListContext);
+impl_hoverable!(ReorderListContext);
+impl_layerable!(ReorderListContext);
+impl_positionable!(ReorderListContext);
+impl_shapeable!(ReorderListContext);
+
+impl<'a> ::draw::Drawable for ReorderListContext<'a> {
+    /// Draw the list. Dropping a dragged item (or moving the selected item with
+    /// Ctrl+Up / Ctrl+Down) calls the `callback` with the strings along with the
+    /// index the item was moved `from` and `to`.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let len = self.strings.len();
+        let is_over_idx = is_over(self.pos, mouse.pos, self.dim, len);
+        let is_valid_drop = is_over_idx.is_some();
+        let new_interaction = get_new_interaction(is_over_idx, state.interaction,
+                                                  self.pos, self.dim, mouse);
+        let mut selected = match state.selected {
+            Some(idx) if idx < len => Some(idx),
+            _ => None,
+        };
+
+        // Determine whether an item was moved by dropping it, or selected by clicking it.
+        let mut maybe_move = None;
+        match (state.interaction, new_interaction) {
+            (Interaction::Dragging(from, _), Interaction::Highlighted(_))
+            | (Interaction::Dragging(from, _), Interaction::Normal) if from < len && is_valid_drop => {
+                let to = slot_to_idx(from, insertion_slot(self.pos, self.dim[1], mouse.pos[1], len));
+                if to != from { maybe_move = Some((from, to)) }
+                selected = Some(to);
+            },
+            (_, Interaction::Normal) if is_over_idx.is_none() => match mouse.left {
+                // Clicking elsewhere deselects the list.
+                ::mouse::ButtonState::Down => selected = None,
+                ::mouse::ButtonState::Up => (),
+            },
+            _ => (),
+        }
+
+        // Move the selected item with Ctrl+Up / Ctrl+Down.
+        if maybe_move.is_none() && self.uic.get_modifiers().ctrl {
+            if let Some(idx) = selected {
+                for key in self.uic.get_pressed_keys().iter() {
+                    match *key {
+                        Key::Up if idx > 0u => maybe_move = Some((idx, idx - 1u)),
+                        Key::Down if idx + 1u < len => maybe_move = Some((idx, idx + 1u)),
+                        _ => (),
+                    }
+                }
+            }
+        }
+
+        // Callback when an item is moved to a new position.
+        if let Some((from, to)) = maybe_move {
+            selected = Some(to);
+            match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(self.strings, from, to),
+                None => (),
+            }
+        }
+
+        // Draw the items.
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let t_size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_medium);
+        let t_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let frame_color = self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, frame_color)),
+            false => None,
+        };
+        for (i, string) in self.strings.iter().enumerate() {
+            let rect_state = match new_interaction {
+                Interaction::Dragging(idx, _) if idx == i => rectangle::State::Clicked,
+                _ if selected == Some(i) => rectangle::State::Clicked,
+                Interaction::Highlighted(idx) if idx == i => rectangle::State::Highlighted,
+                _ => rectangle::State::Normal,
+            };
+            let item_pos = [self.pos[0], self.pos[1] + self.dim[1] * i as f64];
+            rectangle::draw_with_centered_label(
+                self.uic.win_w, self.uic.win_h, graphics, self.uic, rect_state,
+                item_pos, self.dim, maybe_frame, color, string.as_slice(), t_size, t_color
+            );
+        }
+
+        // Draw the insertion indicator, and the dragged item following the cursor over
+        // everything else. Items dropped outside of the list are not moved, which is
+        // indicated beside the cursor.
+        match new_interaction {
+            Interaction::Dragging(idx, grab) if idx < len => {
+                let context = Context::abs(self.uic.win_w, self.uic.win_h);
+                if is_valid_drop {
+                    let slot = insertion_slot(self.pos, self.dim[1], mouse.pos[1], len);
+                    let y = self.pos[1] + self.dim[1] * slot as f64;
+                    let Color(col) = color.plain_contrast();
+                    graphics::Line::round(col, 1.5)
+                        .draw([self.pos[0], y, self.pos[0] + self.dim[0], y], &context, graphics);
+                }
+                let ghost_pos = vec2_sub(mouse.pos, grab);
+                let mut primitives = match self.maybe_drag_ghost {
+                    Some(ref mut ghost) => (*ghost)(ghost_pos, self.dim, idx),
+                    None => {
+                        let ghost_color = Color::new(1.0, 1.0, 1.0, 0.6) * color.highlighted();
+                        vec![Primitive::LabelledRect(rectangle::State::Normal, ghost_pos,
+                                                     self.dim, maybe_frame, ghost_color,
+                                                     self.strings[idx].clone(), t_size, t_color)]
+                    },
+                };
+                if !is_valid_drop {
+                    let indicator_pos = vec2_add(mouse.pos, [NOT_ALLOWED_RADIUS * 2.0, 0.0]);
+                    primitives.push(Primitive::NotAllowed(indicator_pos));
+                }
+                self.uic.push_overlay_shapes(self.depth, primitives);
+            },
+            _ => (),
+        }
+
+        let new_state = State { interaction: new_interaction, selected: selected };
+        // Place every row, as the given dimensions are those of a single row.
+        let list_dim = [self.dim[0], self.dim[1] * len as f64];
+        set_state(self.uic, self.ui_id, new_state, self.pos, list_dim);
+
+    }
+}
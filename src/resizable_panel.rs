@@ -0,0 +1,292 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use mouse::{Cursor, Mouse};
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use rectangle::Gradient;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use vecmath::{vec2_add, vec2_sub};
+use widget::Widget::ResizablePanel;
+
+/// The thickness of the grips along each edge.
+static GRIP_THICKNESS: f64 = 6.0;
+/// The side length of the grip in the bottom right corner.
+static CORNER_GRIP_SIZE: f64 = 12.0;
+
+/// Represents the grips by which the panel may be resized.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Grip {
+    Left,
+    Top,
+    Right,
+    Bottom,
+    Corner,
+}
+
+/// Represents the interaction with the panel's grips.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    Highlighted(Grip),
+    /// The grip is being dragged. Stores the mouse offset from the panel's top left corner
+    /// for the left and top grips, or from its bottom right corner for the others.
+    Dragging(Grip, Point),
+}
+
+/// Represents the state of the ResizablePanel widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct State {
+    interaction: Interaction,
+    /// The offset of the panel from its given position, moved by the left and top grips.
+    offset: Point,
+    /// The dimensions the panel has been resized to, or None if it has not yet been resized.
+    maybe_dim: Option<Dimensions>,
+}
+
+widget_fns!(ResizablePanel, State, ResizablePanel(State {
+    interaction: Interaction::Normal,
+    offset: [0.0, 0.0],
+    maybe_dim: None,
+}));
+
+/// Return the grip under the mouse (if any).
+fn is_over(pos: Point, mouse_pos: Point, dim: Dimensions) -> Option<Grip> {
+    let corner = [pos[0] + dim[0], pos[1] + dim[1]];
+    if rectangle::is_over(vec2_sub(corner, [CORNER_GRIP_SIZE, CORNER_GRIP_SIZE]), mouse_pos,
+                          [CORNER_GRIP_SIZE, CORNER_GRIP_SIZE]) {
+        Some(Grip::Corner)
+    }
+    else if rectangle::is_over([corner[0] - GRIP_THICKNESS, pos[1]], mouse_pos, [GRIP_THICKNESS, dim[1]]) {
+        Some(Grip::Right)
+    }
+    else if rectangle::is_over([pos[0], corner[1] - GRIP_THICKNESS], mouse_pos, [dim[0], GRIP_THICKNESS]) {
+        Some(Grip::Bottom)
+    }
+    else if rectangle::is_over(pos, mouse_pos, [GRIP_THICKNESS, dim[1]]) {
+        Some(Grip::Left)
+    }
+    else if rectangle::is_over(pos, mouse_pos, [dim[0], GRIP_THICKNESS]) {
+        Some(Grip::Top)
+    }
+    else { None }
+}
+
+/// Check the current interaction with the grips.
+fn get_new_interaction(is_over_grip: Option<Grip>,
+                       prev: Interaction,
+                       pos: Point,
+                       corner: Point,
+                       mouse: Mouse) -> Interaction {
+    use mouse::ButtonState::{Down, Up};
+    use self::Interaction::{Normal, Highlighted, Dragging};
+    match (is_over_grip, prev, mouse.left) {
+        (_,          Dragging(grip, grab), Down) => Dragging(grip, grab),
+        (Some(grip), Highlighted(_),       Down) => {
+            let anchor = match grip {
+                Grip::Left | Grip::Top => pos,
+                Grip::Right | Grip::Bottom | Grip::Corner => corner,
+            };
+            Dragging(grip, vec2_sub(mouse.pos, anchor))
+        },
+        (Some(grip), _,                    Up)   => Highlighted(grip),
+        _                                        => Normal,
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct ResizablePanelContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    min_dim: Dimensions,
+    pos: Point,
+    dim: Dimensions,
+    maybe_content: Option<|&mut UiContext, &mut Gl, Point, Dimensions|:'a>,
+    maybe_callback: Option<|Dimensions|:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_corner_radius: Option<f64>,
+    maybe_gradient: Option<Gradient>,
+}
+
+impl<'a> ResizablePanelContext<'a> {
+    /// The smallest dimensions to which the panel may be resized.
+    #[inline]
+    pub fn min_dim(self, dim: Dimensions) -> ResizablePanelContext<'a> {
+        ResizablePanelContext { min_dim: dim, ..self }
+    }
+    /// Round the panel's corners to the given radius.
+    #[inline]
+    pub fn corner_radius(self, radius: f64) -> ResizablePanelContext<'a> {
+        ResizablePanelContext { maybe_corner_radius: Some(radius), ..self }
+    }
+    /// Fill the panel with a gradient from the top color to the bottom color in place of
+    /// its color.
+    #[inline]
+    pub fn gradient(self, top: Color, bottom: Color) -> ResizablePanelContext<'a> {
+        ResizablePanelContext { maybe_gradient: Some(Gradient::Vertical(top, bottom)), ..self }
+    }
+    /// Draw the panel's contents. The closure is given the position and
+    /// dimensions of the area within the panel's frame.
+    #[inline]
+    pub fn content(self, content: |&mut UiContext, &mut Gl, Point, Dimensions|:'a) -> ResizablePanelContext<'a> {
+        ResizablePanelContext { maybe_content: Some(content), ..self }
+    }
+}
+
+pub trait ResizablePanelBuilder<'a> {
+    /// A resizable_panel builder method to be implemented by the UiContext.
+    fn resizable_panel(&'a mut self, ui_id: UIID) -> ResizablePanelContext<'a>;
+}
+
+impl<'a> ResizablePanelBuilder<'a> for UiContext {
+    fn resizable_panel(&'a mut self, ui_id: UIID) -> ResizablePanelContext<'a> {
+        let pos = self.flow_pos();
+        ResizablePanelContext {
+            uic: self,
+            ui_id: ui_id,
+            min_dim: [32.0, 32.0],
+            pos: pos,
+            dim: [200.0, 150.0],
+            maybe_content: None,
+            maybe_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_corner_radius: None,
+            maybe_gradient: None,
+        }
+    }
+}
+
+impl_callable!(ResizablePanelContext, |Dimensions|:'a);
+impl_colorable!(ResizablePanelContext);
+impl_frameable!(ResizablePanelContext);
+impl_hoverable!(ResizablePanelContext);
+impl_positionable!(ResizablePanelContext);
+impl_shapeable!(ResizablePanelContext);
+
+impl<'a> ::draw::Drawable for ResizablePanelContext<'a> {
+    /// Draw the panel. The given dimensions are only used until the panel is first resized,
+    /// after which the resized dimensions are kept. The `callback` is called with the new
+    /// dimensions while a grip is dragged. Dragging the left or top grip moves the panel's
+    /// top left corner, which is then kept as an offset from its given position. The panel's
+    /// rect may be retrieved with `UiContext::get_placing` for laying out widgets beside it.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let prev_pos = vec2_add(self.pos, state.offset);
+        let prev_dim = state.maybe_dim.unwrap_or(self.dim);
+        let corner = [prev_pos[0] + prev_dim[0], prev_pos[1] + prev_dim[1]];
+        let is_over_grip = is_over(prev_pos, mouse.pos, prev_dim);
+        let new_interaction = get_new_interaction(is_over_grip, state.interaction,
+                                                  prev_pos, corner, mouse);
+        match new_interaction {
+            Interaction::Highlighted(grip) | Interaction::Dragging(grip, _) => {
+                self.uic.set_cursor(match grip {
+                    Grip::Left | Grip::Right => Cursor::ResizeHorizontal,
+                    Grip::Top | Grip::Bottom => Cursor::ResizeVertical,
+                    Grip::Corner => Cursor::ResizeDiagonal,
+                })
+            },
+            Interaction::Normal => (),
+        }
+
+        // Resize while a grip is dragged, keeping the opposite edges in place.
+        let (offset, maybe_dim) = match new_interaction {
+            Interaction::Dragging(grip, grab) => {
+                let (min_w, min_h) = (self.min_dim[0], self.min_dim[1]);
+                let edge = vec2_sub(mouse.pos, grab);
+                let from_left = (edge[0] - prev_pos[0]).max(min_w);
+                let from_top = (edge[1] - prev_pos[1]).max(min_h);
+                let from_right = (corner[0] - edge[0]).max(min_w);
+                let from_bottom = (corner[1] - edge[1]).max(min_h);
+                let (new_pos, dim) = match grip {
+                    Grip::Left => ([corner[0] - from_right, prev_pos[1]],
+                                   [from_right, prev_dim[1]]),
+                    Grip::Top => ([prev_pos[0], corner[1] - from_bottom],
+                                  [prev_dim[0], from_bottom]),
+                    Grip::Right => (prev_pos, [from_left, prev_dim[1]]),
+                    Grip::Bottom => (prev_pos, [prev_dim[0], from_top]),
+                    Grip::Corner => (prev_pos, [from_left, from_top]),
+                };
+                if dim != prev_dim {
+                    match self.maybe_callback {
+                        Some(ref mut callback) => (*callback)(dim),
+                        None => (),
+                    }
+                }
+                (vec2_sub(new_pos, self.pos), Some(dim))
+            },
+            _ => (state.offset, state.maybe_dim),
+        };
+        let pos = vec2_add(self.pos, offset);
+        let dim = maybe_dim.unwrap_or(self.dim);
+
+        // Draw.
+        let (win_w, win_h) = (self.uic.win_w, self.uic.win_h);
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            false => None,
+        };
+        let radius = self.maybe_corner_radius.unwrap_or(self.uic.theme.corner_radius());
+        let maybe_gradient = self.maybe_gradient.or(self.uic.theme.maybe_gradient);
+        rectangle::draw_rounded(win_w, win_h, graphics, rectangle::State::Normal, pos, dim,
+                                maybe_frame, color, maybe_gradient, radius);
+
+        // Contents.
+        {
+            let ui_id = self.ui_id;
+            let ResizablePanelContext { // borrowck
+                ref mut uic,
+                ref mut maybe_content,
+                ..
+            } = *self;
+            match *maybe_content {
+                Some(ref mut content) => {
+                    let content_pos = [pos[0] + frame_w, pos[1] + frame_w];
+                    let content_dim = [dim[0] - frame_w * 2.0, dim[1] - frame_w * 2.0];
+                    uic.begin_children(ui_id);
+                    (*content)(&mut **uic, graphics, content_pos, content_dim);
+                    uic.end_children();
+                },
+                None => (),
+            }
+        }
+
+        // Draw the highlighted or dragged grip over the contents.
+        let maybe_grip = match new_interaction {
+            Interaction::Highlighted(grip) => Some((grip, rectangle::State::Highlighted)),
+            Interaction::Dragging(grip, _) => Some((grip, rectangle::State::Clicked)),
+            Interaction::Normal => None,
+        };
+        if let Some((grip, grip_state)) = maybe_grip {
+            let corner = [pos[0] + dim[0], pos[1] + dim[1]];
+            let (grip_pos, grip_dim) = match grip {
+                Grip::Left => (pos, [GRIP_THICKNESS, dim[1]]),
+                Grip::Top => (pos, [dim[0], GRIP_THICKNESS]),
+                Grip::Right => ([corner[0] - GRIP_THICKNESS, pos[1]], [GRIP_THICKNESS, dim[1]]),
+                Grip::Bottom => ([pos[0], corner[1] - GRIP_THICKNESS], [dim[0], GRIP_THICKNESS]),
+                Grip::Corner => (vec2_sub(corner, [CORNER_GRIP_SIZE, CORNER_GRIP_SIZE]),
+                                 [CORNER_GRIP_SIZE, CORNER_GRIP_SIZE]),
+            };
+            rectangle::draw(win_w, win_h, graphics, grip_state, grip_pos, grip_dim, None, color.clicked());
+        }
+
+        let new_state = State {
+            interaction: new_interaction,
+            offset: offset,
+            maybe_dim: maybe_dim,
+        };
+        set_state(self.uic, self.ui_id, new_state, pos, dim);
+
+    }
+}
@@ -0,0 +1,297 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use utils::clamp;
+use widget::Widget::ScrollArea;
+
+/// The thickness of each scrollbar.
+static SCROLLBAR_THICKNESS: f64 = 10.0;
+/// The minimum length of a scrollbar's handle.
+static MIN_HANDLE_LENGTH: f64 = 16.0;
+/// The number of pixels scrolled per unit of mouse wheel movement.
+static WHEEL_SPEED: f64 = 20.0;
+
+/// Represents the scrollbars of the ScrollArea.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Element {
+    Vertical,
+    Horizontal,
+}
+
+/// Represents the interaction with the scrollbars. Clicked stores the
+/// distance from the start of the handle at which it was grabbed.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    Highlighted(Element),
+    Clicked(Element, f64),
+}
+
+/// Represents the state of the ScrollArea widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct State {
+    interaction: Interaction,
+    /// The distance the content has been scrolled along each axis.
+    offset: Point,
+}
+
+widget_fns!(ScrollArea, State, ScrollArea(State {
+    interaction: Interaction::Normal,
+    offset: [0.0, 0.0],
+}));
+
+/// The geometry of a single scrollbar: the position and length of its track, along
+/// with the offset and length of its handle along the track.
+#[deriving(Copy)]
+struct Bar {
+    pos: Point,
+    length: f64,
+    handle_start: f64,
+    handle_length: f64,
+    max_offset: f64,
+}
+
+impl Bar {
+    /// Return the bar for the given axis, or None if the content fits along it.
+    fn new(elem: Element, pos: Point, view_dim: Dimensions,
+           content_dim: Dimensions, offset: Point) -> Option<Bar> {
+        let axis = match elem { Element::Vertical => 1u, Element::Horizontal => 0u };
+        if content_dim[axis] <= view_dim[axis] { return None }
+        let length = view_dim[axis];
+        let max_offset = content_dim[axis] - view_dim[axis];
+        let handle_length = clamp(length * view_dim[axis] / content_dim[axis], MIN_HANDLE_LENGTH, length);
+        let handle_start = offset[axis] / max_offset * (length - handle_length);
+        let pos = match elem {
+            Element::Vertical => [pos[0] + view_dim[0], pos[1]],
+            Element::Horizontal => [pos[0], pos[1] + view_dim[1]],
+        };
+        Some(Bar { pos: pos, length: length, handle_start: handle_start,
+                   handle_length: handle_length, max_offset: max_offset })
+    }
+    /// The position and dimensions of the handle.
+    fn handle_rect(&self, elem: Element) -> (Point, Dimensions) {
+        match elem {
+            Element::Vertical => ([self.pos[0], self.pos[1] + self.handle_start],
+                                  [SCROLLBAR_THICKNESS, self.handle_length]),
+            Element::Horizontal => ([self.pos[0] + self.handle_start, self.pos[1]],
+                                    [self.handle_length, SCROLLBAR_THICKNESS]),
+        }
+    }
+    /// The position and dimensions of the track.
+    fn track_rect(&self, elem: Element) -> (Point, Dimensions) {
+        match elem {
+            Element::Vertical => (self.pos, [SCROLLBAR_THICKNESS, self.length]),
+            Element::Horizontal => (self.pos, [self.length, SCROLLBAR_THICKNESS]),
+        }
+    }
+    /// The offset at which the handle would start at the given distance along the track.
+    fn offset_at(&self, handle_start: f64) -> f64 {
+        let travel = self.length - self.handle_length;
+        if travel <= 0.0 { return 0.0 }
+        clamp(handle_start / travel * self.max_offset, 0.0, self.max_offset)
+    }
+}
+
+/// Return the scrollbar handle under the mouse (if any).
+fn is_over(mouse_pos: Point, bars: &[(Element, Option<Bar>)]) -> Option<Element> {
+    for &(elem, maybe_bar) in bars.iter() {
+        if let Some(bar) = maybe_bar {
+            let (pos, dim) = bar.handle_rect(elem);
+            if rectangle::is_over(pos, mouse_pos, dim) { return Some(elem) }
+        }
+    }
+    None
+}
+
+/// Check the current interaction with the scrollbars. Clicked stores the distance
+/// into the handle at which it was grabbed.
+fn get_new_interaction(is_over_elem: Option<Element>,
+                       prev: Interaction,
+                       mouse: Mouse,
+                       grab_at: |Element| -> f64) -> Interaction {
+    use mouse::ButtonState::{Down, Up};
+    use self::Interaction::{Normal, Highlighted, Clicked};
+    match (is_over_elem, prev, mouse.left) {
+        (Some(_),    Normal,              Down) => Normal,
+        (Some(elem), Highlighted(_),      Down) => Clicked(elem, grab_at(elem)),
+        (_,          Clicked(elem, grab), Down) => Clicked(elem, grab),
+        (Some(elem), _,                   Up)   => Highlighted(elem),
+        _                                       => Normal,
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct ScrollAreaContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    content_dim: Dimensions,
+    pos: Point,
+    dim: Dimensions,
+    maybe_content: Option<|&mut UiContext, &mut Gl, Point|:'a>,
+    maybe_callback: Option<|Point|:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+}
+
+impl<'a> ScrollAreaContext<'a> {
+    /// Instantiate the widgets within the scroll area. The closure is given the origin of
+    /// the content in window co-ordinates (already translated by the scroll offset), from
+    /// which the children should be positioned. All drawing within it is clipped to the
+    /// scroll area (within any clip of its own, see `UiContext::push_clip`).
+    #[inline]
+    pub fn content(self, content: |&mut UiContext, &mut Gl, Point|:'a) -> ScrollAreaContext<'a> {
+        ScrollAreaContext { maybe_content: Some(content), ..self }
+    }
+}
+
+pub trait ScrollAreaBuilder<'a> {
+    /// A scroll_area builder method to be implemented by the UiContext. `content_dim`
+    /// is the size of the content, which may be larger than the scroll area itself.
+    fn scroll_area(&'a mut self, ui_id: UIID, content_dim: Dimensions) -> ScrollAreaContext<'a>;
+}
+
+impl<'a> ScrollAreaBuilder<'a> for UiContext {
+    fn scroll_area(&'a mut self, ui_id: UIID, content_dim: Dimensions) -> ScrollAreaContext<'a> {
+        let pos = self.flow_pos();
+        ScrollAreaContext {
+            uic: self,
+            ui_id: ui_id,
+            content_dim: content_dim,
+            pos: pos,
+            dim: [256.0, 256.0],
+            maybe_content: None,
+            maybe_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+        }
+    }
+}
+
+impl_callable!(ScrollAreaContext, |Point|:'a);
+impl_colorable!(ScrollAreaContext);
+impl_frameable!(ScrollAreaContext);
+impl_hoverable!(ScrollAreaContext);
+impl_positionable!(ScrollAreaContext);
+impl_shapeable!(ScrollAreaContext);
+
+impl<'a> ::draw::Drawable for ScrollAreaContext<'a> {
+    /// Draw the scroll area and its content. The content is scrolled with the mouse wheel
+    /// while hovered or by dragging the scrollbars, and the `callback` is called with the
+    /// new offset whenever it changes.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let inner_pos = [self.pos[0] + frame_w, self.pos[1] + frame_w];
+        let inner_dim = [self.dim[0] - frame_w * 2.0, self.dim[1] - frame_w * 2.0];
+
+        // Leave room for a scrollbar along each axis on which the content overflows. A
+        // vertical scrollbar narrows the view, which may cause the content to overflow
+        // horizontally and vice versa.
+        let content_dim = self.content_dim;
+        let mut view_dim = inner_dim;
+        for _ in range(0u, 2u) {
+            view_dim = [
+                if content_dim[1] > view_dim[1] { inner_dim[0] - SCROLLBAR_THICKNESS } else { inner_dim[0] },
+                if content_dim[0] > view_dim[0] { inner_dim[1] - SCROLLBAR_THICKNESS } else { inner_dim[1] },
+            ];
+        }
+        let max_offset = [(content_dim[0] - view_dim[0]).max(0.0), (content_dim[1] - view_dim[1]).max(0.0)];
+
+        // Scroll with the mouse wheel.
+        let mut offset = state.offset;
+        if rectangle::is_over(self.pos, mouse.pos, self.dim) {
+            let scroll = mouse.scroll;
+            offset = [offset[0] - scroll[0] * WHEEL_SPEED, offset[1] - scroll[1] * WHEEL_SPEED];
+        }
+        offset = [clamp(offset[0], 0.0, max_offset[0]), clamp(offset[1], 0.0, max_offset[1])];
+
+        // Scroll by dragging the scrollbars.
+        let bars = [(Element::Vertical, Bar::new(Element::Vertical, inner_pos, view_dim, content_dim, offset)),
+                    (Element::Horizontal, Bar::new(Element::Horizontal, inner_pos, view_dim, content_dim, offset))];
+        let is_over_elem = is_over(mouse.pos, &bars);
+        let new_interaction = get_new_interaction(is_over_elem, state.interaction, mouse, |elem| {
+            match Bar::new(elem, inner_pos, view_dim, content_dim, offset) {
+                Some(bar) => match elem {
+                    Element::Vertical => mouse.pos[1] - bar.pos[1] - bar.handle_start,
+                    Element::Horizontal => mouse.pos[0] - bar.pos[0] - bar.handle_start,
+                },
+                None => 0.0,
+            }
+        });
+        if let Interaction::Clicked(elem, grab) = new_interaction {
+            if let Some(bar) = Bar::new(elem, inner_pos, view_dim, content_dim, offset) {
+                offset = match elem {
+                    Element::Vertical => [offset[0], bar.offset_at(mouse.pos[1] - grab - bar.pos[1])],
+                    Element::Horizontal => [bar.offset_at(mouse.pos[0] - grab - bar.pos[0]), offset[1]],
+                };
+            }
+        }
+        if offset != state.offset {
+            match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(offset),
+                None => (),
+            }
+        }
+
+        // Draw the background and the clipped content.
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            false => None,
+        };
+        rectangle::draw(self.uic.win_w, self.uic.win_h, graphics, rectangle::State::Normal,
+                        self.pos, self.dim, maybe_frame, color);
+        {
+            let ui_id = self.ui_id;
+            let ScrollAreaContext { // borrowck
+                ref mut uic,
+                ref mut maybe_content,
+                ..
+            } = *self;
+            match *maybe_content {
+                Some(ref mut content) => {
+                    uic.push_clip(inner_pos, view_dim);
+                    let origin = [inner_pos[0] - offset[0], inner_pos[1] - offset[1]];
+                    uic.begin_children(ui_id);
+                    (*content)(&mut **uic, graphics, origin);
+                    uic.end_children();
+                    uic.pop_clip();
+                },
+                None => (),
+            }
+        }
+
+        // Draw the scrollbars over the content.
+        for &elem in [Element::Vertical, Element::Horizontal].iter() {
+            if let Some(bar) = Bar::new(elem, inner_pos, view_dim, content_dim, offset) {
+                let (track_pos, track_dim) = bar.track_rect(elem);
+                let (handle_pos, handle_dim) = bar.handle_rect(elem);
+                let handle_state = match new_interaction {
+                    Interaction::Highlighted(e) if e == elem => rectangle::State::Highlighted,
+                    Interaction::Clicked(e, _) if e == elem => rectangle::State::Clicked,
+                    _ => rectangle::State::Normal,
+                };
+                rectangle::draw(self.uic.win_w, self.uic.win_h, graphics, rectangle::State::Normal,
+                                track_pos, track_dim, None, color.clicked());
+                rectangle::draw(self.uic.win_w, self.uic.win_h, graphics, handle_state,
+                                handle_pos, handle_dim, None, color.plain_contrast());
+            }
+        }
+
+        let new_state = State { interaction: new_interaction, offset: offset };
+        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+
+    }
+}
@@ -0,0 +1,104 @@
+
+use color::Color;
+use graphics;
+use graphics::Context;
+use label;
+use opengl_graphics::Gl;
+use point::Point;
+use ui_context::UiContext;
+
+/// The gap left between the label and the line on either side.
+static LABEL_GAP: f64 = 6.0;
+
+/// A context on which the builder pattern can be implemented.
+pub struct SeparatorContext<'a> {
+    uic: &'a mut UiContext,
+    length: f64,
+    thickness: f64,
+    is_vertical: bool,
+    pos: Point,
+    maybe_color: Option<Color>,
+    maybe_label: Option<&'a str>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+    maybe_label_min_font_size: Option<u32>,
+}
+
+impl<'a> SeparatorContext<'a> {
+    /// The thickness of the line.
+    #[inline]
+    pub fn thickness(self, thickness: f64) -> SeparatorContext<'a> {
+        SeparatorContext { thickness: thickness, ..self }
+    }
+    /// Draw the line downwards rather than across.
+    #[inline]
+    pub fn vertical(self) -> SeparatorContext<'a> {
+        SeparatorContext { is_vertical: true, ..self }
+    }
+}
+
+pub trait SeparatorBuilder<'a> {
+    /// A separator builder method to be implemented by the UiContext.
+    fn separator(&'a mut self, length: f64) -> SeparatorContext<'a>;
+}
+
+impl<'a> SeparatorBuilder<'a> for UiContext {
+    fn separator(&'a mut self, length: f64) -> SeparatorContext<'a> {
+        let pos = self.flow_pos();
+        SeparatorContext {
+            uic: self,
+            length: length,
+            thickness: 1.0,
+            is_vertical: false,
+            pos: pos,
+            maybe_color: None,
+            maybe_label: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+            maybe_label_min_font_size: None,
+        }
+    }
+}
+
+impl_colorable!(SeparatorContext);
+impl_labelable!(SeparatorContext);
+impl_positionable!(SeparatorContext);
+
+impl<'a> ::draw::Drawable for SeparatorContext<'a> {
+    /// Draw the separator from its position, broken around the label if one was given.
+    fn draw(&mut self, graphics: &mut Gl) {
+        let context = Context::abs(self.uic.win_w, self.uic.win_h);
+        let Color(col) = self.maybe_color.unwrap_or(self.uic.theme.frame_color);
+        let line = graphics::Line::new(col, self.thickness / 2.0);
+        let (x, y, len, is_vertical) = (self.pos[0], self.pos[1], self.length, self.is_vertical);
+        let draw_span = |graphics: &mut Gl, start: f64, end: f64| {
+            if end <= start { return }
+            match is_vertical {
+                true => line.draw([x, y + start, x, y + end], &context, graphics),
+                false => line.draw([x + start, y, x + end, y], &context, graphics),
+            }
+        };
+
+        match self.maybe_label {
+            None => draw_span(graphics, 0.0, len),
+            Some(text) => {
+                let size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_small);
+                let size = label::shrink_to_fit(self.uic, text, size, self.maybe_label_min_font_size,
+                                                len - LABEL_GAP * 2.0);
+                let text_w = label::width(self.uic, size, text);
+                // The extent of the label along the line.
+                let text_len = if is_vertical { size as f64 } else { text_w };
+                let gap_start = (len - text_len) / 2.0 - LABEL_GAP;
+                let gap_end = (len + text_len) / 2.0 + LABEL_GAP;
+                draw_span(graphics, 0.0, gap_start);
+                draw_span(graphics, gap_end, len);
+                let text_pos = match is_vertical {
+                    true => [x - text_w / 2.0, y + (len - size as f64) / 2.0],
+                    false => [x + (len - text_w) / 2.0, y - size as f64 / 2.0],
+                };
+                let text_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+                self.uic.draw_text(graphics, text_pos, size, text_color, text);
+            },
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use color::Color;
+use dimensions::Dimensions;
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+
+/// The number of layers with which the fading edge of a blurred shadow is approximated.
+static BLUR_LAYERS: uint = 4;
+
+/// A shadow drawn beneath overlays (i.e. open DropDownLists and Windows) so that they
+/// appear to be above the rest of the UI.
+#[deriving(Show, Clone, Copy, Encodable, Decodable)]
+pub struct Shadow {
+    /// The offset of the shadow from the rect that casts it.
+    pub offset: Point,
+    /// The width over which the edge of the shadow fades out.
+    pub blur: f64,
+    pub color: Color,
+}
+
+impl Shadow {
+    /// Constructor for a Shadow.
+    pub fn new(offset: Point, blur: f64, color: Color) -> Shadow {
+        Shadow { offset: offset, blur: blur, color: color }
+    }
+}
+
+/// Draw the shadow cast by the rect of the given position and dimensions. The blurred edge
+/// is approximated by layering translucent rects, each smaller than the last.
+pub fn draw(win_w: f64, win_h: f64, graphics: &mut Gl,
+            pos: Point, dim: Dimensions, shadow: Shadow) {
+    let pos = [pos[0] + shadow.offset[0], pos[1] + shadow.offset[1]];
+    let num_layers = if shadow.blur > 0.0 { BLUR_LAYERS } else { 1u };
+    let mut color = shadow.color;
+    color.set_a(shadow.color.a() / num_layers as f32);
+    for i in range(0u, num_layers) {
+        // The rects spread from half of the blur within the rect to half of it outside.
+        let perc = (num_layers - i) as f64 / num_layers as f64 - 0.5;
+        let spread = if shadow.blur > 0.0 { shadow.blur * perc } else { 0.0 };
+        let layer_pos = [pos[0] - spread, pos[1] - spread];
+        let layer_dim = [dim[0] + spread * 2.0, dim[1] + spread * 2.0];
+        if layer_dim[0] <= 0.0 || layer_dim[1] <= 0.0 { continue }
+        rectangle::draw_rounded(win_w, win_h, graphics, rectangle::State::Normal,
+                                layer_pos, layer_dim, None, color, None, spread.max(0.0));
+    }
+}
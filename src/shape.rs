@@ -1,5 +1,8 @@
 
 use dimensions::Dimensions;
+use layout::RectId;
+use point::Point;
+use position::{Anchor, Constraints};
 
 /// A trait that indicates whether or not a widget
 /// builder is positionable.
@@ -8,5 +11,33 @@ pub trait Shapeable {
     fn dim(self, dim: Dimensions) -> Self;
     fn width(self, width: f64) -> Self;
     fn height(self, height: f64) -> Self;
+    /// Set the width, and a height that gives the ratio of width to height.
+    fn dimensions_with_aspect(self, width: f64, ratio: f64) -> Self;
+    /// Size the widget to the given percentages of the window's width and height.
+    fn dim_percent(self, width: f64, height: f64) -> Self;
+    fn width_percent(self, width: f64) -> Self;
+    fn height_percent(self, height: f64) -> Self;
+    /// Position the widget against the given edges of the window, `offset` pixels in
+    /// from them. As this uses the widget's dimensions, it should follow any sizing methods.
+    fn anchor(self, anchor: Anchor, offset: Point) -> Self;
+    /// Center the widget within the window's width, keeping its y position.
+    fn center_horizontally(self) -> Self;
+    /// Center the widget within the window's height, keeping its x position.
+    fn center_vertically(self) -> Self;
+    fn center_of_window(self) -> Self;
+    /// Move the widget to the given distance from an edge of the window, keeping its
+    /// position along that edge. These are named apart from `Positionable::align_left_with`
+    /// (which aligns with another widget) as they ignore the current container's origin.
+    /// Like `anchor`, they use the widget's dimensions so should follow any sizing methods.
+    fn align_left_of_window(self, padding: f64) -> Self;
+    fn align_right_of_window(self, padding: f64) -> Self;
+    fn align_top_of_window(self, padding: f64) -> Self;
+    fn align_bottom_of_window(self, padding: f64) -> Self;
+    /// Position and size the widget by the given constraints, resolved against the
+    /// window's current size.
+    fn constrain(self, constraints: Constraints) -> Self;
+    /// Position and size the widget to fill the given rect of the UiContext's constraint
+    /// layout, as solved at the latest render event.
+    fn layout_rect(self, id: RectId) -> Self;
 }
 
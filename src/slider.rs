@@ -1,9 +1,14 @@
 use std::num::Float;
 use color::Color;
 use dimensions::Dimensions;
+use focus;
+use graphics;
+use graphics::Context;
+use input::keyboard::Key;
 use label;
-use mouse::Mouse;
+use mouse::{FINE_DRAG_SCALE, Mouse};
 use opengl_graphics::Gl;
+use padding;
 use point::Point;
 use rectangle;
 use ui_context::{
@@ -12,12 +17,21 @@ use ui_context::{
 };
 use utils::{
     clamp,
+    clampf32,
     percentage,
     value_from_perc,
 };
 use widget::Widget::Slider;
 use vecmath::vec2_add;
 
+/// The length of a tick mark relative to the slider's thickness.
+static TICK_LENGTH: f64 = 0.25;
+/// The gap between the slider and its tick labels.
+static TICK_LABEL_PADDING: f64 = 2.0;
+/// The fraction of the slider's range by which each arrow key press or mouse wheel
+/// notch moves the value.
+static STEP: f32 = 0.05;
+
 /// Represents the state of the Button widget.
 #[deriving(PartialEq, Clone, Copy)]
 pub enum State {
@@ -70,6 +84,39 @@ pub struct SliderContext<'a, T> {
     maybe_label: Option<&'a str>,
     maybe_label_color: Option<Color>,
     maybe_label_font_size: Option<u32>,
+    maybe_label_min_font_size: Option<u32>,
+    maybe_padding: Option<f64>,
+    maybe_margin: Option<f64>,
+    num_ticks: uint,
+    maybe_tick_labels: Option<&'a [&'a str]>,
+    is_enabled: bool,
+    is_focusable: bool,
+    maybe_default: Option<T>,
+}
+
+impl<'a, T> SliderContext<'a, T> {
+    /// Draw `n` evenly spaced tick marks along the track, including either end.
+    #[inline]
+    pub fn ticks(self, n: uint) -> SliderContext<'a, T> {
+        SliderContext { num_ticks: n, ..self }
+    }
+    /// Draw the given labels evenly spaced beneath (or beside, if vertical) the track.
+    /// A tick is drawn for each label if no number of ticks was given.
+    #[inline]
+    pub fn tick_labels(self, labels: &'a [&'a str]) -> SliderContext<'a, T> {
+        SliderContext { maybe_tick_labels: Some(labels), ..self }
+    }
+    /// Allow the slider to take keyboard focus when pressed or tabbed to. While focused,
+    /// the slider shows a focus ring and the arrow keys step its value.
+    #[inline]
+    pub fn focusable(self, focusable: bool) -> SliderContext<'a, T> {
+        SliderContext { is_focusable: focusable, ..self }
+    }
+    /// The value to which the slider is reset when Ctrl-clicked.
+    #[inline]
+    pub fn default_value(self, value: T) -> SliderContext<'a, T> {
+        SliderContext { maybe_default: Some(value), ..self }
+    }
 }
 
 pub trait SliderBuilder<'a, T: Float + Copy + FromPrimitive + ToPrimitive> {
@@ -83,13 +130,14 @@ SliderBuilder<'a, T> for UiContext {
     /// A button builder method to be implemented by the UiContext.
     fn slider(&'a mut self, ui_id: UIID,
               value: T, min: T, max: T) -> SliderContext<'a, T> {
+        let pos = self.flow_pos();
         SliderContext {
             uic: self,
             ui_id: ui_id,
             value: value,
             min: min,
             max: max,
-            pos: [0.0, 0.0],
+            pos: pos,
             dim: [192.0, 48.0],
             maybe_callback: None,
             maybe_color: None,
@@ -98,39 +146,90 @@ SliderBuilder<'a, T> for UiContext {
             maybe_label: None,
             maybe_label_color: None,
             maybe_label_font_size: None,
+            maybe_label_min_font_size: None,
+            maybe_padding: None,
+            maybe_margin: None,
+            num_ticks: 0u,
+            maybe_tick_labels: None,
+            is_enabled: true,
+            is_focusable: false,
+            maybe_default: None,
         }
     }
 }
 
 impl_callable!(SliderContext, |T|:'a, T);
 impl_colorable!(SliderContext, T);
+impl_enableable!(SliderContext, T);
 impl_frameable!(SliderContext, T);
+impl_hoverable!(SliderContext, T);
 impl_labelable!(SliderContext, T);
+impl_paddable!(SliderContext, T);
 impl_positionable!(SliderContext, T);
 impl_shapeable!(SliderContext, T);
 
 impl<'a, T: Float + Copy + FromPrimitive + ToPrimitive>
 ::draw::Drawable for SliderContext<'a, T> {
     fn draw(&mut self, graphics: &mut Gl) {
+        // Draw within the margin, while placing the area that includes it.
+        let (outer_pos, outer_dim) = (self.pos, self.dim);
+        let (pos, dim) = padding::inset(self.pos, self.dim, self.maybe_margin.unwrap_or(0.0));
+        self.pos = pos;
+        self.dim = dim;
 
         let state = *get_state(self.uic, self.ui_id);
-        let mouse = self.uic.get_mouse_state();
-        let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
-        let new_state = get_new_state(is_over, state, mouse);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let is_over = self.is_enabled && rectangle::is_over(self.pos, mouse.pos, self.dim);
+        let new_state = match self.is_enabled {
+            true => get_new_state(is_over, state, mouse),
+            false => State::Normal,
+        };
+        // Keep the mouse while dragging, so the drag continues wherever the mouse goes.
+        match new_state {
+            State::Clicked => self.uic.capture_mouse(self.ui_id),
+            _ => self.uic.uncapture_mouse(self.ui_id),
+        }
 
-        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let style = self.uic.theme.style(self.uic.theme.maybe_slider);
+        let frame_w = self.maybe_frame.unwrap_or(style.frame_width);
         let frame_w2 = frame_w * 2.0;
-        let frame_color = self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color);
+        let frame_color = self.maybe_frame_color.unwrap_or(style.frame_color);
+        let frame_color = if self.is_enabled { frame_color } else { frame_color.disabled() };
 
         let is_horizontal = self.dim[0] > self.dim[1];
+        // A press sets the value beneath the mouse, which then only follows the mouse once
+        // the press has become a drag.
+        let is_pressed = match (is_over, state, new_state) {
+            (true, State::Highlighted, State::Clicked) => true,
+            (_, State::Clicked, State::Clicked) => self.uic.is_past_drag_threshold(),
+            _ => false,
+        };
+        // Ctrl-clicking resets the default value (if any) on the press alone, so that
+        // holding Ctrl during a drag doesn't pin the value, while holding Shift slows the
+        // drag for fine adjustment.
+        let modifiers = self.uic.get_modifiers();
+        let maybe_perc = match (is_pressed, state, self.maybe_default) {
+            (true, State::Highlighted, Some(default)) if modifiers.ctrl =>
+                Some(percentage(default, self.min, self.max)),
+            (true, State::Clicked, _) if modifiers.shift => {
+                let (delta, len) = match is_horizontal {
+                    true => (mouse.pos[0] - mouse.prev_pos[0], self.dim[0] - frame_w2),
+                    false => (mouse.prev_pos[1] - mouse.pos[1], self.dim[1] - frame_w2),
+                };
+                let perc = percentage(self.value, self.min, self.max);
+                Some(clampf32(perc + (delta / len * FINE_DRAG_SCALE) as f32))
+            },
+            _ => None,
+        };
         let (new_value, pad_pos, pad_dim) = if is_horizontal {
             // Horizontal.
             let p = vec2_add(self.pos, [frame_w, frame_w]);
             let max_w = self.dim[0] - frame_w2;
-            let w = match (is_over, state, new_state) {
-                (true, State::Highlighted, State::Clicked) | (_, State::Clicked, State::Clicked)  =>
-                     clamp(mouse.pos[0] - p[0], 0f64, max_w),
-                _ => clamp(percentage(self.value, self.min, self.max) as f64 * max_w, 0f64, max_w),
+            let w = match (maybe_perc, is_pressed) {
+                (Some(perc), _) => clamp(perc as f64 * max_w, 0f64, max_w),
+                (None, true) => clamp(mouse.pos[0] - p[0], 0f64, max_w),
+                (None, false) =>
+                    clamp(percentage(self.value, self.min, self.max) as f64 * max_w, 0f64, max_w),
             };
             let h = self.dim[1] - frame_w2;
             let new_value = value_from_perc((w / max_w) as f32, self.min, self.max);
@@ -140,14 +239,15 @@ impl<'a, T: Float + Copy + FromPrimitive + ToPrimitive>
             let max_h = self.dim[1] - frame_w2;
             let corner = vec2_add(self.pos, [frame_w, frame_w]);
             let y_max = corner[1] + max_h;
-            let (h, p) = match (is_over, state, new_state) {
-                (true, State::Highlighted, State::Clicked) | (_, State::Clicked, State::Clicked) => {
+            let (h, p) = match (maybe_perc, is_pressed) {
+                (None, true) => {
                     let p = [corner[0], clamp(mouse.pos[1], corner[1], y_max)];
                     let h = clamp(max_h - (p[1] - corner[1]), 0.0, max_h);
                     (h, p)
                 },
-                _ => {
-                    let h = clamp(percentage(self.value, self.min, self.max) as f64 * max_h, 0.0, max_h);
+                (maybe_perc, _) => {
+                    let perc = maybe_perc.unwrap_or(percentage(self.value, self.min, self.max));
+                    let h = clamp(perc as f64 * max_h, 0.0, max_h);
                     let p = [corner[0], corner[1] + max_h - h];
                     (h, p)
                 },
@@ -157,34 +257,109 @@ impl<'a, T: Float + Copy + FromPrimitive + ToPrimitive>
             (new_value, p, [w, h])
         };
 
+        // Step the value with the arrow keys while focused and the mouse wheel while hovered.
+        let is_focusable = self.is_enabled && self.is_focusable;
+        let is_focused = self.uic.update_focus(self.ui_id, is_focusable,
+                                               new_state == State::Clicked, is_over);
+        let key_steps = match is_focused {
+            true => self.uic.get_pressed_keys().iter().fold(0i32, |steps, key| match *key {
+                Key::Right | Key::Up => steps + 1,
+                Key::Left | Key::Down => steps - 1,
+                _ => steps,
+            }),
+            false => 0,
+        };
+        let wheel_steps = match is_over {
+            true => mouse.scroll[1].round() as i32,
+            false => 0,
+        };
+        let new_value = match key_steps + wheel_steps {
+            0 => new_value,
+            steps => {
+                let perc = percentage(self.value, self.min, self.max);
+                let perc = clampf32(perc + steps as f32 * STEP);
+                value_from_perc(perc, self.min, self.max)
+            },
+        };
+
         // Callback.
         match self.maybe_callback {
-            Some(ref mut callback) => {
+            Some(ref mut callback) if self.is_enabled => {
                 if self.value != new_value || match (state, new_state) {
                     (State::Highlighted, State::Clicked) | (State::Clicked, State::Highlighted) => true,
                     _ => false,
                 } { (*callback)(new_value) }
-            }, None => (),
+            }, _ => (),
         }
 
         // Draw.
-        let rect_state = new_state.as_rectangle_state();
-        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        // The colors fade between states, so the rectangles themselves are drawn as Normal.
+        let transition = self.uic.transition(self.ui_id, new_state.as_rectangle_state());
+        let rect_state = rectangle::State::Normal;
+        let color = self.maybe_color.unwrap_or(style.shape_color);
+        let color = if self.is_enabled { color } else { color.disabled() };
 
         // Rectangle frame / backdrop.
         rectangle::draw(self.uic.win_w, self.uic.win_h, graphics, rect_state,
-                        self.pos, self.dim, None, frame_color);
+                        self.pos, self.dim, None, transition.color(frame_color));
         // Slider rectangle.
         rectangle::draw(self.uic.win_w, self.uic.win_h, graphics, rect_state,
-                        pad_pos, pad_dim, None, color);
+                        pad_pos, pad_dim, None, transition.color(color));
+
+        // Tick marks and their labels, from `min` to `max`.
+        let num_ticks = match (self.num_ticks, self.maybe_tick_labels) {
+            (0u, Some(labels)) => labels.len(),
+            (n, _) => n,
+        };
+        let (pos, dim) = (self.pos, self.dim);
+        let tick_pos = |i: uint, n: uint| -> f64 {
+            let perc = if n > 1u { i as f64 / (n - 1u) as f64 } else { 0.0 };
+            if is_horizontal { pos[0] + frame_w + perc * (dim[0] - frame_w2) }
+            else { pos[1] + dim[1] - frame_w - perc * (dim[1] - frame_w2) }
+        };
+        if num_ticks > 0u {
+            let context = Context::abs(self.uic.win_w, self.uic.win_h);
+            let Color(col) = frame_color.plain_contrast();
+            let line = graphics::Line::round(col, 0.5);
+            for i in range(0u, num_ticks) {
+                let p = tick_pos(i, num_ticks);
+                if is_horizontal {
+                    let y = self.pos[1] + self.dim[1];
+                    line.draw([p, y - self.dim[1] * TICK_LENGTH, p, y], &context, graphics);
+                } else {
+                    let x = self.pos[0] + self.dim[0];
+                    line.draw([x - self.dim[0] * TICK_LENGTH, p, x, p], &context, graphics);
+                }
+            }
+        }
+        if let Some(labels) = self.maybe_tick_labels {
+            let text_color = self.maybe_label_color.unwrap_or(style.label_color);
+            let text_color = if self.is_enabled { text_color } else { text_color.disabled() };
+            let size = self.uic.theme.font_size_small;
+            for (i, text) in labels.iter().enumerate() {
+                let p = tick_pos(i, labels.len());
+                let label_w = label::width(self.uic, size, *text);
+                let l_pos = if is_horizontal {
+                    [p - label_w / 2.0, self.pos[1] + self.dim[1] + TICK_LABEL_PADDING]
+                } else {
+                    [self.pos[0] + self.dim[0] + TICK_LABEL_PADDING, p - size as f64 / 2.0]
+                };
+                self.uic.draw_text(graphics, l_pos, size, text_color, *text);
+            }
+        }
 
         // If there's a label, draw it.
         if let Some(text) = self.maybe_label {
-            let text_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+            let text_color = self.maybe_label_color.unwrap_or(style.label_color);
+            let text_color = if self.is_enabled { text_color } else { text_color.disabled() };
             let size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_medium);
             let is_horizontal = self.dim[0] > self.dim[1];
+            let text_padding = self.maybe_padding.unwrap_or(0.0);
+            let max_w = if is_horizontal { self.dim[0] } else { self.dim[1] } - frame_w2
+                - text_padding * 2.0;
+            let size = label::shrink_to_fit(self.uic, text, size, self.maybe_label_min_font_size, max_w);
             let l_pos = if is_horizontal {
-                let x = pad_pos[0] + (pad_dim[1] - size as f64) / 2.0;
+                let x = pad_pos[0] + self.maybe_padding.unwrap_or((pad_dim[1] - size as f64) / 2.0);
                 let y = pad_pos[1] + (pad_dim[1] - size as f64) / 2.0;
                 [x, y]
             } else {
@@ -194,10 +369,16 @@ impl<'a, T: Float + Copy + FromPrimitive + ToPrimitive>
                 [x, y]
             };
             // Draw the label.
+            self.uic.set_baseline(self.ui_id, label::baseline(l_pos[1], size));
             self.uic.draw_text(graphics, l_pos, size, text_color, text.as_slice());
         }
 
-        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+        if is_focused {
+            let ring_color = self.uic.theme.link_color();
+            focus::draw_ring(self.uic.win_w, self.uic.win_h, graphics, self.pos, self.dim, ring_color);
+        }
+
+        set_state(self.uic, self.ui_id, new_state, outer_pos, outer_dim);
 
     }
 }
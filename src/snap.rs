@@ -0,0 +1,84 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use graphics;
+use graphics::Context;
+use opengl_graphics::Gl;
+use point::Point;
+
+/// The distance within which a dragged edge will snap to an alignment line.
+pub static SNAP_DISTANCE: f64 = 8.0;
+
+/// An alignment line that a dragged rect has snapped to.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Guide {
+    /// A vertical line at the given x position.
+    Vertical(f64),
+    /// A horizontal line at the given y position.
+    Horizontal(f64),
+}
+
+/// Snap a single axis. Returns the new start of the span and, if it snapped to one
+/// of the given lines (rather than the grid), the line that it snapped to.
+fn snap_axis(start: f64, len: f64, lines: &[f64], maybe_grid: Option<f64>) -> (f64, Option<f64>) {
+    let mut best: Option<(f64, f64)> = None; // (offset, line)
+    for &line in lines.iter() {
+        for &edge in [start, start + len].iter() {
+            let offset = line - edge;
+            let is_closer = match best {
+                Some((best_offset, _)) => offset.abs() < best_offset.abs(),
+                None => true,
+            };
+            if offset.abs() <= SNAP_DISTANCE && is_closer { best = Some((offset, line)) }
+        }
+    }
+    match best {
+        Some((offset, line)) => (start + offset, Some(line)),
+        None => match maybe_grid {
+            Some(spacing) if spacing > 0.0 => {
+                let snapped = (start / spacing).round() * spacing;
+                match (snapped - start).abs() <= SNAP_DISTANCE {
+                    true => (snapped, None),
+                    false => (start, None),
+                }
+            },
+            _ => (start, None),
+        },
+    }
+}
+
+/// Snap the rect being dragged to the window edges, the edges of the `others` rects
+/// and (failing those) the grid with the given spacing. Returns the snapped position
+/// along with the guides that should be drawn to show the alignment.
+pub fn snap(pos: Point, dim: Dimensions,
+            win_w: f64, win_h: f64,
+            others: &[(Point, Dimensions)],
+            maybe_grid: Option<f64>) -> (Point, Vec<Guide>) {
+    let mut xs = vec![0.0, win_w];
+    let mut ys = vec![0.0, win_h];
+    for &(o_pos, o_dim) in others.iter() {
+        xs.push(o_pos[0]);
+        xs.push(o_pos[0] + o_dim[0]);
+        ys.push(o_pos[1]);
+        ys.push(o_pos[1] + o_dim[1]);
+    }
+    let (x, maybe_x_line) = snap_axis(pos[0], dim[0], xs.as_slice(), maybe_grid);
+    let (y, maybe_y_line) = snap_axis(pos[1], dim[1], ys.as_slice(), maybe_grid);
+    let mut guides = Vec::new();
+    if let Some(line) = maybe_x_line { guides.push(Guide::Vertical(line)) }
+    if let Some(line) = maybe_y_line { guides.push(Guide::Horizontal(line)) }
+    ([x, y], guides)
+}
+
+/// Draw the given guides across the entire window.
+pub fn draw_guides(win_w: f64, win_h: f64, graphics: &mut Gl, guides: &[Guide], color: Color) {
+    let context = Context::abs(win_w, win_h);
+    let Color(col) = color;
+    let line = graphics::Line::round(col, 0.5);
+    for guide in guides.iter() {
+        match *guide {
+            Guide::Vertical(x) => line.draw([x, 0.0, x, win_h], &context, graphics),
+            Guide::Horizontal(y) => line.draw([0.0, y, win_w, y], &context, graphics),
+        }
+    }
+}
@@ -0,0 +1,194 @@
+
+use color::Color;
+use dimensions::Dimensions;
+use mouse::{Cursor, Mouse};
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use stack::Direction;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use utils::clamp;
+use widget::Widget::Split;
+
+/// The thickness of the draggable divider between the two panes.
+static DIVIDER_THICKNESS: f64 = 6.0;
+
+/// Represents the interaction with the divider.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    Highlighted,
+    /// The divider is being dragged. Stores the mouse offset from the divider's start.
+    Dragging(f64),
+}
+
+/// Represents the state of a Split.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct State {
+    interaction: Interaction,
+    /// The ratio the divider has been dragged to, or None if it has not yet been moved.
+    maybe_ratio: Option<f64>,
+}
+
+widget_fns!(Split, State, Split(State {
+    interaction: Interaction::Normal,
+    maybe_ratio: None,
+}));
+
+/// Check the current interaction with the divider.
+fn get_new_interaction(is_over: bool, prev: Interaction, grab: f64, mouse: Mouse) -> Interaction {
+    use mouse::ButtonState::{Down, Up};
+    use self::Interaction::{Normal, Highlighted, Dragging};
+    match (is_over, prev, mouse.left) {
+        (_,    Dragging(grab), Down) => Dragging(grab),
+        (true, Highlighted,    Down) => Dragging(grab),
+        (true, _,              Up)   => Highlighted,
+        _                            => Normal,
+    }
+}
+
+/// Divides a rect into two panes separated by a divider that may be dragged to change
+/// the share each pane is given. The ratio is kept by UIID across frames. Either returned
+/// pane may be split again for nested layouts.
+pub struct SplitContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    direction: Direction,
+    pos: Point,
+    dim: Dimensions,
+    ratio: f64,
+    min_size: f64,
+    maybe_color: Option<Color>,
+}
+
+impl<'a> SplitContext<'a> {
+
+    /// The share of the rect given to the first pane until the divider is dragged.
+    #[inline]
+    pub fn ratio(self, ratio: f64) -> SplitContext<'a> {
+        SplitContext { ratio: ratio, ..self }
+    }
+
+    /// The smallest length to which either pane may be dragged.
+    #[inline]
+    pub fn min_size(self, min_size: f64) -> SplitContext<'a> {
+        SplitContext { min_size: min_size, ..self }
+    }
+
+    /// Draw the divider and return the position and dimensions of the two panes, the
+    /// left (or top) pane first. This should be called following all builder methods.
+    pub fn panes(&mut self, graphics: &mut Gl) -> ((Point, Dimensions), (Point, Dimensions)) {
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let (pos, dim) = (self.pos, self.dim);
+        let is_horizontal = self.direction == Direction::Horizontal;
+        let (start, length, mouse_p) = match is_horizontal {
+            true => (pos[0], dim[0], mouse.pos[0]),
+            false => (pos[1], dim[1], mouse.pos[1]),
+        };
+        let available = length - DIVIDER_THICKNESS;
+        let divider_rect = |ratio: f64| {
+            let offset = available * ratio;
+            match is_horizontal {
+                true => ([pos[0] + offset, pos[1]], [DIVIDER_THICKNESS, dim[1]]),
+                false => ([pos[0], pos[1] + offset], [dim[0], DIVIDER_THICKNESS]),
+            }
+        };
+        let prev_ratio = state.maybe_ratio.unwrap_or(self.ratio);
+        let (prev_divider_pos, prev_divider_dim) = divider_rect(prev_ratio);
+        let is_over = rectangle::is_over(prev_divider_pos, mouse.pos, prev_divider_dim);
+        let grab = mouse_p - (start + available * prev_ratio);
+        let new_interaction = get_new_interaction(is_over, state.interaction, grab, mouse);
+        if new_interaction != Interaction::Normal {
+            self.uic.set_cursor(match is_horizontal {
+                true => Cursor::ResizeHorizontal,
+                false => Cursor::ResizeVertical,
+            })
+        }
+
+        // Move the divider while it is dragged.
+        let maybe_ratio = match new_interaction {
+            Interaction::Dragging(grab) if available > 0.0 => {
+                let min_ratio = clamp(self.min_size / available, 0.0, 0.5);
+                Some(clamp((mouse_p - grab - start) / available, min_ratio, 1.0 - min_ratio))
+            },
+            _ => state.maybe_ratio,
+        };
+        let ratio = maybe_ratio.unwrap_or(self.ratio);
+        let first_len = available * ratio;
+        let second_len = available - first_len;
+        let panes = match is_horizontal {
+            true => (
+                (pos, [first_len, dim[1]]),
+                ([pos[0] + first_len + DIVIDER_THICKNESS, pos[1]], [second_len, dim[1]]),
+            ),
+            false => (
+                (pos, [dim[0], first_len]),
+                ([pos[0], pos[1] + first_len + DIVIDER_THICKNESS], [dim[0], second_len]),
+            ),
+        };
+
+        // Draw the divider.
+        let (divider_pos, divider_dim) = divider_rect(ratio);
+        let color = self.maybe_color.unwrap_or(self.uic.theme.frame_color);
+        let rect_state = match new_interaction {
+            Interaction::Normal => rectangle::State::Normal,
+            Interaction::Highlighted => rectangle::State::Highlighted,
+            Interaction::Dragging(_) => rectangle::State::Clicked,
+        };
+        rectangle::draw(self.uic.win_w, self.uic.win_h, graphics, rect_state,
+                        divider_pos, divider_dim, None, color);
+
+        let new_state = State { interaction: new_interaction, maybe_ratio: maybe_ratio };
+        set_state(self.uic, self.ui_id, new_state, pos, dim);
+        panes
+    }
+
+}
+
+pub trait SplitBuilder<'a> {
+    /// Split a rect into two panes placed beside one another.
+    fn hsplit(&'a mut self, ui_id: UIID) -> SplitContext<'a>;
+    /// Split a rect into two panes placed beneath one another.
+    fn vsplit(&'a mut self, ui_id: UIID) -> SplitContext<'a>;
+}
+
+impl<'a> SplitBuilder<'a> for UiContext {
+
+    fn hsplit(&'a mut self, ui_id: UIID) -> SplitContext<'a> {
+        let pos = self.flow_pos();
+        SplitContext {
+            uic: self,
+            ui_id: ui_id,
+            direction: Direction::Horizontal,
+            pos: pos,
+            dim: [256.0, 256.0],
+            ratio: 0.5,
+            min_size: 0.0,
+            maybe_color: None,
+        }
+    }
+
+    fn vsplit(&'a mut self, ui_id: UIID) -> SplitContext<'a> {
+        let pos = self.flow_pos();
+        SplitContext {
+            uic: self,
+            ui_id: ui_id,
+            direction: Direction::Vertical,
+            pos: pos,
+            dim: [256.0, 256.0],
+            ratio: 0.5,
+            min_size: 0.0,
+            maybe_color: None,
+        }
+    }
+
+}
+
+impl_colorable!(SplitContext);
+impl_hoverable!(SplitContext);
+impl_positionable!(SplitContext);
+impl_shapeable!(SplitContext);
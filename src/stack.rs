@@ -0,0 +1,116 @@
+use std::num::Float;
+use dimensions::Dimensions;
+use point::Point;
+use ui_context::UiContext;
+
+/// The size of a cell along a stack's direction.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Size {
+    /// A length in pixels.
+    Fixed(f64),
+    /// A share of the length left over after the fixed cells and spacing.
+    Weight(f64),
+}
+
+/// The direction in which a stack lays out its cells.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+/// Return the offset and length of each cell sharing `length`, separated by `spacing`.
+/// Fixed cells take their own length and weighted cells divide the rest between them.
+pub fn spans(sizes: &[Size], length: f64, spacing: f64) -> Vec<(f64, f64)> {
+    let num_gaps = if sizes.len() > 0 { sizes.len() - 1 } else { 0 };
+    let (fixed, total_weight) = sizes.iter().fold((0.0, 0.0), |(fixed, weight), size| match *size {
+        Size::Fixed(len) => (fixed + len, weight),
+        Size::Weight(w) => (fixed, weight + w),
+    });
+    let remaining = (length - fixed - spacing * num_gaps as f64).max(0.0);
+    let mut offset = 0.0;
+    sizes.iter().map(|size| {
+        let len = match *size {
+            Size::Fixed(len) => len,
+            Size::Weight(w) if total_weight > 0.0 => remaining * w / total_weight,
+            Size::Weight(_) => 0.0,
+        };
+        let span = (offset, len);
+        offset += len + spacing;
+        span
+    }).collect()
+}
+
+/// Divide a rect into a row or column of cells, sized by the given `Size`s, within
+/// which widgets may be drawn. Stacks may be nested to build toolbars and sidebars.
+pub struct StackContext<'a> {
+    uic: &'a mut UiContext,
+    direction: Direction,
+    sizes: &'a [Size],
+    pos: Point,
+    dim: Dimensions,
+    spacing: f64,
+}
+
+impl<'a> StackContext<'a> {
+
+    /// The callback called for each cell with its index, position and dimensions.
+    /// This should be called following all builder methods.
+    pub fn each_cell(&'a mut self, callback: |&mut UiContext, uint, Point, Dimensions|) {
+        let length = match self.direction {
+            Direction::Horizontal => self.dim[0],
+            Direction::Vertical => self.dim[1],
+        };
+        for (i, &(offset, len)) in spans(self.sizes, length, self.spacing).iter().enumerate() {
+            let (pos, dim) = match self.direction {
+                Direction::Horizontal => ([self.pos[0] + offset, self.pos[1]], [len, self.dim[1]]),
+                Direction::Vertical => ([self.pos[0], self.pos[1] + offset], [self.dim[0], len]),
+            };
+            callback(self.uic, i, pos, dim);
+        }
+    }
+
+    /// A builder method for the space between neighbouring cells.
+    pub fn spacing(self, spacing: f64) -> StackContext<'a> {
+        StackContext { spacing: spacing, ..self }
+    }
+
+}
+
+pub trait StackBuilder<'a> {
+    /// A stack of cells placed beside one another.
+    fn hstack(&'a mut self, sizes: &'a [Size]) -> StackContext<'a>;
+    /// A stack of cells placed beneath one another.
+    fn vstack(&'a mut self, sizes: &'a [Size]) -> StackContext<'a>;
+}
+
+impl<'a> StackBuilder<'a> for UiContext {
+
+    fn hstack(&'a mut self, sizes: &'a [Size]) -> StackContext<'a> {
+        let pos = self.flow_pos();
+        StackContext {
+            uic: self,
+            direction: Direction::Horizontal,
+            sizes: sizes,
+            pos: pos,
+            dim: [256.0, 48.0],
+            spacing: 0.0,
+        }
+    }
+
+    fn vstack(&'a mut self, sizes: &'a [Size]) -> StackContext<'a> {
+        let pos = self.flow_pos();
+        StackContext {
+            uic: self,
+            direction: Direction::Vertical,
+            sizes: sizes,
+            pos: pos,
+            dim: [48.0, 256.0],
+            spacing: 0.0,
+        }
+    }
+
+}
+
+impl_positionable!(StackContext);
+impl_shapeable!(StackContext);
@@ -0,0 +1,211 @@
+use std::num::Int;
+use color::Color;
+use dimensions::Dimensions;
+use label;
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use widget::Widget::Stepper;
+
+/// The time (in seconds) a button must be held before the value begins to repeat.
+static REPEAT_DELAY: f64 = 0.4;
+/// The time (in seconds) between each repeated step while a button is held.
+static REPEAT_INTERVAL: f64 = 0.08;
+
+/// Represents the buttons either side of the value.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Element {
+    Decrement,
+    Increment,
+}
+
+/// Represents the interaction with the Stepper's buttons. Clicked stores
+/// the time at which the next repeated step is due.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted(Element),
+    Clicked(Element, f64),
+}
+
+widget_fns!(Stepper, State, Stepper(State::Normal));
+
+/// Return the element under the mouse (if any). Each button is square
+/// with a side of the stepper's height.
+fn is_over(pos: Point, mouse_pos: Point, dim: Dimensions) -> Option<Element> {
+    let button_dim = [dim[1], dim[1]];
+    if rectangle::is_over(pos, mouse_pos, button_dim) { Some(Element::Decrement) }
+    else if rectangle::is_over([pos[0] + dim[0] - dim[1], pos[1]], mouse_pos, button_dim) {
+        Some(Element::Increment)
+    }
+    else { None }
+}
+
+/// Check the current state of the stepper's buttons.
+fn get_new_state(is_over_elem: Option<Element>,
+                 prev: State,
+                 mouse: Mouse,
+                 time: f64) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::State::{Normal, Highlighted, Clicked};
+    match (is_over_elem, prev, mouse.left) {
+        (Some(_),    Normal,          Down) => Normal,
+        (Some(elem), Highlighted(_),  Down) => Clicked(elem, time + REPEAT_DELAY),
+        (Some(elem), Clicked(p_elem, next), Down) if elem == p_elem => Clicked(elem, next),
+        (Some(elem), _,               Up)   => Highlighted(elem),
+        _                                   => Normal,
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct StepperContext<'a, T> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    value: T,
+    min: T,
+    max: T,
+    step: T,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<|T|:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label: Option<&'a str>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+    maybe_label_min_font_size: Option<u32>,
+}
+
+impl<'a, T: Int + ToString> StepperContext<'a, T> {
+    /// The amount by which each press changes the value.
+    #[inline]
+    pub fn step(self, step: T) -> StepperContext<'a, T> {
+        StepperContext { step: step, ..self }
+    }
+    /// Return the value after one step in the direction of the given element.
+    fn stepped(&self, elem: Element) -> T {
+        match elem {
+            Element::Decrement => match self.value.checked_sub(self.step) {
+                Some(value) if value > self.min => value,
+                _ => self.min,
+            },
+            Element::Increment => match self.value.checked_add(self.step) {
+                Some(value) if value < self.max => value,
+                _ => self.max,
+            },
+        }
+    }
+}
+
+pub trait StepperBuilder<'a, T: Int + ToString> {
+    /// A stepper builder method to be implemented by the UiContext.
+    fn stepper(&'a mut self, ui_id: UIID, value: T, min: T, max: T) -> StepperContext<'a, T>;
+}
+
+impl<'a, T: Int + ToString> StepperBuilder<'a, T> for UiContext {
+    fn stepper(&'a mut self, ui_id: UIID, value: T, min: T, max: T) -> StepperContext<'a, T> {
+        let pos = self.flow_pos();
+        StepperContext {
+            uic: self,
+            ui_id: ui_id,
+            value: if value < min { min } else if value > max { max } else { value },
+            min: min,
+            max: max,
+            step: Int::one(),
+            pos: pos,
+            dim: [128.0, 32.0],
+            maybe_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+            maybe_label_min_font_size: None,
+        }
+    }
+}
+
+impl_callable!(StepperContext, |T|:'a, T);
+impl_colorable!(StepperContext, T);
+impl_frameable!(StepperContext, T);
+impl_hoverable!(StepperContext, T);
+impl_labelable!(StepperContext, T);
+impl_positionable!(StepperContext, T);
+impl_shapeable!(StepperContext, T);
+
+impl<'a, T: Int + ToString> ::draw::Drawable for StepperContext<'a, T> {
+    /// Draw the stepper. The `callback` is called with the new value when a
+    /// button is pressed, and repeatedly while it is held.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let time = self.uic.frame_time();
+        let is_over_elem = is_over(self.pos, mouse.pos, self.dim);
+        let mut new_state = get_new_state(is_over_elem, state, mouse, time);
+
+        // Step on press and repeat while held.
+        let maybe_step = match (state, new_state) {
+            (State::Highlighted(_), State::Clicked(elem, _)) => Some(elem),
+            (State::Clicked(_, next), State::Clicked(elem, _)) if time >= next => {
+                new_state = State::Clicked(elem, time + REPEAT_INTERVAL);
+                Some(elem)
+            },
+            _ => None,
+        };
+        if let Some(elem) = maybe_step {
+            let new_value = self.stepped(elem);
+            if new_value != self.value {
+                match self.maybe_callback {
+                    Some(ref mut callback) => (*callback)(new_value),
+                    None => (),
+                }
+            }
+        }
+
+        // Draw.
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            false => None,
+        };
+        let t_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+        let t_size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_medium);
+        let value_text = match self.maybe_label {
+            Some(label) => format!("{}: {}", label, self.value.to_string()),
+            None => self.value.to_string(),
+        };
+        let button_w = self.dim[1];
+        let value_size = label::shrink_to_fit(self.uic, value_text.as_slice(), t_size,
+                                              self.maybe_label_min_font_size,
+                                              self.dim[0] - button_w * 2.0 - frame_w * 2.0);
+        rectangle::draw_with_centered_label(
+            self.uic.win_w, self.uic.win_h, graphics, self.uic, rectangle::State::Normal,
+            [self.pos[0] + button_w, self.pos[1]], [self.dim[0] - button_w * 2.0, self.dim[1]],
+            maybe_frame, color, value_text.as_slice(), value_size, t_color
+        );
+        for &(elem, x, text) in [(Element::Decrement, self.pos[0], "-"),
+                                 (Element::Increment, self.pos[0] + self.dim[0] - button_w, "+")].iter() {
+            let rect_state = match new_state {
+                State::Highlighted(h_elem) if h_elem == elem => rectangle::State::Highlighted,
+                State::Clicked(c_elem, _) if c_elem == elem => rectangle::State::Clicked,
+                _ => rectangle::State::Normal,
+            };
+            rectangle::draw_with_centered_label(
+                self.uic.win_w, self.uic.win_h, graphics, self.uic, rect_state,
+                [x, self.pos[1]], [button_w, button_w], maybe_frame, color, text, t_size, t_color
+            );
+        }
+
+        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+
+    }
+}
@@ -0,0 +1,271 @@
+
+use color::Color;
+use dimensions::Dimensions;
+use label;
+use label::FontSize;
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use utils::format_time;
+use widget::Widget::Stopwatch;
+
+/// The stopwatch's control buttons.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Element {
+    StartStop,
+    Lap,
+    Reset,
+}
+
+/// The events reported via the stopwatch's `callback` along with the elapsed time.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Event {
+    Started,
+    Stopped,
+    Lap,
+    Reset,
+}
+
+/// Represents the interaction with the stopwatch's buttons.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    Highlighted(Element),
+    Clicked(Element),
+}
+
+/// Represents the state of the Stopwatch widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct State {
+    interaction: Interaction,
+    is_running: bool,
+    /// Seconds elapsed while running.
+    elapsed: f64,
+    /// The number of laps marked since the last reset. The lap times are kept as lines of
+    /// text (newest first) in the widget's `UiContext::text_buffer`.
+    laps: uint,
+}
+
+widget_fns!(Stopwatch, State, Stopwatch(State {
+    interaction: Interaction::Normal,
+    is_running: false,
+    elapsed: 0.0,
+    laps: 0u,
+}));
+
+/// The order in which the buttons are laid out.
+static ELEMENTS: [Element, ..3] = [Element::StartStop, Element::Lap, Element::Reset];
+
+/// Return the position and dimensions of the button at the given slot.
+fn button_rect(pos: Point, dim: Dimensions, slot: uint) -> (Point, Dimensions) {
+    let button_dim = [dim[0] / ELEMENTS.len() as f64, dim[1] / 3.0];
+    ([pos[0] + button_dim[0] * slot as f64, pos[1] + dim[1] - button_dim[1]], button_dim)
+}
+
+/// Return the button under the mouse (if any).
+fn is_over(pos: Point, mouse_pos: Point, dim: Dimensions) -> Option<Element> {
+    for (i, elem) in ELEMENTS.iter().enumerate() {
+        let (b_pos, b_dim) = button_rect(pos, dim, i);
+        if rectangle::is_over(b_pos, mouse_pos, b_dim) { return Some(*elem) }
+    }
+    None
+}
+
+/// Check the current interaction with the buttons.
+fn get_new_interaction(is_over_elem: Option<Element>,
+                       prev: Interaction,
+                       mouse: Mouse) -> Interaction {
+    use mouse::ButtonState::{Down, Up};
+    use self::Interaction::{Normal, Highlighted, Clicked};
+    match (is_over_elem, prev, mouse.left) {
+        (Some(_),    Normal,          Down) => Normal,
+        (Some(elem), _,               Up)   => Highlighted(elem),
+        (Some(elem), Highlighted(_),  Down) => Clicked(elem),
+        (_,          Clicked(p_elem), Down) => Clicked(p_elem),
+        _                                   => Normal,
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct StopwatchContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    font_size: FontSize,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<|Event, f64|:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+}
+
+impl<'a> StopwatchContext<'a> {
+    /// The font size used for the elapsed time display.
+    #[inline]
+    pub fn value_font_size(self, size: FontSize) -> StopwatchContext<'a> {
+        StopwatchContext { font_size: size, ..self }
+    }
+    #[inline]
+    pub fn label_color(self, color: Color) -> StopwatchContext<'a> {
+        StopwatchContext { maybe_label_color: Some(color), ..self }
+    }
+    #[inline]
+    pub fn label_font_size(self, size: u32) -> StopwatchContext<'a> {
+        StopwatchContext { maybe_label_font_size: Some(size), ..self }
+    }
+}
+
+pub trait StopwatchBuilder<'a> {
+    /// A stopwatch builder method to be implemented by the UiContext.
+    fn stopwatch(&'a mut self, ui_id: UIID) -> StopwatchContext<'a>;
+}
+
+impl<'a> StopwatchBuilder<'a> for UiContext {
+    fn stopwatch(&'a mut self, ui_id: UIID) -> StopwatchContext<'a> {
+        let pos = self.flow_pos();
+        StopwatchContext {
+            uic: self,
+            ui_id: ui_id,
+            font_size: 32u32,
+            pos: pos,
+            dim: [192.0, 96.0],
+            maybe_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+        }
+    }
+}
+
+impl_callable!(StopwatchContext, |Event, f64|:'a);
+impl_colorable!(StopwatchContext);
+impl_frameable!(StopwatchContext);
+impl_hoverable!(StopwatchContext);
+impl_positionable!(StopwatchContext);
+impl_shapeable!(StopwatchContext);
+
+impl<'a> ::draw::Drawable for StopwatchContext<'a> {
+    /// Draw the stopwatch. Pressing any of its buttons calls the `callback`
+    /// with the associated Event and the elapsed time.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let is_over_elem = is_over(self.pos, mouse.pos, self.dim);
+        let new_interaction = get_new_interaction(is_over_elem, state.interaction, mouse);
+
+        // Advance the clock.
+        let mut new_state = State { interaction: new_interaction, ..state };
+        if state.is_running { new_state.elapsed += self.uic.frame_dt(); }
+
+        // Handle button releases.
+        let maybe_event = match (state.interaction, new_interaction) {
+            (Interaction::Clicked(elem), Interaction::Highlighted(h_elem)) if elem == h_elem => {
+                match elem {
+                    Element::StartStop => {
+                        new_state.is_running = !state.is_running;
+                        Some(if new_state.is_running { Event::Started } else { Event::Stopped })
+                    },
+                    Element::Lap => {
+                        new_state.laps += 1u;
+                        let mut buf = [0u8, ..32];
+                        let line = format!("Lap {}: {}\n", new_state.laps,
+                                           format_time(new_state.elapsed, &mut buf));
+                        let laps = self.uic.text_buffer(self.ui_id);
+                        let prev_laps = ::std::mem::replace(laps, line);
+                        laps.push_str(prev_laps.as_slice());
+                        Some(Event::Lap)
+                    },
+                    Element::Reset => {
+                        new_state = State {
+                            interaction: new_interaction,
+                            is_running: false,
+                            elapsed: 0.0,
+                            laps: 0u,
+                        };
+                        self.uic.text_buffer(self.ui_id).clear();
+                        Some(Event::Reset)
+                    },
+                }
+            },
+            _ => None,
+        };
+        if let Some(event) = maybe_event {
+            match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(event, new_state.elapsed),
+                None => (),
+            }
+        }
+
+        // Draw the display.
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            false => None,
+        };
+        let t_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+        let t_size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_small);
+        let display_dim = [self.dim[0], self.dim[1] - button_rect(self.pos, self.dim, 0u).1[1]];
+        rectangle::draw(self.uic.win_w, self.uic.win_h, graphics, rectangle::State::Normal,
+                        self.pos, display_dim, maybe_frame, color);
+        let mut buf = [0u8, ..32];
+        {
+            let time_str = format_time(new_state.elapsed, &mut buf);
+            let time_w = label::width(self.uic, self.font_size, time_str);
+            let time_pos = [self.pos[0] + (display_dim[0] - time_w) / 2.0,
+                            self.pos[1] + frame_w];
+            self.uic.draw_text(graphics, time_pos, self.font_size, t_color, time_str);
+        }
+
+        // Every lap, newest first, beneath the time for as many as fit in the display. The
+        // buffer is taken for the duration so that the lines needn't be copied each frame.
+        if new_state.laps > 0u {
+            let laps = ::std::mem::replace(self.uic.text_buffer(self.ui_id), String::new());
+            let lap_h = t_size as f64 + 2.0;
+            let top = self.pos[1] + frame_w + self.font_size as f64 + 2.0;
+            let bottom = self.pos[1] + display_dim[1];
+            self.uic.push_clip([self.pos[0], top], [display_dim[0], bottom - top]);
+            for (i, line) in laps.as_slice().lines().enumerate() {
+                let y = top + lap_h * i as f64;
+                if y >= bottom { break }
+                let lap_w = label::width(self.uic, t_size, line);
+                let lap_pos = [self.pos[0] + (display_dim[0] - lap_w) / 2.0, y];
+                self.uic.draw_text(graphics, lap_pos, t_size, t_color, line);
+            }
+            self.uic.pop_clip();
+            *self.uic.text_buffer(self.ui_id) = laps;
+        }
+
+        // Draw the buttons.
+        for (i, elem) in ELEMENTS.iter().enumerate() {
+            let (b_pos, b_dim) = button_rect(self.pos, self.dim, i);
+            let rect_state = match new_interaction {
+                Interaction::Highlighted(h_elem) if h_elem == *elem => rectangle::State::Highlighted,
+                Interaction::Clicked(c_elem) if c_elem == *elem => rectangle::State::Clicked,
+                _ => rectangle::State::Normal,
+            };
+            let text = match *elem {
+                Element::StartStop => if new_state.is_running { "Stop" } else { "Start" },
+                Element::Lap => "Lap",
+                Element::Reset => "Reset",
+            };
+            rectangle::draw_with_centered_label(
+                self.uic.win_w, self.uic.win_h, graphics, self.uic, rect_state,
+                b_pos, b_dim, maybe_frame, color, text, t_size, t_color
+            );
+        }
+
+        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+
+    }
+}
@@ -7,17 +7,28 @@ use graphics::{
 };
 use label;
 use label::FontSize;
-use mouse::Mouse;
+use mouse::{Cursor, Mouse};
 use opengl_graphics::Gl;
+use padding;
 use input::keyboard::Key::{
     Backspace,
+    C,
+    Down,
     Left,
     Right,
     Return,
+    Up,
+    V,
+    X,
+    Y,
+    Z,
 };
 use point::Point;
 use rectangle;
+use serialize::json;
+use std::io::File;
 use std::num::Float;
+use std::str;
 use time::precise_time_s;
 use ui_context::{
     UIID,
@@ -30,6 +41,7 @@ use vecmath::{
 use widget::Widget::TextBox;
 use std::cmp;
 
+/// A byte offset into the text, always at the boundary of a character.
 pub type Idx = uint;
 pub type CursorX = f64;
 
@@ -49,7 +61,9 @@ pub enum DrawState {
 #[deriving(Show, PartialEq, Clone, Copy)]
 pub enum Capturing {
     Uncaptured,
-    Captured(Idx, CursorX),
+    /// Stores the cursor's index and position along with the index at which the selection
+    /// is anchored. Nothing is selected while the anchor is at the cursor.
+    Captured(Idx, CursorX, Idx),
 }
 
 /// Represents an element of the TextBox widget.
@@ -65,7 +79,7 @@ impl State {
     fn as_rectangle_state(&self) -> rectangle::State {
         match self {
             &State(state, capturing) => match capturing {
-                Capturing::Captured(_, _) => rectangle::State::Normal,
+                Capturing::Captured(_, _, _) => rectangle::State::Normal,
                 Capturing::Uncaptured => match state {
                     DrawState::Normal => rectangle::State::Normal,
                     DrawState::Highlighted(_) => rectangle::State::Highlighted,
@@ -114,7 +128,7 @@ fn closest_idx(uic: &mut UiContext,
     let mut x = text_x;
     let mut prev_x = x;
     let mut left_x = text_x;
-    for (i, ch) in text.chars().enumerate() {
+    for (i, ch) in text.char_indices() {
         let character = uic.get_character(font_size, ch);
         let char_w = character.width();
         x += char_w;
@@ -142,28 +156,242 @@ fn get_new_state(over_elem: Element,
                 (Nill, Highlighted(_), Up)              => State(Normal, Uncaptured),
                 (_, Normal, Up)                         |
                 (_, Highlighted(_), Up)                 => State(Highlighted(over_elem), Uncaptured),
+                (Text(idx, x), Clicked(Text(a_idx, a_x)), Down) =>
+                    State(Clicked(Text(a_idx, a_x)), Captured(idx, x, a_idx)),
                 (_, Highlighted(p_elem), Down)          |
                 (_, Clicked(p_elem), Down)              => State(Clicked(p_elem), Uncaptured),
-                (Text(idx, x), Clicked(Text(_, _)), Up) => State(Highlighted(over_elem), Captured(idx, x)),
+                (Text(idx, x), Clicked(Text(a_idx, _)), Up) =>
+                    State(Highlighted(over_elem), Captured(idx, x, a_idx)),
                 (Nill, _, _)                            => State(Normal, Uncaptured),
                 _                                       => prev_box_state,
             }
         },
-        State(prev, Captured(p_idx, p_x)) => {
+        State(prev, Captured(p_idx, p_x, p_anchor)) => {
+            let prev_capture = Captured(p_idx, p_x, p_anchor);
             match (over_elem, prev, mouse.left) {
                 (Nill, Clicked(Nill), Up)               => State(Normal, Uncaptured),
-                (Text(idx, x), Clicked(Text(_, _)), Up) => State(Highlighted(over_elem), Captured(idx, x)),
+                (Text(idx, x), Clicked(Text(a_idx, _)), Up) =>
+                    State(Highlighted(over_elem), Captured(idx, x, a_idx)),
+                // Dragging across the text selects from where it was pressed.
+                (Text(idx, x), Clicked(Text(a_idx, a_x)), Down) =>
+                    State(Clicked(Text(a_idx, a_x)), Captured(idx, x, a_idx)),
                 (_, Normal, Up)                         |
                 (_, Highlighted(_), Up)                 |
-                (_, Clicked(_), Up)                     => State(Highlighted(over_elem), Captured(p_idx, p_x)),
+                (_, Clicked(_), Up)                     => State(Highlighted(over_elem), prev_capture),
                 (_, Highlighted(p_elem), Down)          |
-                (_, Clicked(p_elem), Down)              => State(Clicked(p_elem), Captured(p_idx, p_x)),
+                (_, Clicked(p_elem), Down)              => State(Clicked(p_elem), prev_capture),
                 _                                       => prev_box_state,
             }
         },
     }
 }
 
+/// A history of the lines entered into a command-style TextBox. Pressing Return pushes
+/// the line onto the history, while Up and Down recall previous entries.
+#[deriving(Show, Clone)]
+pub struct History {
+    entries: Vec<String>,
+    max_len: uint,
+    /// The index of the entry currently recalled (if any).
+    maybe_recall: Option<Idx>,
+    /// The text that was being edited before recalling began.
+    draft: String,
+}
+
+impl History {
+
+    /// Constructor for an empty History that keeps at most `max_len` entries.
+    pub fn new(max_len: uint) -> History {
+        History { entries: Vec::new(), max_len: max_len, maybe_recall: None, draft: String::new() }
+    }
+
+    /// The entries from oldest to newest.
+    pub fn entries(&self) -> &[String] { self.entries.as_slice() }
+
+    /// Push a committed line, skipping repeats of the newest entry.
+    fn push(&mut self, line: &str) {
+        self.maybe_recall = None;
+        if self.entries.last().map_or(false, |last| last.as_slice() == line) { return }
+        self.entries.push(line.to_string());
+        if self.entries.len() > self.max_len {
+            let excess = self.entries.len() - self.max_len;
+            self.entries = self.entries.slice_from(excess).to_vec();
+        }
+    }
+
+    /// Step back to the previous entry, returning the text to display.
+    fn recall_prev(&mut self, current: &str) -> Option<String> {
+        let idx = match self.maybe_recall {
+            Some(0u) => return None,
+            Some(idx) => idx - 1u,
+            None if self.entries.len() > 0u => {
+                self.draft = current.to_string();
+                self.entries.len() - 1u
+            },
+            None => return None,
+        };
+        self.maybe_recall = Some(idx);
+        Some(self.entries[idx].clone())
+    }
+
+    /// Step forward to the next entry, returning the text to display. The draft
+    /// is restored after stepping past the newest entry.
+    fn recall_next(&mut self) -> Option<String> {
+        match self.maybe_recall {
+            Some(idx) if idx + 1u < self.entries.len() => {
+                self.maybe_recall = Some(idx + 1u);
+                Some(self.entries[idx + 1u].clone())
+            },
+            Some(_) => {
+                self.maybe_recall = None;
+                Some(::std::mem::replace(&mut self.draft, String::new()))
+            },
+            None => None,
+        }
+    }
+
+    /// Load a History's entries from file so that they persist across sessions.
+    pub fn load(path: &str, max_len: uint) -> Result<History, String> {
+        let contents = match File::open(&Path::new(path)).read_to_end() {
+            Ok(buf) => buf,
+            Err(e) => return Err(format!("Failed to load History correctly: {}", e)),
+        };
+        let entries: Option<Vec<String>> = str::from_utf8(contents.as_slice())
+            .and_then(|s| json::decode(s).ok());
+        match entries {
+            Some(entries) => {
+                let mut history = History::new(max_len);
+                for entry in entries.iter() { history.push(entry.as_slice()) }
+                Ok(history)
+            },
+            None => Err(String::from_str("Failed to load History correctly")),
+        }
+    }
+
+    /// Save a History's entries to file.
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let json_string = json::encode(&self.entries);
+        let mut file = File::create(&Path::new(path));
+        match file.write(json_string.as_bytes()) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(format!("History failed to save correctly: {}", e)),
+        }
+    }
+
+}
+
+/// The most edits that may be undone for each TextBox.
+static MAX_UNDO_LEN: uint = 100;
+
+/// The kinds of edit, by which consecutive edits are grouped into a single undo.
+#[deriving(Show, PartialEq, Clone, Copy)]
+enum EditKind {
+    /// Typing one piece of text, noting whether it was whitespace.
+    Typing(bool),
+    Deleting,
+    /// Pasting, cutting or recalling a line, which is never grouped.
+    Other,
+}
+
+/// The text, cursor index and selection anchor of a TextBox before an edit.
+#[deriving(Show, Clone)]
+struct Snapshot {
+    text: String,
+    idx: Idx,
+    anchor: Idx,
+}
+
+/// The edits made to a TextBox that may be undone with Ctrl+Z and redone with Ctrl+Y (or
+/// Ctrl+Shift+Z). The UiContext keeps one for each TextBox UIID. Typing is grouped by
+/// word and deleting by run, so that each undo reverts a sensible amount.
+#[deriving(Show, Clone)]
+pub struct EditHistory {
+    undos: Vec<Snapshot>,
+    redos: Vec<Snapshot>,
+    /// The kind of the latest edit, which a following edit of the same kind may join.
+    maybe_last_kind: Option<EditKind>,
+}
+
+impl EditHistory {
+
+    /// Constructor for an empty EditHistory.
+    pub fn new() -> EditHistory {
+        EditHistory { undos: Vec::new(), redos: Vec::new(), maybe_last_kind: None }
+    }
+
+    /// Record the state of the TextBox before an edit of the given kind.
+    fn record(&mut self, text: &str, idx: Idx, anchor: Idx, kind: EditKind) {
+        let is_grouped = match (self.maybe_last_kind, kind) {
+            // A word begins a new group once the whitespace after the previous one is typed.
+            (Some(EditKind::Typing(was_space)), EditKind::Typing(is_space)) =>
+                !was_space || is_space,
+            (Some(EditKind::Deleting), EditKind::Deleting) => true,
+            _ => false,
+        };
+        if !is_grouped {
+            self.undos.push(Snapshot { text: text.to_string(), idx: idx, anchor: anchor });
+            if self.undos.len() > MAX_UNDO_LEN { self.undos.remove(0u); }
+        }
+        self.redos.clear();
+        self.maybe_last_kind = Some(kind);
+    }
+
+    /// End the current group of edits, i.e. when the cursor is moved.
+    fn end_group(&mut self) {
+        self.maybe_last_kind = None;
+    }
+
+    /// Revert the latest group of edits, returning the text, cursor index and anchor to
+    /// restore.
+    fn undo(&mut self, text: &str, idx: Idx, anchor: Idx) -> Option<(String, Idx, Idx)> {
+        self.maybe_last_kind = None;
+        self.undos.pop().map(|snapshot| {
+            self.redos.push(Snapshot { text: text.to_string(), idx: idx, anchor: anchor });
+            (snapshot.text, snapshot.idx, snapshot.anchor)
+        })
+    }
+
+    /// Reapply the latest group of edits that was undone.
+    fn redo(&mut self, text: &str, idx: Idx, anchor: Idx) -> Option<(String, Idx, Idx)> {
+        self.maybe_last_kind = None;
+        self.redos.pop().map(|snapshot| {
+            self.undos.push(Snapshot { text: text.to_string(), idx: idx, anchor: anchor });
+            (snapshot.text, snapshot.idx, snapshot.anchor)
+        })
+    }
+
+    /// The number of states in the history, from the oldest that may be restored by undoing
+    /// to the newest that may be restored by redoing.
+    pub fn len(&self) -> uint { self.undos.len() + 1u + self.redos.len() }
+
+    /// The index of the current state within the history.
+    pub fn current(&self) -> uint { self.undos.len() }
+
+    /// Undo or redo until the state at the given index is current, returning the text,
+    /// cursor index and anchor to restore, or None if the state is already current.
+    pub fn seek(&mut self, text: &str, idx: Idx, anchor: Idx,
+                target: uint) -> Option<(String, Idx, Idx)> {
+        let target = cmp::min(target, self.len() - 1u);
+        if target == self.current() { return None }
+        let mut restored = (text.to_string(), idx, anchor);
+        while target != self.current() {
+            let next = {
+                let (ref text, idx, anchor) = restored;
+                match target < self.current() {
+                    true => self.undo(text.as_slice(), idx, anchor),
+                    false => self.redo(text.as_slice(), idx, anchor),
+                }
+            };
+            match next {
+                Some(next) => restored = next,
+                None => break,
+            }
+        }
+        Some(restored)
+    }
+
+}
+
 /// Draw the text cursor.
 fn draw_cursor(
     win_w: f64,
@@ -180,6 +408,107 @@ fn draw_cursor(
         .draw([cursor_x, pad_pos_y, cursor_x, pad_pos_y + pad_h], &context, graphics);
 }
 
+/// Draw a line beneath the text being composed by an input method.
+fn draw_underline(
+    win_w: f64,
+    win_h: f64,
+    graphics: &mut Gl,
+    color: Color,
+    start_x: f64,
+    end_x: f64,
+    y: f64
+) {
+    let context = Context::abs(win_w, win_h);
+    let Color([r, g, b, a]) = color.plain_contrast();
+    graphics::Line::round([r, g, b, a], 0.5f64)
+        .draw([start_x, y, end_x, y], &context, graphics);
+}
+
+/// Return the selected range between the cursor and the anchor as `(start, end)`.
+fn selection(idx: Idx, anchor: Idx) -> (Idx, Idx) {
+    (cmp::min(idx, anchor), cmp::max(idx, anchor))
+}
+
+/// Return the position of the cursor placed before the character at the given index.
+fn cursor_x_at(uic: &mut UiContext, text_x: f64, font_size: FontSize,
+               text: &str, idx: Idx) -> CursorX {
+    text_x + label::width(uic, font_size, text.slice_to(idx))
+}
+
+/// Return the index of the character before the given index.
+fn prev_idx(text: &str, idx: Idx) -> Idx {
+    if idx == 0u { 0u } else { text.char_range_at_reverse(idx).next }
+}
+
+/// Return the index of the character after the given index.
+fn next_idx(text: &str, idx: Idx) -> Idx {
+    if idx >= text.len() { text.len() } else { text.char_range_at(idx).next }
+}
+
+/// Clamp the given index to the text, moving it back to the start of the character that
+/// it falls within (i.e. after the text was replaced by a callback).
+fn clamp_idx(text: &str, idx: Idx) -> Idx {
+    let mut idx = cmp::min(idx, text.len());
+    while !text.is_char_boundary(idx) { idx -= 1u }
+    idx
+}
+
+/// Return the bounds `(start, end)` of the word containing the character at the given
+/// index. A character that isn't part of a word is selected on its own.
+fn word_bounds(text: &str, idx: Idx) -> (Idx, Idx) {
+    let is_word = |ch: char| ch.is_alphanumeric() || ch == '_';
+    let idx = clamp_idx(text, idx);
+    if idx < text.len() && !is_word(text.char_at(idx)) { return (idx, next_idx(text, idx)) }
+    let mut start = idx;
+    while start > 0u && is_word(text.char_at_reverse(start)) { start = prev_idx(text, start) }
+    let mut end = idx;
+    while end < text.len() && is_word(text.char_at(end)) { end = next_idx(text, end) }
+    (start, end)
+}
+
+/// Delete the selection, or the character before the cursor if nothing is selected,
+/// returning the new cursor index.
+fn delete_back(text: &mut String, idx: Idx, anchor: Idx) -> Idx {
+    let (start, end) = match selection(idx, anchor) {
+        (start, end) if start < end => (start, end),
+        _ => (prev_idx(text.as_slice(), idx), idx),
+    };
+    replace_selection(text, start, end, "")
+}
+
+/// Replace the text between the cursor and the anchor with the given text, returning the
+/// index just after the inserted text.
+fn replace_selection(text: &mut String, idx: Idx, anchor: Idx, with: &str) -> Idx {
+    let (start, end) = selection(idx, anchor);
+    let new_text = format!("{}{}{}", text.as_slice().slice_to(start), with,
+                           text.as_slice().slice_from(end));
+    *text = new_text;
+    start + with.len()
+}
+
+/// Keep the capture in step with keyboard focus. Capturing with the mouse takes focus,
+/// gaining focus by Tab captures at the end of the text and losing focus uncaptures.
+fn sync_focus(uic: &mut UiContext, ui_id: UIID, prev: State, state: State,
+              end_idx: Idx, end_x: CursorX) -> State {
+    let State(draw_state, capturing) = state;
+    let was_captured = match prev { State(_, Capturing::Captured(_, _, _)) => true, _ => false };
+    uic.register_focusable(ui_id);
+    match (capturing, uic.is_focused(ui_id)) {
+        (Capturing::Captured(_, _, _), false) if !was_captured => {
+            uic.set_focus(Some(ui_id));
+            state
+        },
+        (Capturing::Captured(_, _, _), false) => State(draw_state, Capturing::Uncaptured),
+        (Capturing::Uncaptured, true) if was_captured => {
+            uic.set_focus(None);
+            state
+        },
+        (Capturing::Uncaptured, true) =>
+            State(draw_state, Capturing::Captured(end_idx, end_x, end_idx)),
+        _ => state,
+    }
+}
+
 /// A context on which the builder pattern can be implemented.
 pub struct TextBoxContext<'a> {
     uic: &'a mut UiContext,
@@ -192,12 +521,25 @@ pub struct TextBoxContext<'a> {
     maybe_color: Option<Color>,
     maybe_frame: Option<f64>,
     maybe_frame_color: Option<Color>,
+    maybe_padding: Option<f64>,
+    maybe_margin: Option<f64>,
+    maybe_history: Option<&'a mut History>,
+    is_focusable: bool,
 }
 
 impl<'a> TextBoxContext<'a> {
     pub fn font_size(self, font_size: FontSize) -> TextBoxContext<'a> {
         TextBoxContext { font_size: font_size, ..self }
     }
+    /// Record entered lines in the given History and recall them with Up and Down.
+    pub fn history(self, history: &'a mut History) -> TextBoxContext<'a> {
+        TextBoxContext { maybe_history: Some(history), ..self }
+    }
+    /// Allow the text box to take keyboard focus when tabbed to. The box is captured for
+    /// as long as it has focus, and is focused whenever it is captured with the mouse.
+    pub fn focusable(self, focusable: bool) -> TextBoxContext<'a> {
+        TextBoxContext { is_focusable: focusable, ..self }
+    }
 }
 
 pub trait TextBoxBuilder<'a> {
@@ -208,17 +550,22 @@ pub trait TextBoxBuilder<'a> {
 impl<'a> TextBoxBuilder<'a> for UiContext {
     /// Initialise a TextBoxContext.
     fn text_box(&'a mut self, ui_id: UIID, text: &'a mut String) -> TextBoxContext<'a> {
+        let pos = self.flow_pos();
         TextBoxContext {
             uic: self,
             ui_id: ui_id,
             text: text,
             font_size: 24u32, // Default font_size.
-            pos: [0.0, 0.0],
+            pos: pos,
             dim: [192.0, 48.0],
             maybe_callback: None,
             maybe_color: None,
             maybe_frame: None,
             maybe_frame_color: None,
+            maybe_padding: None,
+            maybe_margin: None,
+            maybe_history: None,
+            is_focusable: false,
         }
     }
 }
@@ -227,121 +574,265 @@ impl<'a> TextBoxBuilder<'a> for UiContext {
 impl_callable!(TextBoxContext, |&mut String|:'a);
 impl_colorable!(TextBoxContext);
 impl_frameable!(TextBoxContext);
+impl_hoverable!(TextBoxContext);
+impl_paddable!(TextBoxContext);
 impl_positionable!(TextBoxContext);
 impl_shapeable!(TextBoxContext);
 
 impl<'a> ::draw::Drawable for TextBoxContext<'a> {
     #[inline]
     fn draw(&mut self, graphics: &mut Gl) {
-        let mouse = self.uic.get_mouse_state();
-        let state = *get_state(self.uic, self.ui_id);
+        // Draw within the margin, while placing the area that includes it.
+        let (outer_pos, outer_dim) = (self.pos, self.dim);
+        let (pos, dim) = padding::inset(self.pos, self.dim, self.maybe_margin.unwrap_or(0.0));
+        self.pos = pos;
+        self.dim = dim;
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        // The text may have been changed elsewhere since the previous frame, i.e. by a
+        // HistoryScrubber, so the cursor and the anchor are kept within it.
+        let state = match *get_state(self.uic, self.ui_id) {
+            State(draw_state, Capturing::Captured(idx, cursor_x, anchor)) => {
+                let text = self.text.as_slice();
+                State(draw_state, Capturing::Captured(clamp_idx(text, idx), cursor_x,
+                                                      clamp_idx(text, anchor)))
+            },
+            state => state,
+        };
 
         // Rect.
-        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
-        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let style = self.uic.theme.style(self.uic.theme.maybe_text_box);
+        let color = self.maybe_color.unwrap_or(style.shape_color);
+        let frame_w = self.maybe_frame.unwrap_or(style.frame_width);
         let frame_w2 = frame_w * 2.0;
         let maybe_frame = match frame_w > 0.0 {
-            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(style.frame_color))),
             false => None,
         };
         let pad_pos = vec2_add(self.pos, [frame_w, ..2]);
         let pad_dim = vec2_sub(self.dim, [frame_w2, ..2]);
-        let text_x = pad_pos[0] + TEXT_PADDING;
+        let text_padding = self.maybe_padding.unwrap_or(TEXT_PADDING);
+        let text_x = pad_pos[0] + text_padding;
         let text_y = pad_pos[1] + (pad_dim[1] - self.font_size as f64) / 2.0;
         let text_pos = [text_x, text_y];
+        self.uic.set_baseline(self.ui_id, label::baseline(text_y, self.font_size));
         let text_w = label::width(self.uic, self.font_size, self.text.as_slice());
         let over_elem = over_elem(self.uic, self.pos, mouse.pos, self.dim,
                                   pad_pos, pad_dim, text_pos, text_w,
                                   self.font_size, self.text.as_slice());
-        let new_state = get_new_state(over_elem, state, mouse);
+        match over_elem {
+            Element::Nill => (),
+            _ => self.uic.set_cursor(Cursor::Text),
+        }
+        let new_state = match state {
+            // Clicking an on-screen keyboard (or other focus-safe area) shouldn't uncapture.
+            State(_, Capturing::Captured(_, _, _)) if self.uic.is_focus_safe(mouse.pos) => state,
+            _ => get_new_state(over_elem, state, mouse),
+        };
+        let new_state = match self.is_focusable {
+            true => sync_focus(self.uic, self.ui_id, state, new_state,
+                               self.text.len(), text_x + text_w),
+            false => new_state,
+        };
+        // Double-clicking the text selects the word beneath the mouse. The press is then
+        // held as a press on the rect so that it doesn't drag a new selection.
+        let new_state = match (new_state, over_elem, mouse.is_double_click) {
+            (State(_, Capturing::Captured(_, _, _)), Element::Text(idx, _), true) => {
+                let (start, end) = word_bounds(self.text.as_slice(), idx);
+                let end_x = cursor_x_at(self.uic, text_x, self.font_size, self.text.as_slice(), end);
+                State(DrawState::Clicked(Element::Rect), Capturing::Captured(end, end_x, start))
+            },
+            _ => new_state,
+        };
 
         rectangle::draw(self.uic.win_w, self.uic.win_h, graphics, new_state.as_rectangle_state(),
                         self.pos, self.dim, maybe_frame, color);
-        self.uic.draw_text(graphics, text_pos, self.font_size,
-                           color.plain_contrast(), self.text.as_slice());
+        // The text being composed by an input method is shown at the cursor.
+        let maybe_preedit = match new_state {
+            State(_, Capturing::Captured(_, _, _)) => {
+                self.uic.capture_text();
+                self.uic.get_preedit()
+            },
+            State(_, Capturing::Uncaptured) => None,
+        };
+        // Highlight the selection (if any) beneath the text.
+        if let (State(_, Capturing::Captured(idx, _, anchor)), None) = (new_state, &maybe_preedit) {
+            let (start, end) = selection(idx, anchor);
+            if start < end {
+                let text = self.text.as_slice();
+                let start_x = cursor_x_at(self.uic, text_x, self.font_size, text, start);
+                let end_x = cursor_x_at(self.uic, text_x, self.font_size, text, end);
+                let selection_color = self.uic.theme.link_color();
+                rectangle::draw(self.uic.win_w, self.uic.win_h, graphics, rectangle::State::Normal,
+                                [start_x, pad_pos[1]], [end_x - start_x, pad_dim[1]], None,
+                                selection_color);
+            }
+        }
+        let preedit_w = match (new_state, &maybe_preedit) {
+            (State(_, Capturing::Captured(idx, cursor_x, _)), &Some(ref preedit)) => {
+                let shown = {
+                    let text = self.text.as_slice();
+                    format!("{}{}{}", text.slice_to(idx), preedit, text.slice_from(idx))
+                };
+                self.uic.draw_text(graphics, text_pos, self.font_size,
+                                   color.plain_contrast(), shown.as_slice());
+                let preedit_w = label::width(self.uic, self.font_size, preedit.as_slice());
+                draw_underline(self.uic.win_w, self.uic.win_h, graphics, color, cursor_x,
+                               cursor_x + preedit_w, text_y + self.font_size as f64);
+                preedit_w
+            },
+            _ => {
+                self.uic.draw_text(graphics, text_pos, self.font_size,
+                                   color.plain_contrast(), self.text.as_slice());
+                0.0
+            },
+        };
 
         let new_state = match new_state { State(w_state, capturing) => match capturing {
             Capturing::Uncaptured => new_state,
-            Capturing::Captured(idx, cursor_x) => {
+            Capturing::Captured(idx, cursor_x, anchor) => {
                 draw_cursor(self.uic.win_w, self.uic.win_h, graphics, color,
-                            cursor_x, pad_pos[1], pad_dim[1]);
+                            cursor_x + preedit_w, pad_pos[1], pad_dim[1]);
                 let mut new_idx = idx;
-                let mut new_cursor_x = cursor_x;
-
-                // Check for entered text.
-                let entered_text = self.uic.get_entered_text();
-                for t in entered_text.iter() {
-                    let mut entered_text_width = 0f64;
-                    for ch in t.as_slice().chars() {
-                        let c = self.uic.get_character(self.font_size, ch);
-                        entered_text_width += c.width();
-                    }
-                    if new_cursor_x + entered_text_width < pad_pos[0] + pad_dim[0] - TEXT_PADDING {
-                        new_cursor_x += entered_text_width;
-                    }
-                    else {
-                        break;
-                    }
-                    let new_text = format!("{}{}{}", self.text.as_slice().slice_to(idx),
-                                           t, self.text.as_slice().slice_from(idx));
-                    *self.text = new_text;
-                    new_idx += t.len();
+                let mut new_anchor = anchor;
+                let max_x = pad_pos[0] + pad_dim[0] - text_padding;
+
+                // Check for entered text, which replaces the selection. Text entered
+                // while Ctrl is held belongs to a shortcut rather than being typed.
+                let modifiers = self.uic.get_modifiers();
+                let entered_text = match modifiers.ctrl {
+                    true => Vec::new(),
+                    false => self.uic.get_entered_text(),
+                };
+                let pasted_text = match self.uic.get_pressed_keys().iter()
+                    .any(|key| *key == V) && modifiers.ctrl {
+                    true => self.uic.get_clipboard_text(),
+                    false => None,
+                };
+                // Only the first line of pasted text is kept.
+                let pasted_text = pasted_text.map(|text| {
+                    text.as_slice().lines_any().next().unwrap_or("").to_string()
+                });
+                let num_entered = entered_text.len();
+                for (i, t) in entered_text.iter().chain(pasted_text.iter()).enumerate() {
+                    let (start, end) = selection(new_idx, new_anchor);
+                    let kept_w = {
+                        let (before, after) = (self.text.as_slice().slice_to(start),
+                                               self.text.as_slice().slice_from(end));
+                        label::width(self.uic, self.font_size, before)
+                            + label::width(self.uic, self.font_size, after)
+                    };
+                    let entered_w = label::width(self.uic, self.font_size, t.as_slice());
+                    if text_x + kept_w + entered_w >= max_x { break }
+                    let kind = match i < num_entered {
+                        true => EditKind::Typing(t.as_slice().chars().all(|c| c.is_whitespace())),
+                        false => EditKind::Other,
+                    };
+                    self.uic.edit_history(self.ui_id)
+                        .record(self.text.as_slice(), new_idx, new_anchor, kind);
+                    new_idx = replace_selection(&mut *self.text, new_idx, new_anchor, t.as_slice());
+                    new_anchor = new_idx;
                 }
 
-                // Check for control keys.
-                let pressed_keys = self.uic.get_pressed_keys();
+                // Check for control keys. While composing, the keys belong to the input method.
+                let pressed_keys = match maybe_preedit {
+                    Some(_) => Vec::new(),
+                    None => self.uic.get_pressed_keys(),
+                };
                 for key in pressed_keys.iter() {
+                    let (start, end) = selection(new_idx, new_anchor);
+                    let is_selecting = start < end;
                     match *key {
                         Backspace => {
-                            if self.text.len() > 0u
-                            && self.text.len() >= idx
-                            && idx > 0u {
-                                let rem_idx = idx - 1u;
-                                new_cursor_x -= self.uic.get_character_w(
-                                    self.font_size, self.text.as_slice().char_at(rem_idx)
+                            if is_selecting || new_idx > 0u {
+                                self.uic.edit_history(self.ui_id).record(
+                                    self.text.as_slice(), new_idx, new_anchor, EditKind::Deleting
                                 );
-                                let new_text = format!("{}{}",
-                                                       self.text.as_slice().slice_to(rem_idx),
-                                                       self.text.as_slice().slice_from(idx));
-                                *self.text = new_text;
-                                new_idx = rem_idx;
                             }
+                            new_idx = delete_back(&mut *self.text, new_idx, new_anchor);
+                            new_anchor = new_idx;
                         },
-                        Left => {
-                            if idx > 0 {
-                                new_cursor_x -= self.uic.get_character_w(
-                                    self.font_size, self.text.as_slice().char_at(idx - 1u)
+                        // Shift extends the selection. Otherwise the selection collapses
+                        // to the side moved towards.
+                        Left | Right => {
+                            new_idx = match (*key == Left, is_selecting && !modifiers.shift) {
+                                (true, true) => start,
+                                (false, true) => end,
+                                (true, false) => prev_idx(self.text.as_slice(), new_idx),
+                                (false, false) => next_idx(self.text.as_slice(), new_idx),
+                            };
+                            if !modifiers.shift { new_anchor = new_idx }
+                            self.uic.edit_history(self.ui_id).end_group();
+                        },
+                        // Copy or cut the selection, or the whole text if nothing is selected.
+                        C | X if modifiers.ctrl => {
+                            let (start, end) = match is_selecting {
+                                true => (start, end),
+                                false => (0u, self.text.len()),
+                            };
+                            self.uic.set_clipboard_text(self.text.as_slice().slice(start, end));
+                            if *key == X {
+                                self.uic.edit_history(self.ui_id).record(
+                                    self.text.as_slice(), new_idx, new_anchor, EditKind::Other
                                 );
-                                new_idx -= 1u;
+                                new_idx = replace_selection(&mut *self.text, start, end, "");
+                                new_anchor = new_idx;
                             }
                         },
-                        Right => {
-                            if self.text.len() > idx {
-                                new_cursor_x += self.uic.get_character_w(
-                                    self.font_size, self.text.as_slice().char_at(idx)
-                                );
-                                new_idx += 1u;
+                        // Undo with Ctrl+Z and redo with Ctrl+Y or Ctrl+Shift+Z.
+                        Z | Y if modifiers.ctrl => {
+                            let is_redo = *key == Y || modifiers.shift;
+                            let restored = {
+                                let history = self.uic.edit_history(self.ui_id);
+                                let text = self.text.as_slice();
+                                match is_redo {
+                                    true => history.redo(text, new_idx, new_anchor),
+                                    false => history.undo(text, new_idx, new_anchor),
+                                }
+                            };
+                            if let Some((text, idx, anchor)) = restored {
+                                *self.text = text;
+                                new_idx = clamp_idx(self.text.as_slice(), idx);
+                                new_anchor = clamp_idx(self.text.as_slice(), anchor);
+                            }
+                        },
+                        Up | Down => {
+                            let ui_id = self.ui_id;
+                            let TextBoxContext { // borrowck
+                                ref mut uic,
+                                ref mut maybe_history,
+                                ref mut text,
+                                ..
+                            } = *self;
+                            let recalled = match *maybe_history {
+                                Some(ref mut history) => match *key {
+                                    Up => history.recall_prev(text.as_slice()),
+                                    _ => history.recall_next(),
+                                },
+                                None => None,
+                            };
+                            if let Some(line) = recalled {
+                                uic.edit_history(ui_id)
+                                    .record(text.as_slice(), new_idx, new_anchor, EditKind::Other);
+                                **text = line;
+                                new_idx = text.len();
+                                new_anchor = new_idx;
                             }
                         },
                         Return => if self.text.len() > 0u {
+                            match self.maybe_history {
+                                Some(ref mut history) => history.push(self.text.as_slice()),
+                                None => (),
+                            }
                             let TextBoxContext { // borrowck
                                 ref mut maybe_callback,
-                                ref mut uic,
-                                ref font_size,
                                 ref mut text,
                                 ..
                             } = *self;
                             match *maybe_callback {
                                 Some(ref mut callback) => {
                                     (*callback)(*text);
-
-                                    new_idx = cmp::min(new_idx, text.len());
-                                    let text = &*text;
-                                    new_cursor_x = text.chars()
-                                                       // Add text_pos.x for padding
-                                                       .fold(text_pos[0], |acc, c| {
-                                        acc + uic.get_character_w(*font_size, c)
-                                    });
+                                    new_idx = clamp_idx(text.as_slice(), new_idx);
+                                    new_anchor = clamp_idx(text.as_slice(), new_anchor);
                                 },
                                 None => (),
                             }
@@ -350,11 +841,39 @@ impl<'a> ::draw::Drawable for TextBoxContext<'a> {
                     }
                 }
 
-                State(w_state, Capturing::Captured(new_idx, new_cursor_x))
+                let new_cursor_x = cursor_x_at(self.uic, text_x, self.font_size,
+                                               self.text.as_slice(), new_idx);
+                State(w_state, Capturing::Captured(new_idx, new_cursor_x, new_anchor))
             },
         }};
 
-        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+        set_state(self.uic, self.ui_id, new_state, outer_pos, outer_dim);
+
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{delete_back, replace_selection, word_bounds};
+
+    #[test]
+    fn backspace_after_committing_multi_byte_text_deletes_a_whole_character() {
+        let mut text = String::new();
+        let idx = replace_selection(&mut text, 0u, 0u, "日本");
+        assert_eq!(idx, 6u);
+        let idx = delete_back(&mut text, idx, idx);
+        assert_eq!((text.as_slice(), idx), ("日", 3u));
+        let idx = delete_back(&mut text, idx, idx);
+        assert_eq!((text.as_slice(), idx), ("", 0u));
+        assert_eq!(delete_back(&mut text, idx, idx), 0u);
+    }
 
+    #[test]
+    fn word_bounds_are_byte_offsets_of_multi_byte_words() {
+        let text = "ab 日本語 c";
+        assert_eq!(word_bounds(text, 3u), (3u, 12u));
+        assert_eq!(word_bounds(text, 6u), (3u, 12u));
+        assert_eq!(word_bounds(text, 1u), (0u, 2u));
+        assert_eq!(word_bounds(text, 12u), (12u, 13u));
     }
 }
@@ -1,5 +1,7 @@
 
 use color::Color;
+use rectangle::Gradient;
+use shadow::Shadow;
 use serialize::{
     json,
     Encodable,
@@ -17,12 +19,58 @@ pub struct Theme {
     pub shape_color: Color,
     pub frame_color: Color,
     pub frame_width: f64,
+    /// The radius to which the corners of buttons and panels are rounded (see
+    /// `Theme::corner_radius`). Optional so that themes saved before it still load.
+    pub maybe_corner_radius: Option<f64>,
+    /// The gradient with which buttons and panels are filled in place of their color.
+    pub maybe_gradient: Option<Gradient>,
+    /// The shadow drawn beneath open DropDownLists and Windows.
+    pub maybe_shadow: Option<Shadow>,
     pub label_color: Color,
+    /// The color of links, selections and focus rings (see `Theme::link_color`). Optional so
+    /// that themes saved before it still load.
+    pub maybe_link_color: Option<Color>,
     pub font_size_large: u32,
     pub font_size_medium: u32,
     pub font_size_small: u32,
-    //TODO: Add unique theme-ing for each widget.
-    //i.e. maybe_slider: Option<SliderTheme>, etc
+    pub maybe_button: Option<WidgetTheme>,
+    pub maybe_drop_down_list: Option<WidgetTheme>,
+    pub maybe_number_dialer: Option<WidgetTheme>,
+    pub maybe_slider: Option<WidgetTheme>,
+    pub maybe_text_box: Option<WidgetTheme>,
+    pub maybe_toggle: Option<WidgetTheme>,
+}
+
+/// Style-related data for a single type of widget. Any field left as None falls back to
+/// the Theme's global default.
+#[deriving(Show, Clone, Copy, Encodable, Decodable)]
+pub struct WidgetTheme {
+    pub maybe_shape_color: Option<Color>,
+    pub maybe_frame_color: Option<Color>,
+    pub maybe_frame_width: Option<f64>,
+    pub maybe_label_color: Option<Color>,
+}
+
+impl WidgetTheme {
+    /// Constructor for a WidgetTheme that falls back to the global defaults for everything.
+    pub fn new() -> WidgetTheme {
+        WidgetTheme {
+            maybe_shape_color: None,
+            maybe_frame_color: None,
+            maybe_frame_width: None,
+            maybe_label_color: None,
+        }
+    }
+}
+
+/// The style with which a widget is drawn when the widget itself doesn't override it,
+/// given by `Theme::style`.
+#[deriving(Show, Clone, Copy)]
+pub struct Style {
+    pub shape_color: Color,
+    pub frame_color: Color,
+    pub frame_width: f64,
+    pub label_color: Color,
 }
 
 impl Theme {
@@ -35,13 +83,97 @@ impl Theme {
             shape_color: Color::new(1.0, 1.0, 1.0, 1.0),
             frame_color: Color::new(0.0, 0.0, 0.0, 1.0),
             frame_width: 1.0,
+            maybe_corner_radius: None,
+            maybe_gradient: None,
+            maybe_shadow: None,
+            label_color: Color::new(0.0, 0.0, 0.0, 1.0),
+            maybe_link_color: Some(Color::new(0.1, 0.3, 0.9, 1.0)),
+            font_size_large: 32,
+            font_size_medium: 24,
+            font_size_small: 18,
+            maybe_button: None,
+            maybe_drop_down_list: None,
+            maybe_number_dialer: None,
+            maybe_slider: None,
+            maybe_text_box: None,
+            maybe_toggle: None,
+        }
+    }
+
+    /// A theme using the color-blind safe Okabe-Ito palette (see `color_blind::OKABE_ITO`).
+    pub fn color_blind_safe() -> Theme {
+        Theme {
+            name: "Color Blind Safe Theme".to_string(),
+            background_color: Color::new(0.0, 0.0, 0.0, 1.0),
+            shape_color: Color::new(0.34, 0.71, 0.91, 1.0),
+            frame_color: Color::new(0.0, 0.0, 0.0, 1.0),
+            frame_width: 1.0,
+            maybe_corner_radius: None,
+            maybe_gradient: None,
+            maybe_shadow: None,
             label_color: Color::new(0.0, 0.0, 0.0, 1.0),
+            maybe_link_color: Some(Color::new(0.0, 0.45, 0.7, 1.0)),
             font_size_large: 32,
             font_size_medium: 24,
             font_size_small: 18,
+            maybe_button: None,
+            maybe_drop_down_list: None,
+            maybe_number_dialer: None,
+            maybe_slider: None,
+            maybe_text_box: None,
+            maybe_toggle: None,
         }
     }
 
+    /// A dark variant of the color-blind safe theme.
+    pub fn color_blind_safe_dark() -> Theme {
+        Theme {
+            name: "Color Blind Safe Dark Theme".to_string(),
+            background_color: Color::new(0.1, 0.1, 0.1, 1.0),
+            shape_color: Color::new(0.0, 0.45, 0.7, 1.0),
+            frame_color: Color::new(0.9, 0.6, 0.0, 1.0),
+            frame_width: 1.0,
+            maybe_corner_radius: None,
+            maybe_gradient: None,
+            maybe_shadow: None,
+            label_color: Color::new(1.0, 1.0, 1.0, 1.0),
+            maybe_link_color: Some(Color::new(0.34, 0.71, 0.91, 1.0)),
+            font_size_large: 32,
+            font_size_medium: 24,
+            font_size_small: 18,
+            maybe_button: None,
+            maybe_drop_down_list: None,
+            maybe_number_dialer: None,
+            maybe_slider: None,
+            maybe_text_box: None,
+            maybe_toggle: None,
+        }
+    }
+
+    /// Return the style for a type of widget, i.e. `theme.style(theme.maybe_slider)`, where
+    /// the widget's theme (if any) takes precedence over the global defaults.
+    pub fn style(&self, maybe_widget: Option<WidgetTheme>) -> Style {
+        let widget = maybe_widget.unwrap_or(WidgetTheme::new());
+        Style {
+            shape_color: widget.maybe_shape_color.unwrap_or(self.shape_color),
+            frame_color: widget.maybe_frame_color.unwrap_or(self.frame_color),
+            frame_width: widget.maybe_frame_width.unwrap_or(self.frame_width),
+            label_color: widget.maybe_label_color.unwrap_or(self.label_color),
+        }
+    }
+
+    /// The radius to which the corners of buttons and panels are rounded, or square corners
+    /// if the theme doesn't give one.
+    pub fn corner_radius(&self) -> f64 {
+        self.maybe_corner_radius.unwrap_or(0.0)
+    }
+
+    /// The color of links, selections and focus rings, or a default blue if the theme
+    /// doesn't give one.
+    pub fn link_color(&self) -> Color {
+        self.maybe_link_color.unwrap_or(Color::new(0.1, 0.3, 0.9, 1.0))
+    }
+
     /// Load a theme from file.
     pub fn load(path: &str) -> Result<Theme, String> {
         let contents = match File::open(&Path::new(path)).read_to_end() {
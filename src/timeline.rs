@@ -0,0 +1,380 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use graphics;
+use graphics::Context;
+use label;
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use utils::clamp;
+use widget::Widget::Timeline;
+
+/// The height of the time ruler along the top.
+static RULER_HEIGHT: f64 = 20.0;
+/// The width of the track names down the left.
+static HEADER_WIDTH: f64 = 80.0;
+/// The half-width of each keyframe's diamond.
+static KEYFRAME_RADIUS: f64 = 5.0;
+/// The minimum number of pixels between each of the ruler's ticks.
+static MIN_TICK_SPACING: f64 = 60.0;
+/// The number of pixels the mouse must be dragged to double or halve the zoom.
+static ZOOM_SENSITIVITY: f64 = 100.0;
+/// The length (in seconds) to which a shorter timeline is extended.
+static MIN_LENGTH: f64 = 0.001;
+
+/// A single track of keyframes. Keyframes are times in seconds and are not kept in order,
+/// so the index of a keyframe remains the same while it is dragged past another.
+#[deriving(Show, Clone)]
+pub struct Track {
+    pub name: String,
+    pub keyframes: Vec<f64>,
+}
+
+impl Track {
+    /// Constructor for a Track.
+    pub fn new(name: &str, keyframes: Vec<f64>) -> Track {
+        Track { name: name.to_string(), keyframes: keyframes }
+    }
+}
+
+/// The visible part of the time axis.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct View {
+    pub start: f64,
+    pub duration: f64,
+}
+
+/// The events for which the `callback` is called. Tracks and keyframes are given by index.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Event {
+    Added(uint, uint, f64),
+    Moved(uint, uint, f64),
+    Deleted(uint, uint),
+}
+
+/// Represents the elements that make up the Timeline.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Element {
+    Ruler,
+    Track(uint),
+    Keyframe(uint, uint),
+}
+
+/// An enum to define which button is clicked.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum MouseButton {
+    Left,
+    Right,
+}
+
+/// Represents the interaction with the Timeline. Clicked stores the last
+/// mouse position for determining how far the mouse has been dragged.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted(Element),
+    Clicked(Element, MouseButton, Point),
+}
+
+widget_fns!(Timeline, State, Timeline(State::Normal));
+
+/// Return the element under the mouse (if any).
+fn is_over(pos: Point,
+           mouse_pos: Point,
+           dim: Dimensions,
+           tracks: &Vec<Track>,
+           track_h: f64,
+           x_at: |f64| -> f64) -> Option<Element> {
+    let area_pos = [pos[0] + HEADER_WIDTH, pos[1]];
+    let area_dim = [dim[0] - HEADER_WIDTH, dim[1]];
+    if !rectangle::is_over(area_pos, mouse_pos, area_dim) { return None }
+    if mouse_pos[1] < pos[1] + RULER_HEIGHT { return Some(Element::Ruler) }
+    let track = ((mouse_pos[1] - pos[1] - RULER_HEIGHT) / track_h).floor() as uint;
+    if track >= tracks.len() { return None }
+    let y = pos[1] + RULER_HEIGHT + track_h * (track as f64 + 0.5);
+    for (i, &time) in tracks[track].keyframes.iter().enumerate() {
+        if (mouse_pos[0] - x_at(time)).abs() <= KEYFRAME_RADIUS
+        && (mouse_pos[1] - y).abs() <= KEYFRAME_RADIUS {
+            return Some(Element::Keyframe(track, i))
+        }
+    }
+    Some(Element::Track(track))
+}
+
+/// Determine and return the new state from the previous
+/// state and the mouse position.
+fn get_new_state(is_over_elem: Option<Element>,
+                 prev: State,
+                 mouse: Mouse) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::MouseButton::{Left, Right};
+    use self::State::{Normal, Highlighted, Clicked};
+    match (is_over_elem, prev, mouse.left, mouse.right) {
+        (Some(_),    Normal,                  Down, _)    => Normal,
+        (Some(_),    Normal,                  _,    Down) => Normal,
+        (Some(elem), _,                       Up,   Up)   => Highlighted(elem),
+        (Some(elem), Highlighted(_),          Down, Up)   => Clicked(elem, Left, mouse.pos),
+        (Some(elem), Highlighted(_),          Up,   Down) => Clicked(elem, Right, mouse.pos),
+        (_,          Clicked(elem, Left, _),  Down, _)    => Clicked(elem, Left, mouse.pos),
+        (_,          Clicked(elem, Right, _), _,    Down) => Clicked(elem, Right, mouse.pos),
+        _                                                 => Normal,
+    }
+}
+
+/// Return the interval between the ruler's ticks: the smallest 1, 2 or 5 times
+/// a power of ten that leaves at least `MIN_TICK_SPACING` pixels between ticks.
+fn tick_interval(px_per_sec: f64) -> f64 {
+    let min = MIN_TICK_SPACING / px_per_sec;
+    let magnitude = 10.0f64.powf(min.log10().floor());
+    for &mult in [1.0, 2.0, 5.0].iter() {
+        if magnitude * mult >= min { return magnitude * mult }
+    }
+    magnitude * 10.0
+}
+
+/// Format the time of a tick with as many decimal places as the interval between ticks
+/// needs, so that the labels neither show rounding errors nor drop digits.
+fn format_tick(secs: f64, interval: f64) -> String {
+    match (-interval.log10() - 1e-6).ceil() {
+        d if d <= 0.0 => format!("{:.0}", secs),
+        d if d <= 1.0 => format!("{:.1}", secs),
+        d if d <= 2.0 => format!("{:.2}", secs),
+        _ => format!("{:.3}", secs),
+    }
+}
+
+/// Draw a keyframe as a diamond.
+fn draw_keyframe(context: &Context, graphics: &mut Gl, center: Point, color: Color) {
+    let r = KEYFRAME_RADIUS;
+    let Color(col) = color;
+    graphics::Polygon::new(col).draw(
+        &[[center[0], center[1] - r], [center[0] + r, center[1]],
+          [center[0], center[1] + r], [center[0] - r, center[1]]],
+        context, graphics
+    );
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct TimelineContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    tracks: &'a mut Vec<Track>,
+    view: &'a mut View,
+    length: f64,
+    track_h: f64,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<|Event|:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+}
+
+impl<'a> TimelineContext<'a> {
+    /// The height of each track.
+    #[inline]
+    pub fn track_height(self, height: f64) -> TimelineContext<'a> {
+        TimelineContext { track_h: height, ..self }
+    }
+    #[inline]
+    pub fn label_color(self, color: Color) -> TimelineContext<'a> {
+        TimelineContext { maybe_label_color: Some(color), ..self }
+    }
+    #[inline]
+    pub fn label_font_size(self, size: u32) -> TimelineContext<'a> {
+        TimelineContext { maybe_label_font_size: Some(size), ..self }
+    }
+    /// Call the callback (if there is one) with the given event.
+    fn event(&mut self, event: Event) {
+        match self.maybe_callback {
+            Some(ref mut callback) => (*callback)(event),
+            None => (),
+        }
+    }
+}
+
+pub trait TimelineBuilder<'a> {
+    /// A timeline builder method to be implemented by the UiContext. `length` is the
+    /// duration of the whole timeline in seconds and `view` the part of it that is visible.
+    fn timeline(&'a mut self, ui_id: UIID,
+                tracks: &'a mut Vec<Track>,
+                view: &'a mut View,
+                length: f64) -> TimelineContext<'a>;
+}
+
+impl<'a> TimelineBuilder<'a> for UiContext {
+    fn timeline(&'a mut self, ui_id: UIID,
+                tracks: &'a mut Vec<Track>,
+                view: &'a mut View,
+                length: f64) -> TimelineContext<'a> {
+        let pos = self.flow_pos();
+        TimelineContext {
+            uic: self,
+            ui_id: ui_id,
+            tracks: tracks,
+            view: view,
+            length: length,
+            track_h: 24.0,
+            pos: pos,
+            dim: [512.0, 128.0],
+            maybe_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+        }
+    }
+}
+
+impl_callable!(TimelineContext, |Event|:'a);
+impl_colorable!(TimelineContext);
+impl_frameable!(TimelineContext);
+impl_hoverable!(TimelineContext);
+impl_positionable!(TimelineContext);
+impl_shapeable!(TimelineContext);
+
+impl<'a> ::draw::Drawable for TimelineContext<'a> {
+    /// Draw the timeline. Dragging the ruler pans the view and right-dragging it zooms
+    /// around the mouse. Clicking a track adds a keyframe, dragging a keyframe moves it
+    /// and right-clicking one deletes it. The `callback` is called with each Event.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        // A timeline of no length would leave no time to divide the area between.
+        let length = self.length.max(MIN_LENGTH);
+        let (pos, dim, track_h) = (self.pos, self.dim, self.track_h);
+        let (area_x, area_w) = (pos[0] + HEADER_WIDTH, dim[0] - HEADER_WIDTH);
+        let min_duration = length / 1000.0;
+
+        // Keep the view within the timeline.
+        let duration = clamp(self.view.duration, min_duration, length);
+        *self.view = View { start: clamp(self.view.start, 0.0, length - duration), duration: duration };
+
+        let is_over_elem = {
+            let view = *self.view;
+            is_over(pos, mouse.pos, dim, self.tracks, track_h,
+                    |t| area_x + (t - view.start) / view.duration * area_w)
+        };
+        let mut new_state = get_new_state(is_over_elem, state, mouse);
+
+        // Edit the view and keyframes.
+        let view = *self.view;
+        let time_at = |x: f64| clamp(view.start + (x - area_x) / area_w * view.duration, 0.0, length);
+        match (state, new_state) {
+            (State::Clicked(Element::Ruler, MouseButton::Left, last),
+             State::Clicked(_, _, now)) => {
+                let start = view.start - (now[0] - last[0]) / area_w * view.duration;
+                self.view.start = clamp(start, 0.0, length - view.duration);
+            },
+            (State::Clicked(Element::Ruler, MouseButton::Right, last),
+             State::Clicked(_, _, now)) => {
+                // Zoom in when dragged right, keeping the time under the grab point still.
+                let anchor = time_at(last[0]);
+                let scale = 2.0f64.powf(-(now[0] - last[0]) / ZOOM_SENSITIVITY);
+                let duration = clamp(view.duration * scale, min_duration, length);
+                let start = anchor - (anchor - view.start) * duration / view.duration;
+                *self.view = View { start: clamp(start, 0.0, length - duration), duration: duration };
+            },
+            (State::Clicked(Element::Keyframe(t, k), MouseButton::Left, _),
+             State::Clicked(_, _, now)) => {
+                let time = time_at(now[0]);
+                if time != self.tracks[t].keyframes[k] {
+                    self.tracks[t].keyframes[k] = time;
+                    self.event(Event::Moved(t, k, time));
+                }
+            },
+            (State::Clicked(Element::Keyframe(t, k), MouseButton::Right, _),
+             State::Highlighted(Element::Keyframe(h_t, h_k))) if t == h_t && k == h_k => {
+                self.tracks[t].keyframes.remove(k);
+                self.event(Event::Deleted(t, k));
+                new_state = State::Highlighted(Element::Track(t));
+            },
+            (State::Clicked(Element::Track(t), MouseButton::Left, _),
+             State::Highlighted(Element::Track(h_t))) if t == h_t => {
+                let time = time_at(mouse.pos[0]);
+                self.tracks[t].keyframes.push(time);
+                let k = self.tracks[t].keyframes.len() - 1;
+                self.event(Event::Added(t, k, time));
+                new_state = State::Highlighted(Element::Keyframe(t, k));
+            },
+            _ => (),
+        }
+
+        // Draw.
+        let (win_w, win_h) = (self.uic.win_w, self.uic.win_h);
+        let context = Context::abs(win_w, win_h);
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let frame_color = self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, frame_color)),
+            false => None,
+        };
+        let t_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+        let t_size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_small);
+        rectangle::draw(win_w, win_h, graphics, rectangle::State::Normal, pos, dim, maybe_frame, color);
+        let ruler_state = match new_state {
+            State::Highlighted(Element::Ruler) => rectangle::State::Highlighted,
+            State::Clicked(Element::Ruler, _, _) => rectangle::State::Clicked,
+            _ => rectangle::State::Normal,
+        };
+        rectangle::draw(win_w, win_h, graphics, ruler_state, [area_x, pos[1]],
+                        [area_w, RULER_HEIGHT], maybe_frame, color);
+
+        // Track names and separators.
+        let Color(line_col) = frame_color;
+        let line = graphics::Line::new(line_col, 0.5);
+        for (i, track) in self.tracks.iter().enumerate() {
+            let y = pos[1] + RULER_HEIGHT + track_h * i as f64;
+            if y >= pos[1] + dim[1] { break }
+            let size = label::shrink_to_fit(self.uic, track.name.as_slice(), t_size, Some(8),
+                                            HEADER_WIDTH - 8.0);
+            let text_y = y + (track_h - size as f64) / 2.0;
+            self.uic.draw_text(graphics, [pos[0] + 4.0, text_y], size, t_color, track.name.as_slice());
+            line.draw([pos[0], y + track_h, pos[0] + dim[0], y + track_h], &context, graphics);
+        }
+
+        // The ruler's ticks and the keyframes are clipped to the time axis.
+        self.uic.push_clip([area_x, pos[1]], [area_w, dim[1]]);
+        let px_per_sec = area_w / view.duration;
+        let interval = tick_interval(px_per_sec);
+        // Each tick is found from its index rather than by accumulating the interval, which
+        // would accumulate its rounding error too.
+        let mut n = (view.start / interval).floor() as i64;
+        while n as f64 * interval <= view.start + view.duration {
+            let tick = n as f64 * interval;
+            let x = area_x + (tick - view.start) * px_per_sec;
+            line.draw([x, pos[1] + RULER_HEIGHT * 0.5, x, pos[1] + RULER_HEIGHT], &context, graphics);
+            self.uic.draw_text(graphics, [x + 2.0, pos[1] + 1.0], t_size * 3 / 4,
+                               t_color, format_tick(tick, interval).as_slice());
+            n += 1;
+        }
+        for (t, track) in self.tracks.iter().enumerate() {
+            let y = pos[1] + RULER_HEIGHT + track_h * (t as f64 + 0.5);
+            for (k, &time) in track.keyframes.iter().enumerate() {
+                let key_color = match new_state {
+                    State::Highlighted(Element::Keyframe(h_t, h_k)) if h_t == t && h_k == k =>
+                        frame_color.highlighted(),
+                    State::Clicked(Element::Keyframe(c_t, c_k), _, _) if c_t == t && c_k == k =>
+                        frame_color.clicked(),
+                    _ => frame_color,
+                };
+                draw_keyframe(&context, graphics, [area_x + (time - view.start) * px_per_sec, y], key_color);
+            }
+        }
+        self.uic.pop_clip();
+
+        set_state(self.uic, self.ui_id, new_state, pos, dim);
+
+    }
+}
@@ -1,8 +1,13 @@
 
+use badge;
 use color::Color;
 use dimensions::Dimensions;
+use focus;
+use input::keyboard::Key;
+use label;
 use mouse::Mouse;
 use opengl_graphics::Gl;
+use padding;
 use point::Point;
 use rectangle;
 use ui_context::{
@@ -53,6 +58,8 @@ pub struct ToggleContext<'a> {
     ui_id: UIID,
     pos: Point,
     dim: Dimensions,
+    maybe_badge: Option<u32>,
+    maybe_badge_color: Option<Color>,
     maybe_callback: Option<|bool|:'a>,
     maybe_color: Option<Color>,
     maybe_frame: Option<f64>,
@@ -60,7 +67,21 @@ pub struct ToggleContext<'a> {
     maybe_label: Option<&'a str>,
     maybe_label_color: Option<Color>,
     maybe_label_font_size: Option<u32>,
+    maybe_label_min_font_size: Option<u32>,
+    maybe_padding: Option<f64>,
+    maybe_margin: Option<f64>,
     value: bool,
+    is_enabled: bool,
+    is_focusable: bool,
+}
+
+impl<'a> ToggleContext<'a> {
+    /// Allow the toggle to take keyboard focus when pressed or tabbed to. While focused,
+    /// the toggle shows a focus ring and Enter or Space toggles it.
+    #[inline]
+    pub fn focusable(self, focusable: bool) -> ToggleContext<'a> {
+        ToggleContext { is_focusable: focusable, ..self }
+    }
 }
 
 pub trait ToggleBuilder<'a> {
@@ -72,11 +93,14 @@ impl<'a> ToggleBuilder<'a> for UiContext {
 
     /// Create a toggle context to be built upon.
     fn toggle(&'a mut self, ui_id: UIID, value: bool) -> ToggleContext<'a> {
+        let pos = self.flow_pos();
         ToggleContext {
             uic: self,
             ui_id: ui_id,
-            pos: [0.0, 0.0],
+            pos: pos,
             dim: [64.0, 64.0],
+            maybe_badge: None,
+            maybe_badge_color: None,
             maybe_callback: None,
             maybe_color: None,
             maybe_frame: None,
@@ -84,43 +108,72 @@ impl<'a> ToggleBuilder<'a> for UiContext {
             maybe_label: None,
             maybe_label_color: None,
             maybe_label_font_size: None,
+            maybe_label_min_font_size: None,
+            maybe_padding: None,
+            maybe_margin: None,
             value: value,
+            is_enabled: true,
+            is_focusable: false,
         }
     }
 
 }
 
+impl_auto_sizable!(ToggleContext);
+impl_badgeable!(ToggleContext);
 impl_callable!(ToggleContext, |bool|:'a);
 impl_colorable!(ToggleContext);
+impl_enableable!(ToggleContext);
 impl_frameable!(ToggleContext);
+impl_hoverable!(ToggleContext);
 impl_labelable!(ToggleContext);
+impl_paddable!(ToggleContext);
 impl_positionable!(ToggleContext);
 impl_shapeable!(ToggleContext);
 
 impl<'a> ::draw::Drawable for ToggleContext<'a> {
     fn draw(&mut self, graphics: &mut Gl) {
-        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        // Draw within the margin, while placing the area that includes it.
+        let (outer_pos, outer_dim) = (self.pos, self.dim);
+        let (pos, dim) = padding::inset(self.pos, self.dim, self.maybe_margin.unwrap_or(0.0));
+        self.pos = pos;
+        self.dim = dim;
+        let style = self.uic.theme.style(self.uic.theme.maybe_toggle);
+        let color = self.maybe_color.unwrap_or(style.shape_color);
         let color = match self.value {
             true => color,
             false => color * Color::new(0.1, 0.1, 0.1, 1.0)
         };
+        let color = if self.is_enabled { color } else { color.disabled() };
         let state = *get_state(self.uic, self.ui_id);
-        let mouse = self.uic.get_mouse_state();
-        let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
-        let new_state = get_new_state(is_over, state, mouse);
-        let rect_state = new_state.as_rectangle_state();
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let is_over = self.is_enabled && rectangle::is_over(self.pos, mouse.pos, self.dim);
+        let new_state = match self.is_enabled {
+            true => get_new_state(is_over, state, mouse),
+            false => State::Normal,
+        };
+        // The color fades between states, so the rectangle itself is drawn as Normal.
+        let transition = self.uic.transition(self.ui_id, new_state.as_rectangle_state());
+        let color = transition.color(color);
+        let rect_state = rectangle::State::Normal;
+        let is_focusable = self.is_enabled && self.is_focusable;
+        let is_focused = self.uic.update_focus(self.ui_id, is_focusable,
+                                               new_state == State::Clicked, is_over);
+        let is_key_activated = is_focused && self.uic.get_pressed_keys().iter()
+            .any(|key| match *key { Key::Return | Key::Space => true, _ => false });
         match self.maybe_callback {
             Some(ref mut callback) => {
                 match (is_over, state, new_state) {
                     (true, State::Clicked, State::Highlighted) =>
                         (*callback)(match self.value { true => false, false => true }),
+                    _ if is_key_activated => (*callback)(!self.value),
                     _ => (),
                 }
             }, None => (),
         }
-        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let frame_w = self.maybe_frame.unwrap_or(style.frame_width);
         let maybe_frame = match frame_w > 0.0 {
-            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(style.frame_color))),
             false => None,
         };
         match self.maybe_label {
@@ -131,8 +184,14 @@ impl<'a> ::draw::Drawable for ToggleContext<'a> {
                 )
             },
             Some(text) => {
-                let text_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+                let text_color = self.maybe_label_color.unwrap_or(style.label_color);
+                let text_color = if self.is_enabled { text_color } else { text_color.disabled() };
                 let size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_medium);
+                let pad = frame_w + self.maybe_padding.unwrap_or(0.0);
+                let size = label::shrink_to_fit(self.uic, text, size, self.maybe_label_min_font_size,
+                                                self.dim[0] - pad * 2.0);
+                let text_y = self.pos[1] + (self.dim[1] - size as f64) / 2.0;
+                self.uic.set_baseline(self.ui_id, label::baseline(text_y, size));
                 rectangle::draw_with_centered_label(
                     self.uic.win_w, self.uic.win_h, graphics, self.uic, rect_state,
                     self.pos, self.dim, maybe_frame, color,
@@ -140,8 +199,15 @@ impl<'a> ::draw::Drawable for ToggleContext<'a> {
                 )
             },
         }
+        if let Some(count) = self.maybe_badge {
+            badge::draw(self.uic, graphics, self.pos, self.dim, count, self.maybe_badge_color);
+        }
+        if is_focused {
+            let ring_color = self.uic.theme.link_color();
+            focus::draw_ring(self.uic.win_w, self.uic.win_h, graphics, self.pos, self.dim, ring_color);
+        }
 
-        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+        set_state(self.uic, self.ui_id, new_state, outer_pos, outer_dim);
 
     }
 }
@@ -0,0 +1,113 @@
+use std::num::Float;
+use dimensions::Dimensions;
+use point::Point;
+use utils::clamp;
+
+/// The furthest a View may be zoomed in.
+pub static MAX_ZOOM: f64 = 16.0;
+
+/// Identifies a finger for as long as it touches the screen.
+pub type TouchId = i64;
+
+/// The stage of a touch.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Phase {
+    /// The finger touched the screen during the current frame.
+    Start,
+    /// The finger is on the screen, and may have moved.
+    Move,
+    /// The finger was lifted during the current frame.
+    End,
+    /// The touch was interrupted (i.e. by the system) during the current frame.
+    Cancel,
+}
+
+/// The state of a single finger on a touch screen.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct Touch {
+    pub id: TouchId,
+    pub phase: Phase,
+    pub pos: Point,
+}
+
+impl Touch {
+    /// Constructor for a Touch.
+    pub fn new(id: TouchId, phase: Phase, pos: Point) -> Touch {
+        Touch { id: id, phase: phase, pos: pos }
+    }
+    /// Whether or not the finger is still on the screen.
+    pub fn is_active(&self) -> bool {
+        match self.phase {
+            Phase::Start | Phase::Move => true,
+            Phase::End | Phase::Cancel => false,
+        }
+    }
+}
+
+/// A two finger gesture made between the previous frame and the current one.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct Gesture {
+    /// The point midway between the two fingers.
+    pub center: Point,
+    /// The distance moved by the center since the previous frame.
+    pub pan: Point,
+    /// The distance between the fingers relative to their distance during the previous frame.
+    pub scale: f64,
+}
+
+/// Recognise a two finger gesture from the first two fingers that are on the screen during
+/// both the previous and current frames.
+pub fn gesture(prev: &[Touch], current: &[Touch]) -> Option<Gesture> {
+    let pairs: Vec<(Point, Point)> = current.iter().filter(|touch| touch.is_active())
+        .filter_map(|touch| {
+            prev.iter().find(|p| p.id == touch.id).map(|p| (p.pos, touch.pos))
+        })
+        .take(2)
+        .collect();
+    if pairs.len() < 2u { return None }
+    let ((a_prev, a), (b_prev, b)) = (pairs[0], pairs[1]);
+    let prev_center = [(a_prev[0] + b_prev[0]) / 2.0, (a_prev[1] + b_prev[1]) / 2.0];
+    let center = [(a[0] + b[0]) / 2.0, (a[1] + b[1]) / 2.0];
+    let prev_dist = (b_prev[0] - a_prev[0]).hypot(b_prev[1] - a_prev[1]);
+    let dist = (b[0] - a[0]).hypot(b[1] - a[1]);
+    Some(Gesture {
+        center: center,
+        pan: [center[0] - prev_center[0], center[1] - prev_center[1]],
+        scale: if prev_dist > 0.0 { dist / prev_dist } else { 1.0 },
+    })
+}
+
+/// The visible region of a widget's value range, zoomed by pinching and panned by dragging
+/// two fingers. Positions are fractions of the whole range as shown on screen, measured
+/// from the left and top.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct View {
+    pub zoom: f64,
+    pub center: Point,
+}
+
+impl View {
+
+    /// Constructor for a View showing the whole range.
+    pub fn new() -> View {
+        View { zoom: 1.0, center: [0.5, 0.5] }
+    }
+
+    /// Zoom and pan the view by a gesture made over a widget with the given dimensions.
+    pub fn apply(&mut self, gesture: Gesture, dim: Dimensions) {
+        self.zoom = clamp(self.zoom * gesture.scale, 1.0, MAX_ZOOM);
+        let half = 0.5 / self.zoom;
+        for i in range(0u, 2u) {
+            let center = self.center[i] - gesture.pan[i] / dim[i] / self.zoom;
+            self.center[i] = clamp(center, half, 1.0 - half);
+        }
+    }
+
+    /// Return the visible fractions `(start, end)` of the whole range along the x (0) or
+    /// y (1) axis.
+    pub fn visible(&self, axis: uint) -> (f64, f64) {
+        let half = 0.5 / self.zoom;
+        (self.center[axis] - half, self.center[axis] + half)
+    }
+
+}
@@ -1,34 +1,95 @@
+use std::collections::HashMap;
+use std::num::Float;
 use Color;
+use clipboard::{Clipboard, LocalClipboard};
+use color_blind;
+use color_blind::Deficiency;
+use controller;
+use controller::ControllerButton;
 use dimensions::Dimensions;
+use drag::Drag;
+use draw::ClipStack;
+use graphics;
 use opengl_graphics::glyph_cache::{
     GlyphCache,
     Character,
 };
 use opengl_graphics::Gl;
+use keyboard::{Composition, KeyRepeat, Modifiers, Shortcut, ShortcutId};
+use overlay;
 use label::FontSize;
+use layout::{ConstraintLayout, RectId};
 use mouse::{
     ButtonState,
+    Cursor,
     Mouse,
 };
 use input;
 use event::{
     GenericEvent,
     MouseCursorEvent,
+    MouseScrollEvent,
     PressEvent,
     ReleaseEvent,
     RenderEvent,
     TextEvent,
 };
 use point::Point;
+use rectangle::Transition;
+use text_box::EditHistory;
 use theme::Theme;
+use time::precise_time_s;
+use touch;
+use touch::{Gesture, Phase, Touch, TouchId};
+use utils::clamp;
 use widget;
 use widget::Widget;
 
+/// The font size of the text drawn by `UiContext::debug_layout`.
+static DEBUG_FONT_SIZE: FontSize = 12;
+
 /// User Interface Identifier. Each unique `widget::draw` call
 /// should pass it's own unique UIID so that UiContext can keep
 /// track of it's state.
 pub type UIID = u64;
 
+/// A likely layout bug amongst the widgets placed during the current frame.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum LayoutWarning {
+    /// The widget's rect extends beyond the window.
+    OutOfWindow(UIID),
+    /// The rects of the two widgets overlap.
+    Overlap(UIID, UIID),
+}
+
+/// Input injected into the UiContext to be received during the next frame.
+enum Injected {
+    MouseMove(Point),
+    Press(input::Button),
+    Release(input::Button),
+    Scroll(f64, f64),
+    Text(String),
+}
+
+/// The direction in which a flow lays out its widgets.
+#[deriving(Show, PartialEq, Clone, Copy)]
+enum FlowDirection {
+    Column,
+    Row,
+}
+
+/// A flow in which consecutively placed widgets are laid out (see `UiContext::begin_column`).
+#[deriving(Show, Clone, Copy)]
+struct Flow {
+    direction: FlowDirection,
+    spacing: f64,
+    start: Point,
+    /// The position at which the next widget will be placed.
+    next: Point,
+    /// The bottom right corner of the area covered by the flow's widgets so far.
+    extent: Point,
+}
+
 /// UiContext retains the state of all widgets and
 /// data relevant to the draw_widget functions.
 pub struct UiContext {
@@ -38,14 +99,136 @@ pub struct UiContext {
     pub keys_just_pressed: Vec<input::keyboard::Key>,
     pub keys_just_released: Vec<input::keyboard::Key>,
     pub text_just_entered: Vec<String>,
+    /// The modifier keys currently held.
+    pub modifiers: Modifiers,
+    /// Repeats the editing keys while they are held.
+    key_repeat: KeyRepeat,
+    /// The text being composed by an input method (if any).
+    maybe_preedit: Option<String>,
+    /// The application's registered shortcuts.
+    shortcuts: Vec<(Shortcut, ShortcutId)>,
+    /// Whether or not a TextBox captured the keyboard during the current and previous frames.
+    is_text_captured: bool,
+    was_text_captured: bool,
     glyph_cache: GlyphCache,
     prev_event_was_render: bool,
     /// Window width.
     pub win_w: f64,
     /// Window height.
     pub win_h: f64,
+    /// When set, `draw_debug` redraws the frame as seen with the given color vision deficiency.
+    pub color_blind_simulation: Option<Deficiency>,
+    color_blind_simulator: color_blind::Simulator,
+    /// When true, `draw_debug` outlines the widgets involved in any `LayoutWarning`s.
+    pub outline_layout_warnings: bool,
+    /// When true, `draw_debug` outlines every widget placed during the frame with its UIID
+    /// and position.
+    is_debugging_layout: bool,
     /// The UIID of the widget drawn previously.
     prev_uiid: u64,
+    /// The UIID of the widget with keyboard focus (if any).
+    maybe_focused: Option<UIID>,
+    /// The focusable widgets in the order they were drawn during the current and previous
+    /// frames. Tab and Shift-Tab move focus through the previous frame's order.
+    focus_order: Vec<UIID>,
+    prev_focus_order: Vec<UIID>,
+    /// The direction in which the controller's stick was last pushed (if any).
+    maybe_stick_direction: Option<ControllerButton>,
+    /// The time (in seconds) and position of the latest left button press that
+    /// wasn't the second press of a double click.
+    maybe_last_click: Option<(f64, Point)>,
+    /// The distance the mouse must move with the left button held before the press becomes
+    /// a drag, the position of the latest press and whether the mouse has since moved
+    /// further than the threshold.
+    drag_threshold: f64,
+    left_press_pos: Point,
+    is_past_drag_threshold: bool,
+    /// The time (in seconds) at which the latest render event occurred.
+    frame_time: f64,
+    /// The time (in seconds) between the two latest render events.
+    frame_dt: f64,
+    /// Input injected by widgets (i.e. an on-screen keyboard) or tests, to be
+    /// received as regular input during the next frame.
+    injected: Vec<Injected>,
+    /// Areas that may be clicked without a captured TextBox losing capture,
+    /// registered during the current and previous frames.
+    focus_safe_areas: Vec<(Point, Dimensions)>,
+    prev_focus_safe_areas: Vec<(Point, Dimensions)>,
+    /// The widgets placed since the latest render event.
+    placed_this_frame: Vec<UIID>,
+    /// The widgets under the mouse at the current and previous render events.
+    hovered: Vec<UIID>,
+    prev_hovered: Vec<UIID>,
+    /// The topmost widget under the mouse at the latest render event (if any), which
+    /// claims the mouse from the widgets that it overlaps.
+    maybe_mouse_claimant: Option<UIID>,
+    /// The rectangles to which drawing is restricted, innermost last.
+    clip_stack: ClipStack,
+    /// The containers whose contents are currently being drawn, innermost last.
+    parent_stack: Vec<UIID>,
+    /// The container of each widget placed within one during the current and previous
+    /// frames (see `begin_children`).
+    parents: HashMap<UIID, UIID>,
+    prev_parents: HashMap<UIID, UIID>,
+    /// The widgets that never claim the mouse, registered during the current frame.
+    transparent_to_mouse: Vec<UIID>,
+    /// Whether or not the widgets drawn from now on ignore all input.
+    is_input_locked: bool,
+    /// The mouse cursor requested during the current frame.
+    cursor: Cursor,
+    /// The UIIDs of the floating windows ordered from bottom to top.
+    window_stack: Vec<UIID>,
+    /// Text belonging to widgets whose State can't hold it, i.e. a DropDownList's filter.
+    text_buffers: HashMap<UIID, String>,
+    /// The edits that may be undone for each TextBox.
+    edit_histories: HashMap<UIID, EditHistory>,
+    /// The time (in seconds) over which a widget's color fades between states, and the
+    /// progress of the fade for each widget.
+    transition_duration: f64,
+    transitions: HashMap<UIID, Transition>,
+    /// The clipboard used by widgets for copying and pasting text.
+    clipboard: Box<Clipboard + 'static>,
+    /// The y position of the text baseline of each widget that has drawn a label or value.
+    baselines: HashMap<UIID, f64>,
+    /// The shapes to be drawn during the overlay pass, each with the depth of its popup.
+    overlay: Vec<(i32, Vec<overlay::Primitive>)>,
+    /// The areas covered by popups during the current and previous frames, within
+    /// which the mouse is hidden from all widgets but the popup's own and those of popups
+    /// at a greater depth.
+    overlay_areas: Vec<(UIID, i32, Point, Dimensions)>,
+    prev_overlay_areas: Vec<(UIID, i32, Point, Dimensions)>,
+    /// The rects and constraints solved against the window's size at each render event,
+    /// and whether or not the constraints all held.
+    constraint_layout: ConstraintLayout,
+    is_layout_satisfied: bool,
+    /// The flows begun and not yet ended, innermost last.
+    flows: Vec<Flow>,
+    /// The window positions of the containers begun and not yet ended, innermost last.
+    origins: Vec<Point>,
+    /// The drag between widgets in progress (if any).
+    maybe_drag: Option<Drag>,
+    /// The widget that captured the mouse when it began a drag (if any).
+    maybe_mouse_captor: Option<UIID>,
+    /// The fingers on the touch screen, along with those lifted during the current frame.
+    touches: Vec<Touch>,
+    /// The finger that acts as the mouse's left button (if any).
+    maybe_primary_touch: Option<TouchId>,
+    /// The touches as they were during the previous frame.
+    prev_touches: Vec<Touch>,
+    /// The two finger gesture made since the previous frame (if any).
+    maybe_gesture: Option<Gesture>,
+}
+
+/// Return the state of the given mouse button, or None for the buttons that aren't tracked.
+fn mouse_button_state(mouse: &mut Mouse,
+                      button: input::MouseButton) -> Option<&mut ButtonState> {
+    use input::MouseButton::{Left, Middle, Right};
+    match button {
+        Left => Some(&mut mouse.left),
+        Middle => Some(&mut mouse.middle),
+        Right => Some(&mut mouse.right),
+        _ => None,
+    }
 }
 
 impl UiContext {
@@ -59,78 +242,668 @@ impl UiContext {
             keys_just_pressed: Vec::with_capacity(10u),
             keys_just_released: Vec::with_capacity(10u),
             text_just_entered: Vec::with_capacity(10u),
+            modifiers: Modifiers::new(),
+            key_repeat: KeyRepeat::new(),
+            maybe_preedit: None,
+            shortcuts: Vec::new(),
+            is_text_captured: false,
+            was_text_captured: false,
             glyph_cache: glyph_cache,
             prev_event_was_render: false,
             win_w: 0f64,
             win_h: 0f64,
+            color_blind_simulation: None,
+            color_blind_simulator: color_blind::Simulator::new(),
+            outline_layout_warnings: false,
+            is_debugging_layout: false,
             prev_uiid: 0u64,
+            maybe_focused: None,
+            focus_order: Vec::new(),
+            prev_focus_order: Vec::new(),
+            maybe_stick_direction: None,
+            maybe_last_click: None,
+            drag_threshold: ::mouse::DEFAULT_DRAG_THRESHOLD,
+            left_press_pos: [0.0, 0.0],
+            is_past_drag_threshold: false,
+            frame_time: precise_time_s(),
+            frame_dt: 0.0,
+            injected: Vec::new(),
+            focus_safe_areas: Vec::new(),
+            prev_focus_safe_areas: Vec::new(),
+            placed_this_frame: Vec::new(),
+            hovered: Vec::new(),
+            prev_hovered: Vec::new(),
+            maybe_mouse_claimant: None,
+            clip_stack: ClipStack::new(),
+            parent_stack: Vec::new(),
+            parents: HashMap::new(),
+            prev_parents: HashMap::new(),
+            transparent_to_mouse: Vec::new(),
+            is_input_locked: false,
+            cursor: Cursor::Default,
+            window_stack: Vec::new(),
+            text_buffers: HashMap::new(),
+            edit_histories: HashMap::new(),
+            transition_duration: ::rectangle::DEFAULT_TRANSITION_SECS,
+            transitions: HashMap::new(),
+            clipboard: box LocalClipboard::new(),
+            baselines: HashMap::new(),
+            overlay: Vec::new(),
+            overlay_areas: Vec::new(),
+            prev_overlay_areas: Vec::new(),
+            constraint_layout: ConstraintLayout::new(0.0, 0.0),
+            is_layout_satisfied: true,
+            flows: Vec::new(),
+            origins: Vec::new(),
+            maybe_drag: None,
+            maybe_mouse_captor: None,
+            touches: Vec::new(),
+            maybe_primary_touch: None,
+            prev_touches: Vec::new(),
+            maybe_gesture: None,
         }
     }
 
     /// Handle game events and update the state.
     pub fn handle_event<E: GenericEvent>(&mut self, event: &E) {
+        self.prepare_for_input();
+        event.render(|args| self.render(args.width as f64, args.height as f64));
+        event.mouse_cursor(|x, y| self.move_mouse([x, y]));
+        event.mouse_scroll(|dx, dy| {
+            self.mouse.scroll = [self.mouse.scroll[0] + dx, self.mouse.scroll[1] + dy];
+        });
+        event.press(|button| self.press(button));
+        event.release(|button| self.release(button));
+        event.text(|text| {
+            self.text_just_entered.push(text.to_string())
+        });
+    }
+
+    /// Flush the previous frame's input if this is the first event since it was rendered.
+    fn prepare_for_input(&mut self) {
         if self.prev_event_was_render {
             self.flush_input();
             self.prev_event_was_render = false;
         }
-        event.render(|args| {
-            self.win_w = args.width as f64;
-            self.win_h = args.height as f64;
-            self.prev_event_was_render = true;
-        });
-        event.mouse_cursor(|x, y| {
-            self.mouse.pos = [x, y];
-        });
-        event.press(|button_type| {
-            use input::Button;
-            use input::MouseButton::Left;
-
-            match button_type {
-                Button::Mouse(button) => {
-                    *match button {
-                        Left => &mut self.mouse.left,
-                        _/*input::mouse::Right*/ => &mut self.mouse.right,
-                        //Middle => &mut self.mouse.middle,
-                    } = ButtonState::Down;
-                },
-                Button::Keyboard(key) => self.keys_just_pressed.push(key),
+    }
+
+    /// Begin a new frame for a window of the given size.
+    fn render(&mut self, win_w: f64, win_h: f64) {
+        self.win_w = win_w;
+        self.win_h = win_h;
+        self.prev_event_was_render = true;
+        self.is_input_locked = false;
+        self.constraint_layout.resize_window(win_w, win_h);
+        self.is_layout_satisfied = self.constraint_layout.solve();
+        self.receive_injected();
+        let now = precise_time_s();
+        self.frame_dt = now - self.frame_time;
+        self.frame_time = now;
+        let repeats = self.key_repeat.repeats(now);
+        self.keys_just_pressed.extend(repeats.into_iter());
+        self.prev_focus_safe_areas = ::std::mem::replace(&mut self.focus_safe_areas, Vec::new());
+        self.prev_overlay_areas = ::std::mem::replace(&mut self.overlay_areas, Vec::new());
+        // Discard any popups that weren't drawn, i.e. if `draw_overlay` wasn't called.
+        self.overlay.clear();
+        self.prev_focus_order = ::std::mem::replace(&mut self.focus_order, Vec::new());
+        self.was_text_captured = ::std::mem::replace(&mut self.is_text_captured, false);
+        self.maybe_gesture = touch::gesture(self.prev_touches.as_slice(),
+                                            self.touches.as_slice());
+        self.prev_touches = self.touches.clone();
+        self.cycle_focus_on_tab();
+        let hovered: Vec<UIID> = self.placed_this_frame.iter().map(|&ui_id| ui_id)
+            .filter(|&ui_id| self.is_hovering(ui_id)).collect();
+        self.prev_hovered = ::std::mem::replace(&mut self.hovered, hovered);
+        self.maybe_mouse_claimant = self.topmost_hovered();
+        self.prev_parents = ::std::mem::replace(&mut self.parents, HashMap::new());
+        self.parent_stack.clear();
+        self.clip_stack.clear();
+        self.transparent_to_mouse.clear();
+        // Forget the windows that weren't drawn, i.e. those that have been closed.
+        let placed = &self.placed_this_frame;
+        self.window_stack.retain(|id| placed.contains(id));
+        self.placed_this_frame.clear();
+        self.flows.clear();
+        self.origins.clear();
+        self.cursor = Cursor::Default;
+    }
+
+    /// Handle the press of a mouse button or key.
+    fn press(&mut self, button: input::Button) {
+        use input::Button;
+        use input::MouseButton::Left;
+        match button {
+            Button::Mouse(button) => {
+                if let Left = button { self.register_click() }
+                if let Some(state) = mouse_button_state(&mut self.mouse, button) {
+                    *state = ButtonState::Down;
+                }
+            },
+            Button::Keyboard(key) => {
+                self.modifiers.update(key, true);
+                if self.key_repeat.press(key, precise_time_s()) {
+                    self.keys_just_pressed.push(key);
+                }
+            },
+        }
+    }
+
+    /// Handle the release of a mouse button or key.
+    fn release(&mut self, button: input::Button) {
+        use input::Button;
+        match button {
+            Button::Mouse(button) => {
+                if let Some(state) = mouse_button_state(&mut self.mouse, button) {
+                    *state = ButtonState::Up;
+                }
+            },
+            Button::Keyboard(key) => {
+                self.modifiers.update(key, false);
+                self.key_repeat.release(key);
+                self.keys_just_released.push(key);
+            },
+        }
+    }
+
+    /// Handle a touch screen event. Touch events aren't carried by the window's events, so
+    /// the application should pass them here alongside `handle_event`. The first finger
+    /// down acts as the mouse with its left button held, so that every widget works on a
+    /// touch screen, while `get_touches` gives the raw touches.
+    pub fn handle_touch(&mut self, touch: Touch) {
+        self.prepare_for_input();
+        match self.touches.iter().position(|t| t.id == touch.id) {
+            Some(idx) => self.touches[idx] = touch,
+            None => self.touches.push(touch),
+        }
+        match (touch.phase, self.maybe_primary_touch) {
+            (Phase::Start, None) => {
+                self.maybe_primary_touch = Some(touch.id);
+                self.move_mouse(touch.pos);
+                self.register_click();
+                self.mouse.left = ButtonState::Down;
+            },
+            (Phase::Move, Some(id)) if id == touch.id => self.move_mouse(touch.pos),
+            (Phase::End, Some(id)) if id == touch.id => {
+                // Move while the touch is primary so that the touch drag threshold applies.
+                self.move_mouse(touch.pos);
+                self.maybe_primary_touch = None;
+                self.mouse.left = ButtonState::Up;
+            },
+            // Move the mouse away before releasing so that nothing is activated.
+            (Phase::Cancel, Some(id)) if id == touch.id => {
+                self.maybe_primary_touch = None;
+                self.mouse.pos = [-1.0, -1.0];
+                self.mouse.left = ButtonState::Up;
+            },
+            _ => (),
+        }
+    }
+
+    /// Handle a pen event from a tablet. Pressure and tilt aren't carried by the window's
+    /// events, so the application should pass them here whenever they change, while the
+    /// pen's position and buttons are received as the mouse's through `handle_event`.
+    pub fn handle_pen(&mut self, pressure: f64, tilt: [f64, ..2]) {
+        self.prepare_for_input();
+        self.mouse.pressure = clamp(pressure, 0.0, 1.0);
+        self.mouse.tilt = [clamp(tilt[0], -1.0, 1.0), clamp(tilt[1], -1.0, 1.0)];
+    }
+
+    /// Handle a text composition event from an input method. Composition events aren't
+    /// carried by the window's events, so the application should pass them here alongside
+    /// `handle_event`. The preedit text is shown by the captured TextBox until the
+    /// composition is committed, at which point it is entered like typed text.
+    pub fn handle_composition(&mut self, composition: Composition) {
+        self.prepare_for_input();
+        match composition {
+            Composition::Preedit(text) => self.maybe_preedit = match text.len() {
+                0u => None,
+                _ => Some(text),
+            },
+            Composition::Commit(text) => {
+                self.maybe_preedit = None;
+                self.text_just_entered.push(text);
+            },
+        }
+    }
+
+    /// Return the text being composed by an input method (if any).
+    pub fn get_preedit(&self) -> Option<String> {
+        match self.is_input_locked {
+            true => None,
+            false => self.maybe_preedit.clone(),
+        }
+    }
+
+    /// Handle a press of a controller button. The direction buttons move focus to the
+    /// nearest focusable widget in that direction (or the first, if none is focused),
+    /// Activate presses Enter for the focused widget and Cancel removes focus.
+    pub fn handle_controller(&mut self, button: ControllerButton) {
+        use input::keyboard::Key;
+        match button {
+            ControllerButton::Activate => if self.maybe_focused.is_some() {
+                self.inject_key(Key::Return)
+            },
+            ControllerButton::Cancel => self.set_focus(None),
+            direction => {
+                let centers: Vec<(UIID, Point)> = self.prev_focus_order.iter()
+                    .filter_map(|&ui_id| match self.get_placing(ui_id) {
+                        widget::Placing::Place(x, y, w, h) =>
+                            Some((ui_id, [x + w / 2.0, y + h / 2.0])),
+                        widget::Placing::NoPlace => None,
+                    })
+                    .collect();
+                let maybe_from = self.maybe_focused.and_then(|focused| {
+                    centers.iter().find(|&&(ui_id, _)| ui_id == focused).map(|&(_, center)| center)
+                });
+                let maybe_next = match maybe_from {
+                    Some(from) => controller::nearest_in_direction(from, direction,
+                                                                   centers.as_slice()),
+                    None => centers.iter().next().map(|&(ui_id, _)| ui_id),
+                };
+                if maybe_next.is_some() { self.set_focus(maybe_next) }
+            },
+        }
+    }
+
+    /// Handle the position of a controller's analogue stick, with each axis ranging from
+    /// -1.0 to 1.0 and positive `y` being down. Pushing the stick beyond
+    /// `controller::STICK_DEAD_ZONE` moves focus as the matching direction button does, once
+    /// for each push, so the stick must return towards its center (or be pushed in another
+    /// direction) to move focus again. This may be called whenever the stick moves.
+    pub fn handle_controller_stick(&mut self, x: f64, y: f64) {
+        let maybe_direction = controller::stick_direction(x, y);
+        if maybe_direction != self.maybe_stick_direction {
+            self.maybe_stick_direction = maybe_direction;
+            if let Some(direction) = maybe_direction { self.handle_controller(direction) }
+        }
+    }
+
+    /// Return the fingers on the touch screen, along with those lifted during the current frame.
+    pub fn get_touches(&self) -> &[Touch] { self.touches.as_slice() }
+
+    /// Return the two finger gesture (i.e. a pinch or pan) made since the previous frame.
+    pub fn get_gesture(&self) -> Option<Gesture> { self.maybe_gesture }
+
+    /// Check whether a press of the left button completes a double click.
+    fn register_click(&mut self) {
+        use mouse::{DOUBLE_CLICK_DISTANCE, DOUBLE_CLICK_SECS};
+        let now = precise_time_s();
+        let pos = self.mouse.pos;
+        self.left_press_pos = pos;
+        self.is_past_drag_threshold = false;
+        self.maybe_last_click = match self.maybe_last_click {
+            Some((time, last_pos)) if now - time <= DOUBLE_CLICK_SECS
+                && (pos[0] - last_pos[0]).abs() <= DOUBLE_CLICK_DISTANCE
+                && (pos[1] - last_pos[1]).abs() <= DOUBLE_CLICK_DISTANCE => {
+                self.mouse.is_double_click = true;
+                None
+            },
+            _ => Some((now, pos)),
+        };
+    }
+
+    /// Move the mouse to the given position, noting whether a press has become a drag.
+    fn move_mouse(&mut self, pos: Point) {
+        self.mouse.pos = pos;
+        if let ButtonState::Down = self.mouse.left {
+            let (dx, dy) = (pos[0] - self.left_press_pos[0], pos[1] - self.left_press_pos[1]);
+            let threshold = match self.maybe_primary_touch {
+                Some(_) => self.drag_threshold * ::mouse::TOUCH_DRAG_THRESHOLD_SCALE,
+                None => self.drag_threshold,
+            };
+            if dx * dx + dy * dy > threshold * threshold {
+                self.is_past_drag_threshold = true;
             }
-        });
-        event.release(|button_type| {
-            use input::Button;
-            use input::MouseButton::Left;
-
-            match button_type {
-                Button::Mouse(button) => {
-                    *match button {
-                        Left => &mut self.mouse.left,
-                        _/*input::mouse::Right*/ => &mut self.mouse.right,
-                        //Middle => &mut self.mouse.middle,
-                    } = ButtonState::Up;
-                },
-                Button::Keyboard(key) => self.keys_just_released.push(key),
+        }
+    }
+
+    /// Set the distance (in pixels) the mouse must move with the left button held before
+    /// the press becomes a drag. Until then, widgets treat the press as a click, so that
+    /// small jitters neither drag values nor cancel the click of a Button. The threshold is
+    /// scaled by `mouse::TOUCH_DRAG_THRESHOLD_SCALE` while a finger drives the mouse.
+    pub fn set_drag_threshold(&mut self, threshold: f64) { self.drag_threshold = threshold }
+
+    /// Return the distance the mouse must move before a press becomes a drag.
+    pub fn get_drag_threshold(&self) -> f64 { self.drag_threshold }
+
+    /// Whether or not the mouse has moved further than the drag threshold since the left
+    /// button was last pressed.
+    pub fn is_past_drag_threshold(&self) -> bool { self.is_past_drag_threshold }
+
+    /// Give keyboard focus to the given widget, or remove focus from all widgets.
+    pub fn set_focus(&mut self, maybe_ui_id: Option<UIID>) { self.maybe_focused = maybe_ui_id }
+
+    /// Return the UIID of the widget with keyboard focus (if any).
+    pub fn get_focus(&self) -> Option<UIID> { self.maybe_focused }
+
+    /// Whether or not the given widget has keyboard focus.
+    pub fn is_focused(&self, ui_id: UIID) -> bool { self.maybe_focused == Some(ui_id) }
+
+    /// Update the focus of a widget that may take keyboard focus and return whether or not
+    /// it is focused. A focusable widget joins the Tab order, takes focus when `is_pressed`
+    /// and loses it when the mouse is pressed elsewhere. A widget that is not focusable
+    /// (i.e. because it is disabled) loses focus.
+    pub fn update_focus(&mut self, ui_id: UIID, is_focusable: bool,
+                        is_pressed: bool, is_over: bool) -> bool {
+        if !is_focusable || self.is_input_locked {
+            if self.is_focused(ui_id) { self.set_focus(None) }
+            return false
+        }
+        self.register_focusable(ui_id);
+        if let ButtonState::Down = self.mouse.left {
+            if is_pressed { self.set_focus(Some(ui_id)) }
+            else if self.is_focused(ui_id) && !is_over { self.set_focus(None) }
+        }
+        self.is_focused(ui_id)
+    }
+
+    /// Add the given widget to the Tab order for the current frame. Widgets that manage
+    /// their own focus should call this each frame while they may be focused.
+    pub fn register_focusable(&mut self, ui_id: UIID) {
+        self.focus_order.push(ui_id);
+    }
+
+    /// Move focus to the next focusable widget (or the previous one while Shift is held)
+    /// for each Tab pressed during the frame. The Tab presses are consumed so that the
+    /// focused widget doesn't receive them.
+    fn cycle_focus_on_tab(&mut self) {
+        use input::keyboard::Key;
+        if self.prev_focus_order.is_empty() { return }
+        let num_tabs = self.keys_just_pressed.iter().filter(|key| **key == Key::Tab).count();
+        if num_tabs == 0u { return }
+        self.keys_just_pressed.retain(|key| *key != Key::Tab);
+        let len = self.prev_focus_order.len();
+        for _ in range(0u, num_tabs) {
+            let maybe_idx = self.maybe_focused.and_then(|id| {
+                self.prev_focus_order.iter().position(|order_id| *order_id == id)
+            });
+            let idx = match (maybe_idx, self.modifiers.shift) {
+                (None, false) => 0u,
+                (None, true) => len - 1u,
+                (Some(idx), false) => (idx + 1u) % len,
+                (Some(idx), true) => (idx + len - 1u) % len,
+            };
+            self.maybe_focused = Some(self.prev_focus_order[idx]);
+        }
+    }
+
+    /// Request the given mouse cursor for the current frame. Widgets request the cursor
+    /// for the element under the mouse while drawing, and the latest request is kept.
+    pub fn set_cursor(&mut self, cursor: Cursor) { self.cursor = cursor }
+
+    /// Return the mouse cursor requested during the current frame.
+    pub fn get_cursor(&self) -> Cursor { self.cursor }
+
+    /// Return the current mouse state. While the mouse is over a popup or captured by
+    /// a widget, it is moved outside of the window so that the other widgets ignore it.
+    pub fn get_mouse_state(&self) -> Mouse {
+        self.mouse_state_beneath_overlay(None)
+    }
+
+    /// Return the mouse state for the given widget (as given by `get_mouse_state_for`) with
+    /// its positions relative to the top left corner of the area in which the widget was
+    /// last placed. Returns None if the widget has not been placed.
+    pub fn get_local_mouse_state(&self, ui_id: UIID) -> Option<Mouse> {
+        match self.get_placing(ui_id) {
+            widget::Placing::Place(x, y, _, _) =>
+                Some(self.get_mouse_state_for(ui_id).relative_to([x, y])),
+            widget::Placing::NoPlace => None,
+        }
+    }
+
+    /// Whether or not the mouse is over the area in which the widget was last placed.
+    fn is_hovering(&self, ui_id: UIID) -> bool {
+        let mouse = self.mouse_state_beneath_overlay(Some(ui_id));
+        match self.get_placing(ui_id) {
+            widget::Placing::Place(x, y, w, h) => ::rectangle::is_over([x, y], mouse.pos, [w, h]),
+            widget::Placing::NoPlace => false,
+        }
+    }
+
+    /// Return the widget drawn last among those under the mouse, ignoring the containers
+    /// of another of them and the widgets that are transparent to the mouse.
+    fn topmost_hovered(&self) -> Option<UIID> {
+        let candidates: Vec<UIID> = self.hovered.iter().map(|&ui_id| ui_id)
+            .filter(|ui_id| !self.transparent_to_mouse.contains(ui_id))
+            .filter(|&ui_id| !self.is_covered_by_window(ui_id, self.mouse.pos)).collect();
+        candidates.iter().rev().map(|&ui_id| ui_id).find(|&ui_id| {
+            !candidates.iter().any(|&other| is_ancestor(&self.parents, ui_id, other))
+        })
+    }
+
+    /// Draw the widgets created until the matching `end_children` as the children of the
+    /// given container widget, i.e. the contents of a ScrollArea or Window. A container
+    /// still receives the mouse while one of its children is the topmost widget under it,
+    /// and never claims the mouse from its children, wherever they lie.
+    pub fn begin_children(&mut self, ui_id: UIID) {
+        self.parent_stack.push(ui_id);
+    }
+
+    /// Restrict all following drawing and the mouse to the intersection of the given
+    /// rectangle with the current clip (if any) until the matching `pop_clip`, i.e. for
+    /// the contents of a ScrollArea. The mouse is hidden from widgets drawn outside the
+    /// clip, and widgets placed while the mouse is outside it never claim the mouse.
+    pub fn push_clip(&mut self, pos: Point, dim: Dimensions) {
+        self.clip_stack.push(self.win_h, pos, dim);
+    }
+
+    /// Remove the latest clip, restoring the one beneath it (if any).
+    pub fn pop_clip(&mut self) {
+        self.clip_stack.pop(self.win_h);
+    }
+
+    /// Return the rectangle to which drawing and the mouse are currently restricted (if any).
+    pub fn get_clip(&self) -> Option<(Point, Dimensions)> {
+        self.clip_stack.current()
+    }
+
+    /// Whether or not the mouse lies outside of the current clip.
+    fn is_mouse_clipped(&self) -> bool {
+        match self.clip_stack.current() {
+            Some((pos, dim)) => !::rectangle::is_over(pos, self.mouse.pos, dim),
+            None => false,
+        }
+    }
+
+    /// End the children of the latest container.
+    pub fn end_children(&mut self) {
+        self.parent_stack.pop();
+    }
+
+    /// Return the container of the given widget (if any), as placed during the current frame
+    /// or, if it hasn't been placed yet, the previous one.
+    pub fn get_parent(&self, ui_id: UIID) -> Option<UIID> {
+        self.parents.get(&ui_id).or_else(|| self.prev_parents.get(&ui_id)).map(|&id| id)
+    }
+
+    /// Whether or not widget `a` is the container of widget `b` or of one of its containers.
+    pub fn is_ancestor(&self, a: UIID, b: UIID) -> bool {
+        is_ancestor(&self.parents, a, b) || is_ancestor(&self.prev_parents, a, b)
+    }
+
+    /// Whether or not the areas in which the two widgets were placed overlap.
+    fn overlaps(&self, a: UIID, b: UIID) -> bool {
+        match (self.get_placing(a), self.get_placing(b)) {
+            (widget::Placing::Place(ax, ay, aw, ah), widget::Placing::Place(bx, by, bw, bh)) =>
+                ax < bx + bw && bx < ax + aw && ay < by + bh && by < ay + ah,
+            _ => false,
+        }
+    }
+
+    /// Register that the widget never claims the mouse from the widgets beneath it
+    /// during the current frame, i.e. a DropTarget placed over the widgets it accepts
+    /// drops for.
+    pub fn set_transparent_to_mouse(&mut self, ui_id: UIID) {
+        self.transparent_to_mouse.push(ui_id);
+    }
+
+    /// Whether or not the mouse entered the widget's area during the current frame.
+    pub fn hover_entered(&self, ui_id: UIID) -> bool {
+        self.hovered.contains(&ui_id) && !self.prev_hovered.contains(&ui_id)
+    }
+
+    /// Whether or not the mouse left the widget's area during the current frame.
+    pub fn hover_left(&self, ui_id: UIID) -> bool {
+        !self.hovered.contains(&ui_id) && self.prev_hovered.contains(&ui_id)
+    }
+
+    /// Return the current mouse state for the given widget. The mouse is hidden from the
+    /// widget while it is over another widget's popup or captured by another widget. It
+    /// is also hidden while another widget drawn above this one and overlapping it is the
+    /// topmost widget under the mouse, so that only one of the widgets reacts. The
+    /// containers of the topmost widget (see `begin_children`) still receive the mouse. The
+    /// mouse is also hidden while it is outside of the current clip (see `push_clip`) or
+    /// over a floating window above the widget (see `is_covered_by_window`).
+    pub fn get_mouse_state_for(&self, ui_id: UIID) -> Mouse {
+        let mouse = self.mouse_state_beneath_overlay(Some(ui_id));
+        if self.maybe_mouse_captor == Some(ui_id) { return mouse }
+        if self.is_mouse_clipped() || self.is_covered_by_window(ui_id, self.mouse.pos) {
+            return Mouse { pos: [-1.0, -1.0], scroll: [0.0, 0.0], ..mouse }
+        }
+        match self.maybe_mouse_claimant {
+            Some(claimant) if claimant != ui_id && self.overlaps(ui_id, claimant)
+                && !is_ancestor(&self.prev_parents, ui_id, claimant) =>
+                Mouse { pos: [-1.0, -1.0], scroll: [0.0, 0.0], ..mouse },
+            _ => mouse,
+        }
+    }
+
+    fn mouse_state_beneath_overlay(&self, maybe_owner: Option<UIID>) -> Mouse {
+        use rectangle::is_over;
+        if self.is_input_locked {
+            return Mouse {
+                pos: [-1.0, -1.0],
+                left: ButtonState::Up,
+                middle: ButtonState::Up,
+                right: ButtonState::Up,
+                scroll: [0.0, 0.0],
+                is_double_click: false,
+                ..self.mouse
             }
+        }
+        // The widget that captured the mouse receives it wherever it is, and no other does.
+        match self.maybe_mouse_captor {
+            Some(captor) if maybe_owner == Some(captor) => return self.mouse,
+            Some(_) => return Mouse { pos: [-1.0, -1.0], scroll: [0.0, 0.0], ..self.mouse },
+            None => (),
+        }
+        let maybe_owner_depth = maybe_owner.and_then(|owner| {
+            self.overlay_areas.iter().chain(self.prev_overlay_areas.iter())
+                .filter(|&&(id, _, _, _)| id == owner).map(|&(_, depth, _, _)| depth).max()
         });
-        event.text(|text| {
-            self.text_just_entered.push(text.to_string())
+        let mut areas = self.overlay_areas.iter().chain(self.prev_overlay_areas.iter());
+        let is_covered = areas.any(|&(id, depth, pos, dim)| {
+            Some(id) != maybe_owner
+            && maybe_owner_depth.map_or(true, |owner_depth| depth >= owner_depth)
+            && is_over(pos, self.mouse.pos, dim)
         });
+        match is_covered {
+            true => Mouse { pos: [-1.0, -1.0], scroll: [0.0, 0.0], ..self.mouse },
+            false => self.mouse,
+        }
     }
 
-    /// Return the current mouse state.
-    pub fn get_mouse_state(&self) -> Mouse {
-        self.mouse
-    }
+    /// Return the mouse wheel movement since the previous frame. Widgets should prefer the
+    /// `scroll` of `get_mouse_state`, which is hidden from them while beneath a popup.
+    pub fn get_scroll(&self) -> Point { self.mouse.scroll }
+
+    /// Return the time (in seconds) at which the current frame began.
+    pub fn frame_time(&self) -> f64 { self.frame_time }
+
+    /// Return the time (in seconds) elapsed between the previous frame and the current one.
+    /// Widgets should use this to drive animations so that they run at the same speed
+    /// regardless of the frame rate.
+    pub fn frame_dt(&self) -> f64 { self.frame_dt }
 
     /// Return the vector of recently pressed keys.
     pub fn get_pressed_keys(&self) -> Vec<input::keyboard::Key> {
-        self.keys_just_pressed.clone()
+        match self.is_input_locked {
+            true => Vec::new(),
+            false => self.keys_just_pressed.clone(),
+        }
+    }
+
+    /// Return the recently pressed keys if no widget has keyboard focus and no TextBox has
+    /// captured the keyboard. Keys are routed to the focused widget or captured TextBox
+    /// first, so global shortcuts (i.e. a button's hotkey) should use these rather than
+    /// `get_pressed_keys`.
+    pub fn get_unfocused_keys(&self) -> Vec<input::keyboard::Key> {
+        match (self.maybe_focused, self.was_text_captured) {
+            (None, false) => self.get_pressed_keys(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Register an application-level shortcut under the given id. Several shortcuts may
+    /// share an id, i.e. for alternative key combinations of the same command.
+    pub fn register_shortcut(&mut self, shortcut: Shortcut, id: ShortcutId) {
+        if !self.shortcuts.iter().any(|&(s, s_id)| s == shortcut && s_id == id) {
+            self.shortcuts.push((shortcut, id));
+        }
+    }
+
+    /// Remove all shortcuts registered under the given id.
+    pub fn unregister_shortcut(&mut self, id: ShortcutId) {
+        self.shortcuts.retain(|&(_, s_id)| s_id != id);
+    }
+
+    /// Whether or not a shortcut registered under the given id was pressed during the
+    /// current frame. While a TextBox has captured the keyboard, shortcuts that could be
+    /// typed (those without Ctrl or Alt) are left to the TextBox.
+    pub fn shortcut_pressed(&self, id: ShortcutId) -> bool {
+        let modifiers = self.modifiers;
+        self.shortcuts.iter()
+            .filter(|&&(shortcut, s_id)| s_id == id
+                    && !(self.was_text_captured && shortcut.is_typeable()))
+            .any(|&(shortcut, _)| self.keys_just_pressed.iter()
+                 .any(|&key| shortcut.is_triggered_by(key, modifiers)))
+    }
+
+    /// Register that a TextBox has captured the keyboard for the current frame.
+    pub fn capture_text(&mut self) {
+        self.is_text_captured = true;
+    }
+
+    /// Use the given clipboard (i.e. one backed by the system clipboard) in place of the
+    /// default, which only holds text copied within the application.
+    pub fn set_clipboard(&mut self, clipboard: Box<Clipboard + 'static>) {
+        self.clipboard = clipboard;
+    }
+
+    /// Return the text held by the clipboard (if any).
+    pub fn get_clipboard_text(&mut self) -> Option<String> {
+        self.clipboard.get_text()
+    }
+
+    /// Replace the clipboard's contents with the given text.
+    pub fn set_clipboard_text(&mut self, text: &str) {
+        self.clipboard.set_text(text)
+    }
+
+    /// Return the current state of the modifier keys.
+    pub fn get_modifiers(&self) -> Modifiers {
+        self.modifiers
     }
 
     /// Return the vector of recently entered text.
     pub fn get_entered_text(&self) -> Vec<String> {
-        self.text_just_entered.clone()
+        match self.is_input_locked {
+            true => Vec::new(),
+            false => self.text_just_entered.clone(),
+        }
     }
 
+    /// Lock (or unlock) the input for the widgets drawn from now on. Locked widgets are
+    /// still drawn, but receive the mouse as if it were outside of the window with its
+    /// buttons released, receive no keys or text and can't be focused. This may be used
+    /// to make the UI beneath a modal dialog non-interactive by locking the input before
+    /// drawing the UI and unlocking it before drawing the dialog. The lock is lifted at
+    /// the start of each frame.
+    pub fn lock_input(&mut self, locked: bool) { self.is_input_locked = locked }
+
+    /// Whether or not the input is locked for the widgets drawn from now on.
+    pub fn is_input_locked(&self) -> bool { self.is_input_locked }
+
     /// Return a mutable reference to the widget that matches the given ui_id
     pub fn get_widget(&mut self, ui_id: UIID, default: Widget) -> &mut Widget {
         let ui_id_idx = ui_id as uint;
@@ -171,7 +944,99 @@ impl UiContext {
                 *placing = widget::Placing::Place(pos[0], pos[1], dim[0], dim[1])
             }
         }
+        if !self.placed_this_frame.contains(&ui_id) { self.placed_this_frame.push(ui_id) }
+        match self.parent_stack.last() {
+            Some(&parent) if parent != ui_id => { self.parents.insert(ui_id, parent); },
+            _ => (),
+        }
+        // A widget that is clipped from beneath the mouse can't receive it.
+        if self.is_mouse_clipped() { self.transparent_to_mouse.push(ui_id) }
         self.prev_uiid = ui_id;
+        self.advance_flow(pos, dim);
+    }
+
+    /// Place the widgets created until the matching `end` beneath one another, starting
+    /// at the given position and separated by `spacing`. A widget given a position of its
+    /// own is still drawn there, and the column continues beneath it.
+    pub fn begin_column(&mut self, pos: Point, spacing: f64) {
+        self.begin_flow(FlowDirection::Column, pos, spacing)
+    }
+
+    /// Place the widgets created until the matching `end` beside one another, starting
+    /// at the given position and separated by `spacing`.
+    pub fn begin_row(&mut self, pos: Point, spacing: f64) {
+        self.begin_flow(FlowDirection::Row, pos, spacing)
+    }
+
+    fn begin_flow(&mut self, direction: FlowDirection, pos: Point, spacing: f64) {
+        let pos = self.to_window(pos);
+        self.flows.push(Flow {
+            direction: direction,
+            spacing: spacing,
+            start: pos,
+            next: pos,
+            extent: pos,
+        });
+    }
+
+    /// End the latest column or row. If it was begun within another, the widgets
+    /// of the outer flow continue after the area it covered.
+    pub fn end(&mut self) {
+        if let Some(flow) = self.flows.pop() {
+            let dim = [flow.extent[0] - flow.start[0], flow.extent[1] - flow.start[1]];
+            self.advance_flow(flow.start, dim);
+        }
+    }
+
+    /// Return the position of the next widget in the current flow, or the origin of the
+    /// current container if no flow has been begun. Widget builders start from this position.
+    pub fn flow_pos(&self) -> Point {
+        match self.flows.last() {
+            Some(flow) => flow.next,
+            None => self.origin(),
+        }
+    }
+
+    /// Position the widgets created until the matching `end_container` relative to the
+    /// given position, which is itself relative to the current container (if any). A
+    /// whole group of widgets may then be moved by changing only the container's position.
+    /// Positions given with `point` and `position` (and to `begin_column` or `begin_row`)
+    /// are relative to the container, while Placings are always stored in window
+    /// coordinates.
+    pub fn begin_container(&mut self, pos: Point) {
+        let origin = self.to_window(pos);
+        self.origins.push(origin);
+    }
+
+    /// End the latest container.
+    pub fn end_container(&mut self) {
+        self.origins.pop();
+    }
+
+    /// Return the window position of the current container's origin.
+    pub fn origin(&self) -> Point {
+        match self.origins.last() {
+            Some(&origin) => origin,
+            None => [0.0, 0.0],
+        }
+    }
+
+    /// Convert a position relative to the current container into window coordinates.
+    pub fn to_window(&self, pos: Point) -> Point {
+        let origin = self.origin();
+        [origin[0] + pos[0], origin[1] + pos[1]]
+    }
+
+    /// Move the current flow past the given rect.
+    fn advance_flow(&mut self, pos: Point, dim: Dimensions) {
+        if let Some(flow) = self.flows.last_mut() {
+            let corner = [pos[0] + dim[0], pos[1] + dim[1]];
+            flow.extent = [flow.extent[0].max(corner[0]), flow.extent[1].max(corner[1])];
+            flow.next = match flow.direction {
+                FlowDirection::Column => [flow.start[0], corner[1] + flow.spacing],
+                FlowDirection::Row => [corner[0] + flow.spacing, flow.start[1]],
+            };
+        }
     }
 
     /// Get the UIID of the previous widget.
@@ -185,6 +1050,220 @@ impl UiContext {
         }
     }
 
+    /// Render the given widget, along with its children and popups, to an offscreen
+    /// framebuffer and return the pixels of the area they cover, i.e. for exporting a chart
+    /// or panel as an image. `draw` must draw the widget as it is drawn during the frame.
+    /// Nothing else appears in the image, whether drawn over or beneath the widget. The
+    /// widgets are drawn with their input locked, and their states are restored afterwards
+    /// so that the frame's own drawing of them is unaffected. Only the area within the
+    /// window is captured. Returns None if `draw` doesn't place the widget.
+    pub fn render_widget_to_image(&mut self, ui_id: UIID, graphics: &mut Gl,
+                                  draw: |&mut UiContext, &mut Gl|) -> Option<::draw::Image> {
+        let mut draw = draw;
+        let saved_data = self.data.clone();
+        let was_input_locked = self.is_input_locked;
+        let num_layers = self.overlay.len();
+        let num_areas = self.overlay_areas.len();
+        let offscreen = ::draw::Offscreen::new(self.win_w, self.win_h);
+
+        // Draw the widget followed by the popups it pushed, which are then removed so that
+        // neither they nor the areas they cover appear in the frame twice.
+        self.is_input_locked = true;
+        for entry in self.data.iter_mut() { entry.1 = widget::Placing::NoPlace }
+        draw(self, graphics);
+        let mut layers = Vec::new();
+        while self.overlay.len() > num_layers { layers.push(self.overlay.pop().unwrap()) }
+        layers.reverse();
+        layers.sort_by(|&(a, _), &(b, _)| a.cmp(&b));
+        for &(_, ref primitives) in layers.iter() {
+            overlay::draw(self, graphics, primitives.as_slice());
+        }
+
+        // Crop to the bounds of everything the widget placed.
+        let maybe_image = {
+            let is_in_subtree = |id: UIID| id == ui_id || self.is_ancestor(ui_id, id);
+            let mut rects: Vec<(Point, Dimensions)> = range(0u, self.data.len())
+                .filter(|&i| is_in_subtree(i as UIID))
+                .filter_map(|i| match self.data[i].1 {
+                    widget::Placing::Place(x, y, w, h) => Some(([x, y], [w, h])),
+                    widget::Placing::NoPlace => None,
+                }).collect();
+            let is_placed = !rects.is_empty();
+            rects.extend(self.overlay_areas.slice_from(num_areas).iter()
+                .filter(|&&(id, _, _, _)| is_in_subtree(id))
+                .map(|&(_, _, pos, dim)| (pos, dim)));
+            let mut start = [::std::f64::MAX, ..2];
+            let mut end = [::std::f64::MIN, ..2];
+            for &(pos, dim) in rects.iter() {
+                start = [start[0].min(pos[0]), start[1].min(pos[1])];
+                end = [end[0].max(pos[0] + dim[0]), end[1].max(pos[1] + dim[1])];
+            }
+            match is_placed {
+                true => Some(::draw::read_pixels(self.win_w, self.win_h, start,
+                                                 [end[0] - start[0], end[1] - start[1]])),
+                false => None,
+            }
+        };
+        drop(offscreen);
+
+        self.overlay_areas.truncate(num_areas);
+        self.data = saved_data;
+        self.is_input_locked = was_input_locked;
+        maybe_image
+    }
+
+    /// Check the widgets placed during the current frame for rects that extend beyond
+    /// the window or overlap one another, other than containers overlapping the widgets
+    /// within them (see `begin_children`). This should be called once all widgets have
+    /// been drawn for the frame.
+    pub fn layout_warnings(&self) -> Vec<LayoutWarning> {
+        let rects: Vec<(UIID, Point, Dimensions)> = self.placed_this_frame.iter()
+            .filter_map(|&ui_id| match self.get_placing(ui_id) {
+                widget::Placing::Place(x, y, w, h) => Some((ui_id, [x, y], [w, h])),
+                widget::Placing::NoPlace => None,
+            }).collect();
+        let mut warnings = Vec::new();
+        for (i, &(a_id, a_pos, a_dim)) in rects.iter().enumerate() {
+            if a_pos[0] < 0.0 || a_pos[1] < 0.0
+            || a_pos[0] + a_dim[0] > self.win_w || a_pos[1] + a_dim[1] > self.win_h {
+                warnings.push(LayoutWarning::OutOfWindow(a_id));
+            }
+            for &(b_id, b_pos, b_dim) in rects.slice_from(i + 1u).iter() {
+                // A container is expected to overlap the widgets within it.
+                if self.is_ancestor(a_id, b_id) || self.is_ancestor(b_id, a_id) { continue }
+                if a_pos[0] < b_pos[0] + b_dim[0] && b_pos[0] < a_pos[0] + a_dim[0]
+                && a_pos[1] < b_pos[1] + b_dim[1] && b_pos[1] < a_pos[1] + a_dim[1] {
+                    warnings.push(LayoutWarning::Overlap(a_id, b_id));
+                }
+            }
+        }
+        warnings
+    }
+
+    /// Return the rects of all dock panels and floating windows other than the given one,
+    /// for snapping a panel or window that is being dragged.
+    pub fn get_panel_rects(&self, except: UIID) -> Vec<(Point, Dimensions)> {
+        self.data.iter().enumerate().filter_map(|(i, entry)| match *entry {
+            (widget::Widget::Dock(_), widget::Placing::Place(x, y, w, h))
+            | (widget::Widget::Window(_), widget::Placing::Place(x, y, w, h))
+                if i as UIID != except => Some(([x, y], [w, h])),
+            _ => None,
+        }).collect()
+    }
+
+    /// Move the given floating window to the top of the window stack.
+    pub fn raise_window(&mut self, ui_id: UIID) {
+        self.window_stack.retain(|&id| id != ui_id);
+        self.window_stack.push(ui_id);
+    }
+
+    /// Return the UIIDs of the floating windows ordered from bottom to top. The mouse
+    /// follows this order whatever order the windows are drawn in, but windows should
+    /// still be drawn in it (after the rest of the widgets) so that they appear that way.
+    pub fn window_order(&self) -> Vec<UIID> { self.window_stack.clone() }
+
+    /// Return the floating window that is, or contains, the given widget (if any).
+    fn window_of(&self, ui_id: UIID) -> Option<UIID> {
+        let mut id = ui_id;
+        for _ in range(0u, self.parents.len() + self.prev_parents.len() + 1u) {
+            if self.window_stack.contains(&id) { return Some(id) }
+            match self.get_parent(id) {
+                Some(parent) => id = parent,
+                None => return None,
+            }
+        }
+        None
+    }
+
+    /// Is the given point covered by any floating window above the given widget. Widgets
+    /// within a window (see `begin_children`) lie at its height, and all other widgets lie
+    /// beneath every window.
+    pub fn is_covered_by_window(&self, ui_id: UIID, p: Point) -> bool {
+        let above = match self.window_of(ui_id) {
+            Some(window) => match self.window_stack.iter().position(|&id| id == window) {
+                Some(idx) => self.window_stack.slice_from(idx + 1u),
+                None => self.window_stack.as_slice(),
+            },
+            None => self.window_stack.as_slice(),
+        };
+        above.iter().any(|&id| match self.get_placing(id) {
+            widget::Placing::Place(x, y, w, h) =>
+                p[0] >= x && p[0] <= x + w && p[1] >= y && p[1] <= y + h,
+            widget::Placing::NoPlace => false,
+        })
+    }
+
+    /// Return the text stored for the given widget, which is empty until first written to.
+    /// This is for widgets that must keep text between frames as their State must be Copy.
+    pub fn text_buffer(&mut self, ui_id: UIID) -> &mut String {
+        if !self.text_buffers.contains_key(&ui_id) {
+            self.text_buffers.insert(ui_id, String::new());
+        }
+        self.text_buffers.get_mut(&ui_id).unwrap()
+    }
+
+    /// Return the edits that may be undone for the given TextBox.
+    pub fn edit_history(&mut self, ui_id: UIID) -> &mut EditHistory {
+        if !self.edit_histories.contains_key(&ui_id) {
+            self.edit_histories.insert(ui_id, EditHistory::new());
+        }
+        self.edit_histories.get_mut(&ui_id).unwrap()
+    }
+
+    /// Set the time (in seconds) over which the colors of widgets such as the Button and
+    /// Slider fade between their Normal, Highlighted and Clicked states. A duration of zero
+    /// changes the colors instantly.
+    pub fn set_transition_duration(&mut self, secs: f64) { self.transition_duration = secs }
+
+    /// Return the time over which the colors of widgets fade between states.
+    pub fn get_transition_duration(&self) -> f64 { self.transition_duration }
+
+    /// Step the given widget's fade towards the given state by the time elapsed since the
+    /// previous frame and return it, for drawing the widget's colors with
+    /// `Transition::color`. This should be called once per frame for each widget.
+    pub fn transition(&mut self, ui_id: UIID, state: ::rectangle::State) -> Transition {
+        let amount = match self.transition_duration > 0.0 {
+            true => self.frame_dt / self.transition_duration,
+            false => 1.0,
+        };
+        let transition = match self.transitions.get(&ui_id) {
+            Some(transition) => transition.step(state, amount),
+            None => Transition::new(state),
+        };
+        self.transitions.insert(ui_id, transition);
+        transition
+    }
+
+    /// Record the y position of the baseline of the text drawn by the given widget, so
+    /// that labels beside it may be aligned with it.
+    pub fn set_baseline(&mut self, ui_id: UIID, y: f64) {
+        self.baselines.insert(ui_id, y);
+    }
+
+    /// Return the constraint layout, to which rects and constraints should be added once
+    /// (i.e. at startup) rather than each frame. It is solved against the window's size at
+    /// each render event, so rects added during a frame are first solved at the next.
+    pub fn constraint_layout(&mut self) -> &mut ConstraintLayout {
+        &mut self.constraint_layout
+    }
+
+    /// Return the position and dimensions of the given rect of the constraint layout, as
+    /// solved at the latest render event.
+    pub fn get_layout_rect(&self, id: RectId) -> (Point, Dimensions) {
+        self.constraint_layout.rect(id)
+    }
+
+    /// Whether or not all of the constraint layout's constraints held when it was last
+    /// solved, i.e. false if any of them conflict.
+    pub fn is_layout_satisfied(&self) -> bool {
+        self.is_layout_satisfied
+    }
+
+    /// Return the y position of the baseline of the text last drawn by the given widget.
+    pub fn get_baseline(&self, ui_id: UIID) -> Option<f64> {
+        self.baselines.get(&ui_id).map(|&y| y)
+    }
+
     /// Return a reference to a `Character` from the GlyphCache.
     pub fn get_character(&mut self, size: FontSize, ch: char) -> &Character {
         use graphics::character::CharacterCache;
@@ -202,6 +1281,216 @@ impl UiContext {
         self.keys_just_pressed.clear();
         self.keys_just_released.clear();
         self.text_just_entered.clear();
+        self.mouse.scroll = [0.0, 0.0];
+        self.mouse.is_double_click = false;
+        self.mouse.prev_pos = self.mouse.pos;
+        self.mouse.prev_pressure = self.mouse.pressure;
+        // Forget the lifted fingers. Those that touched last frame are now moving.
+        self.touches.retain(|touch| touch.is_active());
+        for touch in self.touches.iter_mut() { touch.phase = Phase::Move }
+        // A drag released over no DropTarget is dropped nowhere.
+        if let ButtonState::Up = self.mouse.left {
+            self.maybe_drag = None;
+            self.maybe_mouse_captor = None;
+        }
+    }
+
+    /// Begin a new frame as a render event for a window of the given size would. Along
+    /// with the following methods, this allows widgets to be driven without a window's
+    /// event loop, i.e. by automated tests or an on-screen keyboard. All injected input is
+    /// received by widgets during the frame begun by the next render (injected or not), in
+    /// the order it was injected, whether it is injected between frames or while drawing.
+    pub fn inject_render(&mut self, win_w: f64, win_h: f64) {
+        self.prepare_for_input();
+        self.render(win_w, win_h);
+    }
+
+    /// Inject text as though it were typed.
+    pub fn inject_text(&mut self, text: String) {
+        self.injected.push(Injected::Text(text));
+    }
+
+    /// Inject a press of the given key immediately followed by its release, i.e. a tap.
+    pub fn inject_key(&mut self, key: input::keyboard::Key) {
+        self.inject_key_press(key);
+        self.inject_key_release(key);
+    }
+
+    /// Press the given key, as a window's press event would. Modifier keys are held until
+    /// they are released with `inject_key_release`.
+    pub fn inject_key_press(&mut self, key: input::keyboard::Key) {
+        self.injected.push(Injected::Press(input::Button::Keyboard(key)));
+    }
+
+    /// Release the given key, as a window's release event would.
+    pub fn inject_key_release(&mut self, key: input::keyboard::Key) {
+        self.injected.push(Injected::Release(input::Button::Keyboard(key)));
+    }
+
+    /// Move the mouse to the given position, as a window's mouse cursor event would.
+    pub fn inject_mouse_move(&mut self, pos: Point) {
+        self.injected.push(Injected::MouseMove(pos));
+    }
+
+    /// Press the given mouse button, as a window's press event would. As with a window's
+    /// events, a press must be released during a later frame to click a widget.
+    pub fn inject_mouse_press(&mut self, button: input::MouseButton) {
+        self.injected.push(Injected::Press(input::Button::Mouse(button)));
+    }
+
+    /// Release the given mouse button, as a window's release event would.
+    pub fn inject_mouse_release(&mut self, button: input::MouseButton) {
+        self.injected.push(Injected::Release(input::Button::Mouse(button)));
+    }
+
+    /// Scroll the mouse wheel by the given amount, as a window's scroll event would.
+    pub fn inject_mouse_scroll(&mut self, dx: f64, dy: f64) {
+        self.injected.push(Injected::Scroll(dx, dy));
+    }
+
+    /// Receive the input injected since the previous render, as though its events had
+    /// arrived just before the current one.
+    fn receive_injected(&mut self) {
+        let injected = ::std::mem::replace(&mut self.injected, Vec::new());
+        for input in injected.into_iter() {
+            match input {
+                Injected::MouseMove(pos) => self.move_mouse(pos),
+                Injected::Press(button) => self.press(button),
+                Injected::Release(button) => self.release(button),
+                Injected::Scroll(dx, dy) =>
+                    self.mouse.scroll = [self.mouse.scroll[0] + dx, self.mouse.scroll[1] + dy],
+                Injected::Text(text) => self.text_just_entered.push(text),
+            }
+        }
+    }
+
+    /// Register an area that may be clicked without a captured TextBox losing capture
+    /// for the current frame.
+    pub fn add_focus_safe_area(&mut self, pos: Point, dim: Dimensions) {
+        self.focus_safe_areas.push((pos, dim));
+    }
+
+    /// Whether or not the given point is within an area registered via `add_focus_safe_area`
+    /// during either the current or previous frame.
+    pub fn is_focus_safe(&self, p: Point) -> bool {
+        use rectangle::is_over;
+        self.focus_safe_areas.iter().chain(self.prev_focus_safe_areas.iter())
+            .any(|&(pos, dim)| is_over(pos, p, dim))
+    }
+
+    /// Draw the given shapes during the overlay pass, covering the given area for which
+    /// the popup receives the mouse before the widgets beneath it.
+    pub fn push_overlay(&mut self, ui_id: UIID, pos: Point, dim: Dimensions,
+                        primitives: Vec<overlay::Primitive>) {
+        self.push_overlay_at_depth(ui_id, 0, pos, dim, primitives)
+    }
+
+    /// As `push_overlay`, but the shapes are drawn above those of all popups with a
+    /// lower depth and below those with a greater depth, regardless of the order in which
+    /// the popups were drawn. Popups of equal depth are drawn in order.
+    pub fn push_overlay_at_depth(&mut self, ui_id: UIID, depth: i32, pos: Point, dim: Dimensions,
+                                 primitives: Vec<overlay::Primitive>) {
+        self.overlay_areas.push((ui_id, depth, pos, dim));
+        self.overlay.push((depth, primitives));
+    }
+
+    /// Draw the given shapes during the overlay pass at the given depth (see
+    /// `push_overlay_at_depth`) without covering any area, i.e. for the ghost of an item
+    /// being dragged beneath the cursor.
+    pub fn push_overlay_shapes(&mut self, depth: i32, primitives: Vec<overlay::Primitive>) {
+        self.overlay.push((depth, primitives));
+    }
+
+    /// Draw the popups (i.e. open DropDownLists) and the ghost of any dragged item over the
+    /// rest of the frame. This must be called once all widgets have been drawn for the frame,
+    /// as popups aren't shown otherwise.
+    pub fn draw_overlay(&mut self, graphics: &mut Gl) {
+        let mut layers = ::std::mem::replace(&mut self.overlay, Vec::new());
+        layers.sort_by(|&(a, _), &(b, _)| a.cmp(&b));
+        for &(_, ref primitives) in layers.iter() {
+            overlay::draw(self, graphics, primitives.as_slice());
+        }
+        let maybe_label = self.maybe_drag.as_ref().map(|drag| drag.label.clone());
+        if let Some(label) = maybe_label {
+            let mouse_pos = self.mouse.pos;
+            ::drag::draw_ghost(self, graphics, mouse_pos, label.as_slice());
+        }
+    }
+
+    /// Begin dragging the given payload from the given widget while the left mouse button is
+    /// held. The label is shown beside the cursor during `draw_overlay`. The payload is given
+    /// to the DropTarget of the same type on which the button is released.
+    pub fn start_drag<T: 'static>(&mut self, source: UIID, label: &str, payload: T) {
+        self.maybe_drag = Some(Drag::new(source, label.to_string(), payload));
+    }
+
+    /// Return the drag in progress (if any).
+    pub fn get_drag(&self) -> Option<&Drag> { self.maybe_drag.as_ref() }
+
+    /// Stop the drag in progress (if any) without dropping it.
+    pub fn end_drag(&mut self) { self.maybe_drag = None }
+
+    /// Capture the mouse for the given widget while it is dragged. Until it is released
+    /// (or the left button is), `get_mouse_state_for` gives the captor the mouse wherever
+    /// it is, even over a popup, while every other widget receives the mouse as if it were
+    /// outside of the window.
+    pub fn capture_mouse(&mut self, ui_id: UIID) { self.maybe_mouse_captor = Some(ui_id) }
+
+    /// Release the mouse if it was captured by the given widget.
+    pub fn uncapture_mouse(&mut self, ui_id: UIID) {
+        if self.maybe_mouse_captor == Some(ui_id) { self.maybe_mouse_captor = None }
+    }
+
+    /// Return the widget that has captured the mouse (if any).
+    pub fn get_mouse_captor(&self) -> Option<UIID> { self.maybe_mouse_captor }
+
+    /// Outline every widget placed during the frame and label it with its UIID and
+    /// position when `draw_debug` is called.
+    pub fn debug_layout(&mut self, debug: bool) { self.is_debugging_layout = debug }
+
+    /// Draw any enabled debug rendering. This should be called once all widgets
+    /// have been drawn for the frame.
+    pub fn draw_debug(&mut self, graphics: &mut Gl) {
+        if self.outline_layout_warnings {
+            use graphics::Context;
+            let context = Context::abs(self.win_w, self.win_h);
+            let line = graphics::Line::round([1.0, 0.0, 0.0, 1.0], 1.0);
+            let mut offenders = Vec::new();
+            for warning in self.layout_warnings().iter() {
+                match *warning {
+                    LayoutWarning::OutOfWindow(ui_id) => offenders.push(ui_id),
+                    LayoutWarning::Overlap(a, b) => { offenders.push(a); offenders.push(b); },
+                }
+            }
+            for &ui_id in offenders.iter() {
+                if let widget::Placing::Place(x, y, w, h) = self.get_placing(ui_id) {
+                    line.draw([x, y, x + w, y], &context, graphics);
+                    line.draw([x + w, y, x + w, y + h], &context, graphics);
+                    line.draw([x + w, y + h, x, y + h], &context, graphics);
+                    line.draw([x, y + h, x, y], &context, graphics);
+                }
+            }
+        }
+        if self.is_debugging_layout {
+            use graphics::Context;
+            let context = Context::abs(self.win_w, self.win_h);
+            let line = graphics::Line::round([0.0, 1.0, 1.0, 1.0], 0.5);
+            let placed = self.placed_this_frame.clone();
+            for &ui_id in placed.iter() {
+                if let widget::Placing::Place(x, y, w, h) = self.get_placing(ui_id) {
+                    line.draw([x, y, x + w, y], &context, graphics);
+                    line.draw([x + w, y, x + w, y + h], &context, graphics);
+                    line.draw([x + w, y + h, x, y + h], &context, graphics);
+                    line.draw([x, y + h, x, y], &context, graphics);
+                    let text = format!("{} [{}, {}]", ui_id, x, y);
+                    self.draw_text(graphics, [x + 2.0, y + 2.0], DEBUG_FONT_SIZE,
+                                   Color::new(0.0, 1.0, 1.0, 1.0), text.as_slice());
+                }
+            }
+        }
+        if let Some(deficiency) = self.color_blind_simulation {
+            self.color_blind_simulator.draw(self.win_w, self.win_h, graphics, deficiency);
+        }
     }
 
     /// Draws text
@@ -229,3 +1518,18 @@ impl UiContext {
     }
 
 }
+
+/// Whether or not widget `a` is the container of widget `b` or of one of its containers,
+/// according to the given map from each widget to its container.
+fn is_ancestor(parents: &HashMap<UIID, UIID>, a: UIID, b: UIID) -> bool {
+    let mut id = b;
+    // Bounded by the number of widgets, in case the same UIID was used for two containers.
+    for _ in range(0u, parents.len()) {
+        match parents.get(&id) {
+            Some(&parent) if parent == a => return true,
+            Some(&parent) => id = parent,
+            None => return false,
+        }
+    }
+    false
+}
@@ -86,3 +86,36 @@ pub fn val_to_string<T: ToString + ToPrimitive>
     }
 }
 
+
+/// Map a linear progress value between 0.0 and 1.0 to a smooth "ease-in-out" curve.
+pub fn smooth_step(t: f64) -> f64 {
+    let t = clamp(t, 0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Format a duration in seconds as "mm:ss.cc", or "h:mm:ss.cc" from an hour on, using the
+/// given buffer rather than allocating a new String (useful for values that change every frame).
+pub fn format_time<'a>(secs: f64, buf: &'a mut [u8, ..32]) -> &'a str {
+    let hundredths = (secs.max(0.0) * 100.0) as u64;
+    let hours = hundredths / 360000;
+    let (mins, secs, cents) = ((hundredths / 6000) % 60, (hundredths / 100) % 60, hundredths % 100);
+    let digit = |n: u64| b'0' + n as u8;
+    let mut len = 0u;
+    if hours > 0 {
+        let mut divisor = 1u64;
+        while hours / divisor >= 10 { divisor *= 10 }
+        while divisor > 0 {
+            buf[len] = digit((hours / divisor) % 10);
+            len += 1;
+            divisor /= 10;
+        }
+        buf[len] = b':';
+        len += 1;
+    }
+    for &b in [digit(mins / 10), digit(mins % 10), b':', digit(secs / 10), digit(secs % 10),
+               b'.', digit(cents / 10), digit(cents % 10)].iter() {
+        buf[len] = b;
+        len += 1;
+    }
+    ::std::str::from_utf8(buf[..len]).unwrap()
+}
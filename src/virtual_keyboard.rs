@@ -0,0 +1,262 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use input::keyboard::Key;
+use mouse::Mouse;
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use widget::Widget::VirtualKeyboard;
+
+/// The arrangement of keys displayed by the keyboard.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Layout {
+    Qwerty,
+    Numeric,
+}
+
+/// A single key on the keyboard.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum KeyCap {
+    Char(char),
+    Shift,
+    Space,
+    Backspace,
+    Enter,
+}
+
+impl KeyCap {
+    /// The width of the key relative to a single character key.
+    fn width_weight(&self) -> f64 {
+        match *self {
+            KeyCap::Char(_) => 1.0,
+            KeyCap::Shift | KeyCap::Backspace => 1.5,
+            KeyCap::Enter => 2.0,
+            KeyCap::Space => 5.0,
+        }
+    }
+}
+
+/// A (row, column) index into the keyboard's layout.
+pub type KeyIdx = (uint, uint);
+
+/// Represents the interaction with the keyboard.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    Highlighted(KeyIdx),
+    Clicked(KeyIdx),
+}
+
+/// Represents the state of the VirtualKeyboard widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct State {
+    interaction: Interaction,
+    is_shifted: bool,
+}
+
+widget_fns!(VirtualKeyboard, State, VirtualKeyboard(State {
+    interaction: Interaction::Normal,
+    is_shifted: false,
+}));
+
+/// Return the rows of keys for the given layout.
+fn rows(layout: Layout) -> Vec<Vec<KeyCap>> {
+    let char_row = |chars: &str| -> Vec<KeyCap> { chars.chars().map(|ch| KeyCap::Char(ch)).collect() };
+    match layout {
+        Layout::Qwerty => vec![
+            char_row("1234567890"),
+            char_row("qwertyuiop"),
+            char_row("asdfghjkl"),
+            { let mut row = vec![KeyCap::Shift]; row.extend(char_row("zxcvbnm").into_iter());
+              row.push(KeyCap::Backspace); row },
+            vec![KeyCap::Space, KeyCap::Enter],
+        ],
+        Layout::Numeric => vec![
+            char_row("789"),
+            char_row("456"),
+            char_row("123"),
+            { let mut row = char_row("0."); row.push(KeyCap::Backspace); row },
+            vec![KeyCap::Enter],
+        ],
+    }
+}
+
+/// Return the position and dimensions of every key, row by row. Rows are centered
+/// and a single key's width is determined by the widest row.
+fn key_rects(pos: Point, dim: Dimensions, rows: &Vec<Vec<KeyCap>>) -> Vec<Vec<(Point, Dimensions)>> {
+    let row_weight = |row: &Vec<KeyCap>| row.iter().fold(0.0, |acc, key| acc + key.width_weight());
+    let max_weight = rows.iter().fold(0.0f64, |acc, row| acc.max(row_weight(row)));
+    let unit_w = dim[0] / max_weight;
+    let key_h = dim[1] / rows.len() as f64;
+    rows.iter().enumerate().map(|(i, row)| {
+        let mut x = pos[0] + (dim[0] - row_weight(row) * unit_w) / 2.0;
+        let y = pos[1] + key_h * i as f64;
+        row.iter().map(|key| {
+            let w = key.width_weight() * unit_w;
+            let rect = ([x, y], [w, key_h]);
+            x += w;
+            rect
+        }).collect()
+    }).collect()
+}
+
+/// Return the key under the mouse (if any).
+fn is_over(mouse_pos: Point, rects: &Vec<Vec<(Point, Dimensions)>>) -> Option<KeyIdx> {
+    for (i, row) in rects.iter().enumerate() {
+        for (j, &(pos, dim)) in row.iter().enumerate() {
+            if rectangle::is_over(pos, mouse_pos, dim) { return Some((i, j)) }
+        }
+    }
+    None
+}
+
+/// Check the current interaction with the keys.
+fn get_new_interaction(is_over_key: Option<KeyIdx>,
+                       prev: Interaction,
+                       mouse: Mouse) -> Interaction {
+    use mouse::ButtonState::{Down, Up};
+    use self::Interaction::{Normal, Highlighted, Clicked};
+    match (is_over_key, prev, mouse.left) {
+        (Some(_),   Normal,         Down) => Normal,
+        (Some(key), _,              Up)   => Highlighted(key),
+        (Some(key), Highlighted(_), Down) => Clicked(key),
+        (_,         Clicked(key),   Down) => Clicked(key),
+        _                                 => Normal,
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct VirtualKeyboardContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    layout: Layout,
+    pos: Point,
+    dim: Dimensions,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+}
+
+impl<'a> VirtualKeyboardContext<'a> {
+    #[inline]
+    pub fn layout(self, layout: Layout) -> VirtualKeyboardContext<'a> {
+        VirtualKeyboardContext { layout: layout, ..self }
+    }
+    #[inline]
+    pub fn label_color(self, color: Color) -> VirtualKeyboardContext<'a> {
+        VirtualKeyboardContext { maybe_label_color: Some(color), ..self }
+    }
+    #[inline]
+    pub fn label_font_size(self, size: u32) -> VirtualKeyboardContext<'a> {
+        VirtualKeyboardContext { maybe_label_font_size: Some(size), ..self }
+    }
+}
+
+pub trait VirtualKeyboardBuilder<'a> {
+    /// A virtual_keyboard builder method to be implemented by the UiContext.
+    fn virtual_keyboard(&'a mut self, ui_id: UIID) -> VirtualKeyboardContext<'a>;
+}
+
+impl<'a> VirtualKeyboardBuilder<'a> for UiContext {
+    fn virtual_keyboard(&'a mut self, ui_id: UIID) -> VirtualKeyboardContext<'a> {
+        let pos = self.flow_pos();
+        VirtualKeyboardContext {
+            uic: self,
+            ui_id: ui_id,
+            layout: Layout::Qwerty,
+            pos: pos,
+            dim: [480.0, 200.0],
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+        }
+    }
+}
+
+impl_colorable!(VirtualKeyboardContext);
+impl_frameable!(VirtualKeyboardContext);
+impl_hoverable!(VirtualKeyboardContext);
+impl_positionable!(VirtualKeyboardContext);
+impl_shapeable!(VirtualKeyboardContext);
+
+impl<'a> ::draw::Drawable for VirtualKeyboardContext<'a> {
+    /// Draw the keyboard. Pressed keys are injected into the UiContext's input, so
+    /// they are received by the captured TextBox during the next frame.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let rows = rows(self.layout);
+        let rects = key_rects(self.pos, self.dim, &rows);
+        let is_over_key = is_over(mouse.pos, &rects);
+        let new_interaction = get_new_interaction(is_over_key, state.interaction, mouse);
+
+        // Clicking the keyboard must not steal capture from the TextBox being typed into.
+        self.uic.add_focus_safe_area(self.pos, self.dim);
+
+        // Inject the released key.
+        let mut is_shifted = state.is_shifted;
+        match (state.interaction, new_interaction) {
+            (Interaction::Clicked(key), Interaction::Highlighted(h_key)) if key == h_key => {
+                let (row, col) = key;
+                match rows[row][col] {
+                    KeyCap::Char(ch) => {
+                        let ch = if is_shifted { ch.to_uppercase() } else { ch };
+                        self.uic.inject_text(ch.to_string());
+                        is_shifted = false;
+                    },
+                    KeyCap::Space => self.uic.inject_text(" ".to_string()),
+                    KeyCap::Backspace => self.uic.inject_key(Key::Backspace),
+                    KeyCap::Enter => self.uic.inject_key(Key::Return),
+                    KeyCap::Shift => is_shifted = !is_shifted,
+                }
+            },
+            _ => (),
+        }
+
+        // Draw.
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            false => None,
+        };
+        let t_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+        let t_size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_small);
+        for (i, row) in rows.iter().enumerate() {
+            for (j, key) in row.iter().enumerate() {
+                let (key_pos, key_dim) = rects[i][j];
+                let rect_state = match new_interaction {
+                    Interaction::Clicked(c_key) if c_key == (i, j) => rectangle::State::Clicked,
+                    _ if *key == KeyCap::Shift && is_shifted => rectangle::State::Clicked,
+                    Interaction::Highlighted(h_key) if h_key == (i, j) => rectangle::State::Highlighted,
+                    _ => rectangle::State::Normal,
+                };
+                let text = match *key {
+                    KeyCap::Char(ch) => if is_shifted { ch.to_uppercase() } else { ch }.to_string(),
+                    KeyCap::Shift => "Shift".to_string(),
+                    KeyCap::Space => "Space".to_string(),
+                    KeyCap::Backspace => "Back".to_string(),
+                    KeyCap::Enter => "Enter".to_string(),
+                };
+                rectangle::draw_with_centered_label(
+                    self.uic.win_w, self.uic.win_h, graphics, self.uic, rect_state,
+                    key_pos, key_dim, maybe_frame, color, text.as_slice(), t_size, t_color
+                );
+            }
+        }
+
+        let new_state = State { interaction: new_interaction, is_shifted: is_shifted };
+        set_state(self.uic, self.ui_id, new_state, self.pos, self.dim);
+
+    }
+}
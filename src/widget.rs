@@ -1,11 +1,34 @@
 
+use ab_toggle;
 use button;
+use canvas;
+use compound;
+use dock;
+use drawer;
 use drop_down_list;
+use drop_target;
 use envelope_editor;
+use history_scrubber;
+use link;
+use minimap;
+use node_graph;
 use number_dialer;
+use pagination;
+use picture_in_picture;
+use point::Point;
+use rating;
+use reorder_list;
+use resizable_panel;
+use scroll_area;
 use slider;
+use split;
+use stepper;
+use stopwatch;
 use text_box;
+use timeline;
 use toggle;
+use virtual_keyboard;
+use window;
 use xy_pad;
 
 /// Represents the placement of the widget including
@@ -41,6 +64,20 @@ impl Placing {
             &Placing::NoPlace => (0.0, 0.0),
         }
     }
+    /// The given position moved horizontally to share this placing's left edge.
+    pub fn align_left(&self, pos: Point) -> (f64, f64) {
+        match self {
+            &Placing::Place(x, _, _, _) => (x, pos[1]),
+            &Placing::NoPlace => (pos[0], pos[1]),
+        }
+    }
+    /// The given position moved vertically to share this placing's top edge.
+    pub fn align_top(&self, pos: Point) -> (f64, f64) {
+        match self {
+            &Placing::Place(_, y, _, _) => (pos[0], y),
+            &Placing::NoPlace => (pos[0], pos[1]),
+        }
+    }
 }
 
 /// Algebraic widget type for storing in ui_context
@@ -48,13 +85,35 @@ impl Placing {
 #[deriving(Copy, Clone)]
 pub enum Widget {
     NoWidget,
+    ABToggle(ab_toggle::State),
     Button(button::State),
+    Canvas(canvas::State),
+    Compound(compound::State),
+    Dock(dock::State),
+    Drawer(drawer::State),
     DropDownList(drop_down_list::State),
+    DropTarget(drop_target::State),
     EnvelopeEditor(envelope_editor::State),
+    HistoryScrubber(history_scrubber::State),
+    Link(link::State),
+    Minimap(minimap::State),
+    NodeGraph(node_graph::State),
     NumberDialer(number_dialer::State),
+    Pagination(pagination::State),
+    PictureInPicture(picture_in_picture::State),
+    Rating(rating::State),
+    ReorderList(reorder_list::State),
+    ResizablePanel(resizable_panel::State),
+    ScrollArea(scroll_area::State),
     Slider(slider::State),
+    Split(split::State),
+    Stepper(stepper::State),
+    Stopwatch(stopwatch::State),
     TextBox(text_box::State),
+    Timeline(timeline::State),
     Toggle(toggle::State),
+    VirtualKeyboard(virtual_keyboard::State),
+    Window(window::State),
     XYPad(xy_pad::State),
 }
 
@@ -1,6 +1,8 @@
 
 use dimensions::Dimensions;
 use point::Point;
+use stack;
+use stack::Size;
 use ui_context::UiContext;
 
 /// Callback params.
@@ -25,6 +27,21 @@ pub struct WidgetMatrixContext<'a> {
     dim: Dimensions,
     cell_pad_w: f64,
     cell_pad_h: f64,
+    spacing_w: f64,
+    spacing_h: f64,
+    outer_pad: f64,
+    maybe_col_weights: Option<&'a [f64]>,
+    maybe_row_weights: Option<&'a [f64]>,
+}
+
+/// Return the offset and length of each of `n` cells sharing `length`, separated by
+/// `spacing` and sized in proportion to the given weights (or evenly if there are none).
+fn cell_spans(n: uint, length: f64, spacing: f64, maybe_weights: Option<&[f64]>) -> Vec<(f64, f64)> {
+    let sizes: Vec<Size> = range(0u, n).map(|i| match maybe_weights {
+        Some(weights) if i < weights.len() => Size::Weight(weights[i]),
+        _ => Size::Weight(1.0),
+    }).collect();
+    stack::spans(sizes.as_slice(), length, spacing)
 }
 
 /*
@@ -37,18 +54,22 @@ impl<'a> WidgetMatrixContext<'a> {
     /// The callback called for each widget in the matrix.
     /// This should be called following all builder methods.
     pub fn each_widget(&'a mut self, callback: |&mut UiContext, WidgetNum, ColNum, RowNum, Point, Dimensions|) {
-        let widget_w = self.dim[0] / self.cols as f64;
-        let widget_h = self.dim[1] / self.rows as f64;
+        let inner_pos = [self.pos[0] + self.outer_pad, self.pos[1] + self.outer_pad];
+        let inner_dim = [self.dim[0] - self.outer_pad * 2.0, self.dim[1] - self.outer_pad * 2.0];
+        let col_spans = cell_spans(self.cols, inner_dim[0], self.spacing_w, self.maybe_col_weights);
+        let row_spans = cell_spans(self.rows, inner_dim[1], self.spacing_h, self.maybe_row_weights);
         let mut widget_num = 0u;
         for col in range(0u, self.cols) {
             for row in range(0u, self.rows) {
+                let (x, widget_w) = col_spans[col];
+                let (y, widget_h) = row_spans[row];
                 callback(
                     self.uic,
                     widget_num,
                     col,
                     row,
-                    [self.pos[0] + (widget_w * col as f64) + self.cell_pad_w,
-                     self.pos[1] + (widget_h * row as f64) + self.cell_pad_h],
+                    [inner_pos[0] + x + self.cell_pad_w,
+                     inner_pos[1] + y + self.cell_pad_h],
                     [widget_w - self.cell_pad_w * 2.0,
                      widget_h - self.cell_pad_h * 2.0],
                 );
@@ -62,6 +83,28 @@ impl<'a> WidgetMatrixContext<'a> {
         WidgetMatrixContext { cell_pad_w: w, cell_pad_h: h, ..self }
     }
 
+    /// A builder method for the space between neighbouring columns and rows.
+    pub fn spacing(self, w: f64, h: f64) -> WidgetMatrixContext<'a> {
+        WidgetMatrixContext { spacing_w: w, spacing_h: h, ..self }
+    }
+
+    /// A builder method for the space between the matrix's edges and its outer cells.
+    pub fn outer_padding(self, pad: f64) -> WidgetMatrixContext<'a> {
+        WidgetMatrixContext { outer_pad: pad, ..self }
+    }
+
+    /// Size the columns in proportion to the given weights. Columns beyond the
+    /// end of the slice have a weight of 1.0.
+    pub fn col_weights(self, weights: &'a [f64]) -> WidgetMatrixContext<'a> {
+        WidgetMatrixContext { maybe_col_weights: Some(weights), ..self }
+    }
+
+    /// Size the rows in proportion to the given weights. Rows beyond the
+    /// end of the slice have a weight of 1.0.
+    pub fn row_weights(self, weights: &'a [f64]) -> WidgetMatrixContext<'a> {
+        WidgetMatrixContext { maybe_row_weights: Some(weights), ..self }
+    }
+
     /*
     /// Create an iterator over the matrix cells.
     fn iter_cells(&mut self) -> CellIterator {
@@ -92,14 +135,20 @@ impl<'a> WidgetMatrixBuilder<'a> for UiContext {
 
     /// Create a widget matrix context.
     fn widget_matrix(&'a mut self, cols: uint, rows: uint) -> WidgetMatrixContext<'a> {
+        let pos = self.flow_pos();
         WidgetMatrixContext {
             uic: self,
             cols: cols,
             rows: rows,
-            pos: [0.0, 0.0],
+            pos: pos,
             dim: [256.0, 256.0],
             cell_pad_w: 0.0,
             cell_pad_h: 0.0,
+            spacing_w: 0.0,
+            spacing_h: 0.0,
+            outer_pad: 0.0,
+            maybe_col_weights: None,
+            maybe_row_weights: None,
         }
     }
 }
@@ -0,0 +1,285 @@
+
+use color::Color;
+use dimensions::Dimensions;
+use mouse::{Cursor, Mouse};
+use opengl_graphics::Gl;
+use point::Point;
+use rectangle;
+use shadow;
+use snap;
+use snap::Guide;
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use vecmath::vec2_sub;
+use widget::Widget::Window;
+
+/// The height of a window's title bar.
+static TITLE_BAR_HEIGHT: f64 = 24.0;
+
+/// Represents the elements that make up a Window.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Element {
+    TitleBar,
+    CloseButton,
+    Body,
+}
+
+/// Represents the interaction with a Window.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    Highlighted(Element),
+    Clicked(Element),
+    /// The title bar is being dragged. Stores the mouse offset from the window's position.
+    Dragging(Point),
+}
+
+/// Represents the state of the Window widget.
+#[deriving(Show, PartialEq, Clone, Copy)]
+pub struct State {
+    interaction: Interaction,
+    /// The position the window has been dragged to, or None if it has not yet been moved.
+    maybe_pos: Option<Point>,
+}
+
+widget_fns!(Window, State, Window(State {
+    interaction: Interaction::Normal,
+    maybe_pos: None,
+}));
+
+/// Return the element under the mouse (if any).
+fn is_over(pos: Point, mouse_pos: Point, dim: Dimensions, has_close_button: bool) -> Option<Element> {
+    if !rectangle::is_over(pos, mouse_pos, dim) { return None }
+    if mouse_pos[1] > pos[1] + TITLE_BAR_HEIGHT { return Some(Element::Body) }
+    let close_pos = [pos[0] + dim[0] - TITLE_BAR_HEIGHT, pos[1]];
+    match has_close_button
+    && rectangle::is_over(close_pos, mouse_pos, [TITLE_BAR_HEIGHT, TITLE_BAR_HEIGHT]) {
+        true => Some(Element::CloseButton),
+        false => Some(Element::TitleBar),
+    }
+}
+
+/// Check the current interaction with the window.
+fn get_new_interaction(is_over_elem: Option<Element>,
+                       prev: Interaction,
+                       pos: Point,
+                       mouse: Mouse) -> Interaction {
+    use mouse::ButtonState::{Down, Up};
+    use self::Interaction::{Normal, Highlighted, Clicked, Dragging};
+    match (is_over_elem, prev, mouse.left) {
+        (_,          Dragging(grab),                 Down) => Dragging(grab),
+        (Some(_),    Highlighted(Element::TitleBar), Down) => Dragging(vec2_sub(mouse.pos, pos)),
+        (Some(elem), Highlighted(h_elem),            Down) if elem == h_elem => Clicked(elem),
+        (Some(elem), Clicked(c_elem),                Down) if elem == c_elem => Clicked(elem),
+        (_,          Clicked(Element::Body),         Down) => Clicked(Element::Body),
+        (Some(elem), _,                              Up)   => Highlighted(elem),
+        _                                                  => Normal,
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct WindowContext<'a> {
+    uic: &'a mut UiContext,
+    ui_id: UIID,
+    title: &'a str,
+    has_close_button: bool,
+    pos: Point,
+    dim: Dimensions,
+    maybe_content: Option<|&mut UiContext, &mut Gl, Point, Dimensions|:'a>,
+    maybe_callback: Option<||:'a>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+    maybe_snap_grid: Option<f64>,
+}
+
+impl<'a> WindowContext<'a> {
+    /// Show a button at the right of the title bar. The `callback` is called when it is
+    /// clicked, after which the application should stop drawing the window.
+    #[inline]
+    pub fn close_button(self) -> WindowContext<'a> {
+        WindowContext { has_close_button: true, ..self }
+    }
+    /// Draw the window's contents. The closure is given the position and
+    /// dimensions of the area beneath the title bar.
+    #[inline]
+    pub fn content(self, content: |&mut UiContext, &mut Gl, Point, Dimensions|:'a) -> WindowContext<'a> {
+        WindowContext { maybe_content: Some(content), ..self }
+    }
+    #[inline]
+    pub fn label_color(self, color: Color) -> WindowContext<'a> {
+        WindowContext { maybe_label_color: Some(color), ..self }
+    }
+    #[inline]
+    pub fn label_font_size(self, size: u32) -> WindowContext<'a> {
+        WindowContext { maybe_label_font_size: Some(size), ..self }
+    }
+    /// Also snap the window to a grid with the given spacing while it is dragged.
+    /// Hold Alt while dragging to temporarily disable all snapping.
+    #[inline]
+    pub fn snap_grid(self, spacing: f64) -> WindowContext<'a> {
+        WindowContext { maybe_snap_grid: Some(spacing), ..self }
+    }
+    /// Return the position of the window while it is being dragged, snapped to the
+    /// window edges, the other windows and dock panels and the grid, along with the
+    /// guides to display.
+    fn dragged_pos(&self, mouse_pos: Point, grab: Point) -> (Point, Vec<Guide>) {
+        let pos = vec2_sub(mouse_pos, grab);
+        match self.uic.get_modifiers().alt {
+            true => (pos, Vec::new()),
+            false => {
+                let others = self.uic.get_panel_rects(self.ui_id);
+                snap::snap(pos, self.dim, self.uic.win_w, self.uic.win_h,
+                           others.as_slice(), self.maybe_snap_grid)
+            },
+        }
+    }
+}
+
+pub trait WindowBuilder<'a> {
+    /// A window builder method to be implemented by the UiContext.
+    fn window(&'a mut self, ui_id: UIID, title: &'a str) -> WindowContext<'a>;
+}
+
+impl<'a> WindowBuilder<'a> for UiContext {
+    fn window(&'a mut self, ui_id: UIID, title: &'a str) -> WindowContext<'a> {
+        let pos = self.flow_pos();
+        WindowContext {
+            uic: self,
+            ui_id: ui_id,
+            title: title,
+            has_close_button: false,
+            pos: pos,
+            dim: [240.0, 180.0],
+            maybe_content: None,
+            maybe_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+            maybe_snap_grid: None,
+        }
+    }
+}
+
+impl_callable!(WindowContext, ||:'a);
+impl_colorable!(WindowContext);
+impl_frameable!(WindowContext);
+impl_hoverable!(WindowContext);
+impl_positionable!(WindowContext);
+impl_shapeable!(WindowContext);
+
+impl<'a> ::draw::Drawable for WindowContext<'a> {
+    /// Draw the window. The given position is only used until the window is first dragged,
+    /// after which the dragged position is kept. While dragged, the window snaps to the
+    /// window edges and the edges of other windows and dock panels. Clicking anywhere on
+    /// the window raises it to the top of `UiContext::window_order`, and neither the window
+    /// nor its contents receive the mouse wherever a window above it is covering this one.
+    /// A window that isn't drawn during a frame (i.e. once closed) is removed from the
+    /// window order.
+    fn draw(&mut self, graphics: &mut Gl) {
+
+        let state = *get_state(self.uic, self.ui_id);
+        if !self.uic.window_order().contains(&self.ui_id) { self.uic.raise_window(self.ui_id) }
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
+        let prev_pos = match state.interaction {
+            Interaction::Dragging(grab) => vec2_sub(mouse.pos, grab),
+            _ => state.maybe_pos.unwrap_or(self.pos),
+        };
+        let is_over_elem = is_over(prev_pos, mouse.pos, self.dim, self.has_close_button);
+        let new_interaction = get_new_interaction(is_over_elem, state.interaction, prev_pos, mouse);
+        match new_interaction {
+            Interaction::Highlighted(Element::TitleBar) | Interaction::Dragging(_) =>
+                self.uic.set_cursor(Cursor::Move),
+            Interaction::Highlighted(Element::CloseButton) => self.uic.set_cursor(Cursor::Hand),
+            _ => (),
+        }
+
+        // Raise the window when pressed and check for the close button being released.
+        match (state.interaction, new_interaction) {
+            (Interaction::Highlighted(_), Interaction::Clicked(_))
+            | (Interaction::Highlighted(_), Interaction::Dragging(_)) => self.uic.raise_window(self.ui_id),
+            (Interaction::Clicked(Element::CloseButton),
+             Interaction::Highlighted(Element::CloseButton)) => match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(),
+                None => (),
+            },
+            _ => (),
+        }
+        let (pos, maybe_pos, guides) = match new_interaction {
+            Interaction::Dragging(grab) => {
+                let (pos, guides) = self.dragged_pos(mouse.pos, grab);
+                (pos, Some(pos), guides)
+            },
+            _ => (state.maybe_pos.unwrap_or(self.pos), state.maybe_pos, Vec::new()),
+        };
+        let dim = self.dim;
+
+        // Draw.
+        let (win_w, win_h) = (self.uic.win_w, self.uic.win_h);
+        let color = self.maybe_color.unwrap_or(self.uic.theme.shape_color);
+        let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(self.uic.theme.frame_color))),
+            false => None,
+        };
+        snap::draw_guides(win_w, win_h, graphics, guides.as_slice(), color.highlighted());
+        if let Some(shadow) = self.uic.theme.maybe_shadow {
+            shadow::draw(win_w, win_h, graphics, pos, dim, shadow);
+        }
+        rectangle::draw(win_w, win_h, graphics, rectangle::State::Normal, pos, dim, maybe_frame, color);
+        let t_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
+        let t_size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_small);
+        let title_state = match new_interaction {
+            Interaction::Highlighted(Element::TitleBar) => rectangle::State::Highlighted,
+            Interaction::Dragging(_) => rectangle::State::Clicked,
+            _ => rectangle::State::Normal,
+        };
+        let title = self.title;
+        rectangle::draw_with_centered_label(
+            win_w, win_h, graphics, self.uic, title_state, pos, [dim[0], TITLE_BAR_HEIGHT],
+            maybe_frame, color.clicked(), title, t_size, t_color
+        );
+        if self.has_close_button {
+            let close_state = match new_interaction {
+                Interaction::Highlighted(Element::CloseButton) => rectangle::State::Highlighted,
+                Interaction::Clicked(Element::CloseButton) => rectangle::State::Clicked,
+                _ => rectangle::State::Normal,
+            };
+            rectangle::draw_with_centered_label(
+                win_w, win_h, graphics, self.uic, close_state,
+                [pos[0] + dim[0] - TITLE_BAR_HEIGHT, pos[1]], [TITLE_BAR_HEIGHT, TITLE_BAR_HEIGHT],
+                maybe_frame, color.clicked(), "x", t_size, t_color
+            );
+        }
+
+        // Contents.
+        {
+            let ui_id = self.ui_id;
+            let WindowContext { // borrowck
+                ref mut uic,
+                ref mut maybe_content,
+                ..
+            } = *self;
+            match *maybe_content {
+                Some(ref mut content) => {
+                    let content_pos = [pos[0] + frame_w, pos[1] + TITLE_BAR_HEIGHT];
+                    let content_dim = [dim[0] - frame_w * 2.0, dim[1] - TITLE_BAR_HEIGHT - frame_w];
+                    uic.begin_children(ui_id);
+                    (*content)(&mut **uic, graphics, content_pos, content_dim);
+                    uic.end_children();
+                },
+                None => (),
+            }
+        }
+
+        let new_state = State { interaction: new_interaction, maybe_pos: maybe_pos };
+        set_state(self.uic, self.ui_id, new_state, pos, dim);
+
+    }
+}
@@ -7,13 +7,14 @@ use graphics::{
 };
 use label;
 use label::FontSize;
-use mouse::Mouse;
+use mouse::{FINE_DRAG_SCALE, Mouse};
 use opengl_graphics::Gl;
 use point::Point;
 use rectangle;
 use rectangle::{
     Corner
 };
+use touch::View;
 use ui_context::{
     UIID,
     UiContext,
@@ -101,6 +102,11 @@ pub struct XYPadContext<'a, X, Y> {
     maybe_label: Option<&'a str>,
     maybe_label_color: Option<Color>,
     maybe_label_font_size: Option<u32>,
+    maybe_label_min_font_size: Option<u32>,
+    maybe_view: Option<&'a mut View>,
+    maybe_default: Option<(X, Y)>,
+    pressure_range: (f64, f64),
+    maybe_pressure_callback: Option<|f64|:'a>,
 }
 
 impl <'a, X, Y> XYPadContext<'a, X, Y> {
@@ -112,6 +118,28 @@ impl <'a, X, Y> XYPadContext<'a, X, Y> {
     pub fn value_font_size(self, size: FontSize) -> XYPadContext<'a, X, Y> {
         XYPadContext { font_size: size, ..self }
     }
+    /// Only show the region of the value ranges within the given View, which is zoomed
+    /// and panned with two finger gestures over the pad.
+    #[inline]
+    pub fn view(self, view: &'a mut View) -> XYPadContext<'a, X, Y> {
+        XYPadContext { maybe_view: Some(view), ..self }
+    }
+    /// The values to which the pad is reset when Ctrl-clicked.
+    #[inline]
+    pub fn default_values(self, x: X, y: Y) -> XYPadContext<'a, X, Y> {
+        XYPadContext { maybe_default: Some((x, y)), ..self }
+    }
+    /// The range to which a pen's pressure is mapped for the `pressure_callback`.
+    #[inline]
+    pub fn pressure_range(self, min: f64, max: f64) -> XYPadContext<'a, X, Y> {
+        XYPadContext { pressure_range: (min, max), ..self }
+    }
+    /// Call the given closure with the pressure of the pen (mapped to the `pressure_range`)
+    /// whenever it changes while the pad is pressed, as a third value alongside x and y.
+    #[inline]
+    pub fn pressure_callback(self, callback: |f64|:'a) -> XYPadContext<'a, X, Y> {
+        XYPadContext { maybe_pressure_callback: Some(callback), ..self }
+    }
 }
 
 pub trait XYPadBuilder<'a, X: Float + Copy + ToPrimitive + FromPrimitive + ToString,
@@ -129,6 +157,7 @@ XYPadBuilder<'a, X, Y> for UiContext {
     fn xy_pad(&'a mut self, ui_id: UIID,
               x_val: X, min_x: X, max_x: X,
               y_val: Y, min_y: Y, max_y: Y) -> XYPadContext<'a, X, Y> {
+        let pos = self.flow_pos();
         XYPadContext {
             uic: self,
             ui_id: ui_id,
@@ -136,7 +165,7 @@ XYPadBuilder<'a, X, Y> for UiContext {
             y: y_val, min_y: min_y, max_y: max_y,
             line_width: 1.0,
             font_size: 18u32,
-            pos: [0.0, 0.0],
+            pos: pos,
             dim: [128.0, 128.0],
             maybe_callback: None,
             maybe_color: None,
@@ -145,6 +174,11 @@ XYPadBuilder<'a, X, Y> for UiContext {
             maybe_label: None,
             maybe_label_color: None,
             maybe_label_font_size: None,
+            maybe_label_min_font_size: None,
+            maybe_view: None,
+            maybe_default: None,
+            pressure_range: (0.0, 1.0),
+            maybe_pressure_callback: None,
         }
     }
 }
@@ -152,6 +186,7 @@ XYPadBuilder<'a, X, Y> for UiContext {
 impl_callable!(XYPadContext, |X, Y|:'a, X, Y);
 impl_colorable!(XYPadContext, X, Y);
 impl_frameable!(XYPadContext, X, Y);
+impl_hoverable!(XYPadContext, X, Y);
 impl_labelable!(XYPadContext, X, Y);
 impl_positionable!(XYPadContext, X, Y);
 impl_shapeable!(XYPadContext, X, Y);
@@ -163,7 +198,7 @@ impl<'a, X: Float + Copy + ToPrimitive + FromPrimitive + ToString,
 
         // Init.
         let state = *get_state(self.uic, self.ui_id);
-        let mouse = self.uic.get_mouse_state();
+        let mouse = self.uic.get_mouse_state_for(self.ui_id);
         let frame_w = self.maybe_frame.unwrap_or(self.uic.theme.frame_width);
         let frame_w2 = frame_w * 2.0;
         let maybe_frame = match frame_w > 0.0 {
@@ -173,11 +208,69 @@ impl<'a, X: Float + Copy + ToPrimitive + FromPrimitive + ToString,
         let pad_dim = vec2_sub(self.dim, [frame_w2, ..2]);
         let pad_pos = vec2_add(self.pos, [frame_w, frame_w]);
         let is_over_pad = rectangle::is_over(pad_pos, mouse.pos, pad_dim);
-        let new_state = get_new_state(is_over_pad, state, mouse);
 
-        // Determine new values.
+        // Zoom and pan the visible ranges with two finger gestures over the pad, during
+        // which the pad isn't pressed.
+        let maybe_gesture = match self.uic.get_gesture() {
+            Some(gesture) if rectangle::is_over(pad_pos, gesture.center, pad_dim) => Some(gesture),
+            _ => None,
+        };
+        if let Some(ref mut view) = self.maybe_view {
+            if let Some(gesture) = maybe_gesture { view.apply(gesture, pad_dim) }
+            // The greatest values are shown at the left and top of the pad.
+            let (x_start, x_end) = view.visible(0u);
+            let (y_start, y_end) = view.visible(1u);
+            let (min_x, max_x) = (self.min_x, self.max_x);
+            let (min_y, max_y) = (self.min_y, self.max_y);
+            self.max_x = map_range(x_start, 0.0, 1.0, max_x, min_x);
+            self.min_x = map_range(x_end, 0.0, 1.0, max_x, min_x);
+            self.max_y = map_range(y_start, 0.0, 1.0, max_y, min_y);
+            self.min_y = map_range(y_end, 0.0, 1.0, max_y, min_y);
+        }
+        let new_state = match maybe_gesture.is_some() && self.maybe_view.is_some() {
+            true => State::Normal,
+            false => get_new_state(is_over_pad, state, mouse),
+        };
+        // Keep the mouse while dragging, so the drag continues wherever the mouse goes.
+        match new_state {
+            State::Clicked => self.uic.capture_mouse(self.ui_id),
+            _ => self.uic.uncapture_mouse(self.ui_id),
+        }
+
+        // Determine new values. Ctrl-clicking resets the default values (if any) on the
+        // press alone, so that holding Ctrl during a drag doesn't pin the values, while
+        // holding Shift slows the drag for fine adjustment.
+        let modifiers = self.uic.get_modifiers();
+        let is_reset = match (state, new_state) {
+            (State::Highlighted, State::Clicked) => modifiers.ctrl && self.maybe_default.is_some(),
+            _ => false,
+        };
+        let is_adjusted = match (state, new_state) {
+            (State::Clicked, State::Clicked) => modifiers.shift,
+            _ => is_reset,
+        };
+        // The values only follow the mouse after a press once it has become a drag.
+        let is_held = match (state, new_state) {
+            (State::Clicked, State::Clicked) => !self.uic.is_past_drag_threshold(),
+            _ => false,
+        };
         let (new_x, new_y) = match (is_over_pad, new_state) {
             (_, State::Normal) | (_, State::Highlighted) => (self.x, self.y),
+            (_, State::Clicked) if is_held && !is_adjusted => (self.x, self.y),
+            (_, State::Clicked) if is_adjusted => match self.maybe_default {
+                Some(default) if is_reset => default,
+                _ => {
+                    let delta = vec2_sub(mouse.pos, mouse.prev_pos);
+                    let temp_x = map_range(self.x, self.min_x, self.max_x, pad_dim[0], 0.0)
+                        + delta[0] * FINE_DRAG_SCALE;
+                    let temp_y = map_range(self.y, self.min_y, self.max_y, pad_dim[1], 0.0)
+                        + delta[1] * FINE_DRAG_SCALE;
+                    let (temp_x, temp_y) = (clamp(temp_x, 0.0, pad_dim[0]),
+                                            clamp(temp_y, 0.0, pad_dim[1]));
+                    (map_range(temp_x, pad_dim[0], 0.0, self.min_x, self.max_x),
+                     map_range(temp_y, pad_dim[1], 0.0, self.min_y, self.max_y))
+                },
+            },
             (_, State::Clicked) => {
                 let temp_x = clamp(mouse.pos[0], pad_pos[0], pad_pos[0] + pad_dim[0]);
                 let temp_y = clamp(mouse.pos[1], pad_pos[1], pad_pos[1] + pad_dim[1]);
@@ -200,6 +293,17 @@ impl<'a, X: Float + Copy + ToPrimitive + FromPrimitive + ToString,
             },
             None => (),
         }
+        if let Some(ref mut callback) = self.maybe_pressure_callback {
+            let is_changed = match (state, new_state) {
+                (State::Clicked, State::Clicked) => mouse.pressure != mouse.prev_pressure,
+                (_, State::Clicked) => true,
+                _ => false,
+            };
+            if is_changed {
+                let (min, max) = self.pressure_range;
+                (*callback)(map_range(mouse.pressure, 0.0, 1.0, min, max))
+            }
+        }
 
         // Draw.
         let rect_state = new_state.as_rectangle_state();
@@ -207,12 +311,12 @@ impl<'a, X: Float + Copy + ToPrimitive + FromPrimitive + ToString,
         rectangle::draw(self.uic.win_w, self.uic.win_h, graphics, rect_state, self.pos,
                         self.dim, maybe_frame, color);
         let (vert_x, hori_y) = match (is_over_pad, new_state) {
-            (_, State::Normal) | (_, State::Highlighted) =>
-                (pad_pos[0] + map_range(new_x, self.min_x, self.max_x, pad_dim[0], 0.0),
-                 pad_pos[1] + map_range(new_y, self.min_y, self.max_y, pad_dim[1], 0.0)),
-            (_, State::Clicked) =>
+            (_, State::Clicked) if !is_adjusted && !is_held =>
                 (clamp(mouse.pos[0], pad_pos[0], pad_pos[0] + pad_dim[0]),
                  clamp(mouse.pos[1], pad_pos[1], pad_pos[1] + pad_dim[1])),
+            _ =>
+                (pad_pos[0] + map_range(new_x, self.min_x, self.max_x, pad_dim[0], 0.0),
+                 pad_pos[1] + map_range(new_y, self.min_y, self.max_y, pad_dim[1], 0.0)),
         };
         // Crosshair.
         draw_crosshair(self.uic.win_w, self.uic.win_h, graphics, pad_pos, self.line_width,
@@ -221,6 +325,8 @@ impl<'a, X: Float + Copy + ToPrimitive + FromPrimitive + ToString,
         if let Some(l_text) = self.maybe_label {
             let l_color = self.maybe_label_color.unwrap_or(self.uic.theme.label_color);
             let l_size = self.maybe_label_font_size.unwrap_or(self.uic.theme.font_size_medium);
+            let l_size = label::shrink_to_fit(self.uic, l_text, l_size,
+                                              self.maybe_label_min_font_size, pad_dim[0]);
             let l_w = label::width(self.uic, l_size, l_text);
             let l_x = pad_pos[0] + (pad_dim[0] - l_w) / 2.0;
             let l_y = pad_pos[1] + (pad_dim[1] - l_size as f64) / 2.0;
@@ -0,0 +1,130 @@
+//! Drives a UiContext through the injection API alone. No window or GL context is
+//! created, so these run headlessly: they assert on the input and layout that widgets
+//! read during each frame rather than on anything drawn.
+
+extern crate conrod;
+extern crate input;
+extern crate opengl_graphics;
+
+use conrod::{
+    Shortcut,
+    Theme,
+    UiContext,
+};
+use conrod::layout::{Attr, Relation, WINDOW};
+use conrod::mouse::ButtonState;
+use input::MouseButton;
+use input::keyboard::Key;
+use opengl_graphics::glyph_cache::GlyphCache;
+
+static WIN_W: f64 = 320.0;
+static WIN_H: f64 = 240.0;
+
+/// Construct a UiContext. The glyph cache only loads the font, as glyphs are rendered
+/// when first drawn.
+fn ui_context() -> UiContext {
+    let font = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/Dense-Regular.otf");
+    let glyph_cache = GlyphCache::new(&font).unwrap();
+    UiContext::new(glyph_cache, Theme::default())
+}
+
+fn is_down(state: ButtonState) -> bool {
+    match state { ButtonState::Down => true, ButtonState::Up => false }
+}
+
+#[test]
+fn injected_mouse_input_is_received_during_the_next_frame() {
+    let mut uic = ui_context();
+    uic.inject_mouse_move([10.0, 20.0]);
+    uic.inject_mouse_press(MouseButton::Left);
+    assert!(!is_down(uic.get_mouse_state().left));
+    uic.inject_render(WIN_W, WIN_H);
+    let mouse = uic.get_mouse_state();
+    assert_eq!(mouse.pos, [10.0, 20.0]);
+    assert!(is_down(mouse.left));
+    assert!(!uic.is_past_drag_threshold());
+
+    // Moving beyond the drag threshold while held turns the press into a drag.
+    uic.inject_mouse_move([40.0, 20.0]);
+    uic.inject_render(WIN_W, WIN_H);
+    assert!(uic.is_past_drag_threshold());
+    assert_eq!(uic.get_mouse_state().prev_pos, [10.0, 20.0]);
+
+    uic.inject_mouse_release(MouseButton::Left);
+    uic.inject_render(WIN_W, WIN_H);
+    assert!(!is_down(uic.get_mouse_state().left));
+}
+
+#[test]
+fn injected_scroll_lasts_for_a_single_frame() {
+    let mut uic = ui_context();
+    uic.inject_mouse_scroll(0.0, 2.0);
+    uic.inject_mouse_scroll(1.0, 1.0);
+    uic.inject_render(WIN_W, WIN_H);
+    assert_eq!(uic.get_scroll(), [1.0, 3.0]);
+    uic.inject_render(WIN_W, WIN_H);
+    assert_eq!(uic.get_scroll(), [0.0, 0.0]);
+}
+
+#[test]
+fn a_second_injected_click_is_a_double_click() {
+    let mut uic = ui_context();
+    uic.inject_mouse_move([50.0, 50.0]);
+    uic.inject_mouse_press(MouseButton::Left);
+    uic.inject_mouse_release(MouseButton::Left);
+    uic.inject_render(WIN_W, WIN_H);
+    assert!(!uic.get_mouse_state().is_double_click);
+    uic.inject_mouse_press(MouseButton::Left);
+    uic.inject_render(WIN_W, WIN_H);
+    assert!(uic.get_mouse_state().is_double_click);
+}
+
+#[test]
+fn injected_keys_and_text_are_received_during_the_next_frame() {
+    let mut uic = ui_context();
+    uic.inject_text("日本".to_string());
+    uic.inject_key(Key::Backspace);
+    assert!(uic.get_entered_text().is_empty());
+    uic.inject_render(WIN_W, WIN_H);
+    assert_eq!(uic.get_entered_text(), vec!["日本".to_string()]);
+    assert_eq!(uic.get_pressed_keys(), vec![Key::Backspace]);
+    uic.inject_render(WIN_W, WIN_H);
+    assert!(uic.get_entered_text().is_empty());
+    assert!(uic.get_pressed_keys().is_empty());
+}
+
+#[test]
+fn injected_modifiers_are_held_until_released() {
+    let mut uic = ui_context();
+    uic.register_shortcut(Shortcut::new(Key::S).ctrl(), 0u);
+    uic.inject_key_press(Key::LCtrl);
+    uic.inject_key(Key::S);
+    uic.inject_render(WIN_W, WIN_H);
+    assert!(uic.get_modifiers().ctrl);
+    assert!(uic.shortcut_pressed(0u));
+
+    uic.inject_key_release(Key::LCtrl);
+    uic.inject_key(Key::S);
+    uic.inject_render(WIN_W, WIN_H);
+    assert!(!uic.get_modifiers().ctrl);
+    assert!(!uic.shortcut_pressed(0u));
+}
+
+#[test]
+fn the_constraint_layout_is_solved_against_each_frames_window() {
+    let mut uic = ui_context();
+    let panel = {
+        let layout = uic.constraint_layout();
+        let panel = layout.add_rect([0.0, 0.0], [100.0, 50.0]);
+        layout.constrain((panel, Attr::Right), Relation::Equal, (WINDOW, Attr::Right), -10.0);
+        panel
+    };
+    uic.inject_render(WIN_W, WIN_H);
+    let (pos, dim) = uic.get_layout_rect(panel);
+    assert!((pos[0] + dim[0] - (WIN_W - 10.0)).abs() < 0.1);
+    assert!(uic.is_layout_satisfied());
+
+    uic.inject_render(WIN_W * 2.0, WIN_H);
+    let (pos, dim) = uic.get_layout_rect(panel);
+    assert!((pos[0] + dim[0] - (WIN_W * 2.0 - 10.0)).abs() < 0.1);
+}